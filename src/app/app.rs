@@ -1,19 +1,34 @@
-use crate::core::sim::SimContext;
+use crate::core::elements::{CellId, Uid};
+use crate::core::features::{CellType, ThemeConfig};
+use crate::core::genes::Gene;
+use crate::core::sim::{ConnectError, SimContext, SimulationState, SpawnError};
+use crate::graphics::background::BackgroundTile;
 use crate::graphics::border::BorderTile;
-use crate::graphics::layers::SimulationTile;
+use crate::graphics::models::cpu::Color;
+use crate::graphics::handles::ConnectionHandleTile;
+use crate::graphics::layers::{RenderBackend, SharedTileView, SimulationTile};
+use crate::graphics::renderer::TileEvent;
+use crate::graphics::text::TextTile;
+use crate::graphics::vectors::VectorFieldTile;
 use crate::testing::benches;
-use crate::app::components::Simulation;
+use crate::app::components::{CsvLog, FrameStats, SimRunner, Simulation, TitleStats, UndoStack};
 use crate::gpu;
+use crate::gpu::shaders::{ShaderWatcher, SHADER_DIR};
+use crate::utils::vector::Vec2d;
 use super::utils;
 
 use super::tile::TileViewManager;
 
 use glam::{vec2, Vec2};
+use std::collections::HashSet;
 use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
 use taffy::{Dimension, Size, Style};
 use winit::{
     application::ApplicationHandler,
-    event::WindowEvent,
+    event::{ElementState, KeyEvent, MouseButton, MouseScrollDelta, WindowEvent},
+    keyboard::{Key, KeyCode, ModifiersState, PhysicalKey},
     event_loop::ActiveEventLoop,
     window::{Window, WindowId},
 };
@@ -23,19 +38,124 @@ pub struct App {
     gpu_context: Option<gpu::context::GpuContext>,
     tile_manager: TileViewManager,
     primary_simulation: Simulation,
+    frame_stats: Arc<Mutex<FrameStats>>,
+
+    /// Live stats shown in the window title, toggled with `t`.
+    title_stats: TitleStats,
+
+    /// Maximum redraw rate; `None` runs uncapped.
+    fps_cap: Option<f32>,
+
+    /// Periodic CSV export of the primary simulation, if enabled.
+    /// Handed to the simulation thread when it starts.
+    csv_log: Option<CsvLog>,
+
+    /// Ticks the primary simulation on its own thread once the window is up.
+    sim_runner: Option<SimRunner>,
+
+    /// Physical keys currently held down, for continuous controls.
+    held_keys: HashSet<KeyCode>,
+
+    /// Modifier keys currently held down.
+    modifiers: ModifiersState,
+
+    /// Cursor position in simulation tile pixels, or `None` if it is off the tile.
+    cursor: Option<Vec2>,
+
+    /// View the simulation tile is drawn with, for finding the cell under the cursor.
+    sim_view: SharedTileView,
+
+    /// Cell marked with `j`, whose organism is merged with the next one `j` is pressed over.
+    merge_mark: Option<Uid>,
+
+    /// Per-type color and shape overrides for drawing cells.
+    theme: ThemeConfig,
+
+    /// How the simulation tile draws cells.
+    render_backend: RenderBackend,
+
+    /// Whether the debug overlays are shown, toggled with `v` and kept when shaders are reloaded.
+    debug_visible: bool,
+
+    /// Snapshots of the primary simulation taken before each edit, for Ctrl+Z.
+    /// Shared with the connection handles, which record drags as edits.
+    undo_stack: Arc<Mutex<UndoStack>>,
+
+    /// Whether the window has zero size, pausing updates and rendering.
+    minimized: bool,
+
+    /// Watches shader sources for hot-reloading; only set in debug builds.
+    shader_watcher: Option<ShaderWatcher>,
 }
 
 impl App {
-    /// Target frames per second.
-    const TARGET_FPS: f32 = 60.0;
+    /// Window title, followed by live stats while they are enabled.
+    const TITLE: &str = "Cellular Evolution";
+
+    /// Simulation ticks per second.
+    const TICK_RATE: f64 = 60.0;
+
+    /// MSAA samples per pixel requested from the GPU context.
+    const MSAA_SAMPLES: u32 = 4;
+
+    /// Keyboard pan speed, in view half-extents per second.
+    const PAN_SPEED: f32 = 1.0;
+
+    /// Keyboard rotation speed, in radians per second.
+    const ROTATE_SPEED: f32 = 1.5;
+
+    /// Zoom factor per line scrolled with the mouse wheel.
+    const ZOOM_STEP: f32 = 1.1;
+
+    /// Pixels of touchpad scrolling counted as one wheel line.
+    const PIXELS_PER_LINE: f32 = 40.0;
+
+    /// Change in linear viscosity per press of `[` or `]`; angular viscosity follows in proportion.
+    const VISCOSITY_STEP: f64 = 2.5;
+
+    /// Initial linear viscosity of the primary simulation.
+    const VISCOSITY: f64 = 25.0;
+
+    /// Physics sub-steps per tick, keeping stiff springs from gaining energy at the tick rate.
+    const SPRING_ITERATIONS: usize = 4;
+
+    /// Force per unit of overlap pushing apart unconnected cells in the primary simulation.
+    const COLLISION_STIFFNESS: f64 = 100.0;
+
+    /// Most live cells the primary simulation may grow to.
+    const MAX_CELLS: usize = 5000;
+
+    /// Deepest stems grown by `set_gene`.
+    const GENE_DEPTH: usize = 8;
+
+    /// Distance between a cell grown by `set_gene` and its parent, in world units.
+    const GENE_SPACING: f64 = 2.0;
+
+    /// Fill color of the simulation tile, set apart from the black window clear.
+    const SIM_BACKGROUND: Color = Color::from_hex(0x10121CFF);
+
+    /// Supersampling factor of screenshots saved with `p`.
+    const SCREENSHOT_SCALE: u32 = 4;
+
+    /// Time before a frame deadline at which the limiter stops sleeping and spins instead.
+    const SPIN_MARGIN: Duration = Duration::from_millis(1);
 
     /// Creates a new instance of the application with default simulation and tile layout.
     pub fn new() -> Self {
         let mut tile_manager = TileViewManager::new();
 
-        // Initialize simulation state with custom viscosity.
-        let sim_context = SimContext { viscosity: 25.0 };
-        let initial_state = Arc::new(Mutex::new(benches::organism_lookn_cells(sim_context)));
+        // Initialize simulation state with colliding cells and a bounded population,
+        // resetting any cell that blows up rather than losing the organism to it.
+        let sim_context = SimContext::builder()
+            .viscosity(Self::VISCOSITY, Self::VISCOSITY * SimContext::ANGULAR_VISCOSITY_RATIO)
+            .spring_iterations(Self::SPRING_ITERATIONS)
+            .collision_stiffness(Self::COLLISION_STIFFNESS)
+            .max_cells(Self::MAX_CELLS)
+            .quarantine(true)
+            .build();
+        let mut initial_state = benches::organism_lookn_cells(sim_context);
+        initial_state.save_initial();
+        let initial_state = Arc::new(Mutex::new(initial_state));
 
         // Define UI style for the main simulation tile.
         let style = Style {
@@ -56,6 +176,73 @@ impl App {
                 state: initial_state,
                 tile: Some(sim_tile_node),
             },
+            frame_stats: Arc::new(Mutex::new(FrameStats::new())),
+            // Title updates are off in test builds so they don't disturb benchmarks.
+            title_stats: TitleStats::new(!cfg!(feature = "test"), TitleStats::DEFAULT_INTERVAL),
+            fps_cap: None,
+            csv_log: None,
+            sim_runner: None,
+            held_keys: HashSet::new(),
+            modifiers: ModifiersState::empty(),
+            cursor: None,
+            sim_view: SharedTileView::default(),
+            merge_mark: None,
+            theme: ThemeConfig::default(),
+            render_backend: RenderBackend::default(),
+            debug_visible: false,
+            undo_stack: Arc::new(Mutex::new(UndoStack::new(UndoStack::DEFAULT_DEPTH))),
+            minimized: false,
+            shader_watcher: None,
+        }
+    }
+
+    /// Replaces the primary simulation with an organism grown from `gene` at the origin,
+    /// keeping its context. Fails if the organism would exceed `MAX_CELLS`.
+    pub fn set_gene(&mut self, gene: &Gene) -> Result<(), SpawnError> {
+        let mut state = self.primary_simulation.state.lock().unwrap();
+        let mut grown = SimulationState::new(state.context.clone());
+        grown.grow_from_gene(Vec2d::ZERO, gene, Self::GENE_DEPTH, Self::GENE_SPACING)?;
+        grown.save_initial();
+        *state = grown;
+        Ok(())
+    }
+
+    /// Enables periodic CSV export of the primary simulation.
+    pub fn set_csv_log(&mut self, log: CsvLog) {
+        self.csv_log = Some(log);
+    }
+
+    /// Overrides how cell types are drawn. Takes effect when the renderers are attached.
+    pub fn set_theme(&mut self, theme: ThemeConfig) {
+        self.theme = theme;
+    }
+
+    /// Sets how the simulation tile draws cells. Takes effect when the renderers are attached.
+    pub fn set_render_backend(&mut self, backend: RenderBackend) {
+        self.render_backend = backend;
+    }
+
+    /// Caps the redraw rate at `cap` frames per second, or removes the cap with `None`.
+    /// This is independent of the surface's vsync setting.
+    pub fn set_fps_cap(&mut self, cap: Option<f32>) {
+        self.fps_cap = cap.filter(|fps| *fps > 0.0);
+    }
+
+    /// Waits out the remainder of a frame that began at `frame_start` to honor the FPS cap.
+    ///
+    /// Sleeps coarsely until shortly before the deadline, then spins for precision.
+    fn pace_frame(&self, frame_start: Instant) {
+        let Some(cap) = self.fps_cap else {
+            return;
+        };
+        let deadline = frame_start + Duration::from_secs_f32(1.0 / cap);
+
+        let remaining = deadline.saturating_duration_since(Instant::now());
+        if remaining > Self::SPIN_MARGIN {
+            thread::sleep(remaining - Self::SPIN_MARGIN);
+        }
+        while Instant::now() < deadline {
+            std::hint::spin_loop();
         }
     }
 
@@ -64,7 +251,7 @@ impl App {
         let icon = utils::load_icon("assets/icon1.png");
 
         let window_attrs = Window::default_attributes()
-            .with_title("Cellular Evolution")
+            .with_title(Self::TITLE)
             .with_window_icon(Some(icon));
 
         let window = Arc::new(
@@ -73,44 +260,154 @@ impl App {
                 .expect("Failed to create window"),
         );
 
-        let gpu_context = pollster::block_on(gpu::context::GpuContext::new(window.clone()));
+        // Run uncapped in test builds so throughput can be measured.
+        let present_mode = if cfg!(feature = "test") {
+            wgpu::PresentMode::AutoNoVsync
+        } else {
+            wgpu::PresentMode::AutoVsync
+        };
+
+        let gpu_context = pollster::block_on(gpu::context::GpuContext::new(window.clone(), present_mode, Self::MSAA_SAMPLES));
 
         self.tile_manager.resize(vec2(
             gpu_context.size.width as f32,
             gpu_context.size.height as f32,
         ));
 
-        // Attach renderers to the simulation tile.
+        self.gpu_context = Some(gpu_context);
+        self.attach_renderers();
+
+        self.sim_runner.get_or_insert_with(|| {
+            SimRunner::spawn(self.primary_simulation.state.clone(), 1.0 / Self::TICK_RATE, self.csv_log.take())
+        });
+
+        // Rebuild pipelines when shader sources change during development.
+        if cfg!(debug_assertions) {
+            self.shader_watcher = ShaderWatcher::new(SHADER_DIR)
+                .inspect_err(|err| eprintln!("Shader hot-reload disabled: {err}"))
+                .ok();
+        }
+
+        window.request_redraw();
+    }
+
+    /// Attaches the background, simulation, debug, handle, border, and text renderers to the simulation tile.
+    fn attach_renderers(&mut self) {
+        let Some(gpu_context) = &self.gpu_context else {
+            return;
+        };
+
         if let Some(sim_tile_node) = self.primary_simulation.tile {
+            // The background goes first so every other layer draws over it.
+            self.tile_manager.add_renderer(
+                sim_tile_node,
+                BackgroundTile::new(gpu_context, Self::SIM_BACKGROUND),
+                &gpu_context.queue,
+            );
+            let mut simulation_tile = SimulationTile::new(vec2(15.0, 10.0), 1.0, Vec2::ZERO, gpu_context);
+            simulation_tile.set_theme(self.theme.clone());
+            simulation_tile.set_backend(self.render_backend);
+            simulation_tile.set_camera_relative(true);
+            // Overlays draw through the simulation tile's view so they line up with its cells.
+            let view = simulation_tile.shared_view();
+            self.sim_view = view.clone();
+            self.tile_manager.add_renderer(sim_tile_node, simulation_tile, &gpu_context.queue);
+            // Velocity and force arrows, shown while the debug overlays are toggled on.
+            let mut vector_tile = VectorFieldTile::new(view.clone(), 0.5, 0.02, gpu_context);
+            vector_tile.set_visible(self.debug_visible);
+            self.tile_manager.add_renderer(sim_tile_node, vector_tile, &gpu_context.queue);
+            // Draggable connection angle handles, shown along with the arrows.
+            let mut handle_tile = ConnectionHandleTile::new(
+                view,
+                self.primary_simulation.state.clone(),
+                self.undo_stack.clone(),
+                gpu_context,
+            );
+            handle_tile.set_visible(self.debug_visible);
+            self.tile_manager.add_renderer(sim_tile_node, handle_tile, &gpu_context.queue);
             self.tile_manager.add_renderer(
                 sim_tile_node,
-                SimulationTile::new(vec2(15.0, 10.0), &gpu_context),
+                BorderTile::new(gpu_context),
                 &gpu_context.queue,
             );
+
+            // Overlay live stats in the top-left of the simulation tile.
+            let frame_stats = self.frame_stats.clone();
             self.tile_manager.add_renderer(
                 sim_tile_node,
-                BorderTile::new(&gpu_context),
+                TextTile::new(gpu_context, Box::new(move |state| {
+                    let stats = frame_stats.lock().unwrap();
+                    format!(
+                        "TICK: {}\nTIME: {:.1}\nCELLS: {}/{}\nFREE: {}\nFPS: {:.0}\nVISC: {:.1}\nKE: {:.1}\nPE: {:.1}",
+                        state.tick_count(),
+                        state.sim_time(),
+                        state.cells.len(),
+                        state.cells.capacity(),
+                        state.cells.free_count(),
+                        stats.fps,
+                        state.viscosity(),
+                        state.total_kinetic_energy(),
+                        state.total_potential_energy(),
+                    )
+                })),
                 &gpu_context.queue,
             );
         }
+    }
 
-        self.gpu_context = Some(gpu_context);
-        window.request_redraw();
+    /// Recreates all renderers, recompiling their shaders from disk.
+    ///
+    /// If the new shaders fail validation, the error is printed and the previous
+    /// renderers are kept. Camera state of the simulation tile is reset on success.
+    fn reload_shaders(&mut self) {
+        let (Some(gpu_context), Some(sim_tile_node)) = (&mut self.gpu_context, self.primary_simulation.tile) else {
+            return;
+        };
+
+        let old_layers = self.tile_manager.take_renderers(sim_tile_node);
+        let old_resources = gpu_context.simulation_resources.take();
+        let old_view = self.sim_view.clone();
+
+        gpu_context.device.push_error_scope(wgpu::ErrorFilter::Validation);
+        self.attach_renderers();
+
+        let gpu_context = self.gpu_context.as_mut().expect("GPU context checked above");
+        match pollster::block_on(gpu_context.device.pop_error_scope()) {
+            None => println!("Reloaded shaders."),
+            Some(err) => {
+                eprintln!("Shader reload failed, keeping previous shaders: {err}");
+                self.tile_manager.set_renderers(sim_tile_node, old_layers);
+                self.sim_view = old_view;
+                gpu_context.simulation_resources = Default::default();
+                if let Some(resources) = old_resources {
+                    let _ = gpu_context.simulation_resources.set(resources);
+                }
+            }
+        }
     }
 
-    /// Updates the simulation and renders all tiles to the screen.
+    /// Renders all tiles to the screen from the latest simulation state.
     fn update_and_render(&mut self) {
-        // Advance the simulation.
-        self.primary_simulation
-            .state
-            .lock()
-            .unwrap()
-            .tick((1.0 / Self::TARGET_FPS) as f64);
+        let dt = self.frame_stats.lock().unwrap().record_frame();
+        self.move_camera(dt);
+
+        if self.shader_watcher.as_ref().is_some_and(ShaderWatcher::take_changed) {
+            self.reload_shaders();
+        }
 
         // If GPU is available, load data and render.
         if let Some(gpu_context) = &mut self.gpu_context {
-            self.tile_manager
-                .load_all(self.primary_simulation.state.clone(), &gpu_context.queue);
+            // Resizes and camera transitions don't wait for the simulation.
+            self.tile_manager.update_views(&gpu_context.queue);
+
+            // Keep drawing the previous data rather than stalling while the simulation thread ticks.
+            if let Ok(state) = self.primary_simulation.state.try_lock() {
+                // Smooth motion between ticks when frames come faster than the tick rate.
+                if let (Some(runner), Some(sim_tile_node)) = (&self.sim_runner, self.primary_simulation.tile) {
+                    self.tile_manager.dispatch_event(sim_tile_node, TileEvent::InterpolationAlpha(runner.alpha()));
+                }
+                self.tile_manager.update_render_data(&state, gpu_context);
+            }
 
             let mut frame = gpu_context.start_frame();
             {
@@ -119,18 +416,296 @@ impl App {
             }
             gpu_context.end_frame(frame);
 
+            if self.title_stats.enabled
+                && let Some(fps) = self.title_stats.record_frame(Instant::now())
+            {
+                Self::update_title(gpu_context, &self.primary_simulation.state, fps);
+            }
+
             gpu_context.get_window().request_redraw();
         }
     }
 
+    /// Shows the frame rate, tick count, live cell count, and viscosity in the window title.
+    /// Skipped while the simulation thread holds the state, leaving the previous title.
+    fn update_title(gpu_context: &gpu::context::GpuContext, state: &Mutex<SimulationState>, fps: f32) {
+        if let Ok(state) = state.try_lock() {
+            gpu_context.get_window().set_title(&format!(
+                "{} | {:.0} FPS | tick {} | {} cells | viscosity {:.1}",
+                Self::TITLE,
+                fps,
+                state.tick_count(),
+                state.cells.len(),
+                state.viscosity(),
+            ));
+        }
+    }
+
+    /// Enables or disables live stats in the window title, restoring the plain title when disabled.
+    pub fn set_title_stats(&mut self, enabled: bool) {
+        self.title_stats = TitleStats::new(enabled, TitleStats::DEFAULT_INTERVAL);
+        if !enabled && let Some(gpu_context) = &self.gpu_context {
+            gpu_context.get_window().set_title(Self::TITLE);
+        }
+    }
+
     /// Handles window resizing and updates the GPU and tile layout accordingly.
+    /// A zero size marks the window as minimized until a non-zero size arrives.
     fn handle_resize(&mut self, new_size: winit::dpi::PhysicalSize<u32>) {
+        let was_minimized = self.minimized;
+        self.minimized = new_size.width == 0 || new_size.height == 0;
+        if self.minimized {
+            return;
+        }
+
         if let Some(gpu_context) = &mut self.gpu_context {
             gpu_context.resize(new_size);
             self.tile_manager.resize(vec2(
                 gpu_context.size.width as f32,
                 gpu_context.size.height as f32,
             ));
+
+            // Rendering stopped requesting redraws while minimized, so restart the loop.
+            if was_minimized {
+                gpu_context.get_window().request_redraw();
+            }
+        }
+    }
+
+    /// Pans the primary simulation's camera with WASD and rotates it with Q/E while held.
+    fn move_camera(&mut self, dt: f32) {
+        let axis = |positive: KeyCode, negative: KeyCode| {
+            self.held_keys.contains(&positive) as i32 as f32 - self.held_keys.contains(&negative) as i32 as f32
+        };
+        let pan = vec2(axis(KeyCode::KeyD, KeyCode::KeyA), axis(KeyCode::KeyW, KeyCode::KeyS));
+        let rotate = axis(KeyCode::KeyQ, KeyCode::KeyE);
+
+        if pan == Vec2::ZERO && rotate == 0.0 {
+            return;
+        }
+
+        if let Some(tile) = self.primary_simulation.tile {
+            self.tile_manager.dispatch_event(
+                tile,
+                TileEvent::MoveCamera {
+                    pan: pan * Self::PAN_SPEED * dt,
+                    rotate: rotate * Self::ROTATE_SPEED * dt,
+                },
+            );
+        }
+    }
+
+    /// Forwards the cursor position, in window pixels, to the simulation tile
+    /// in tile-local pixels, or `None` once it is off the tile.
+    ///
+    /// Positions are measured from the clipped tile's corner, matching the clipped size
+    /// the tile's layers are resized to and the viewport they are drawn in.
+    fn handle_cursor(&mut self, position: Option<Vec2>) {
+        let Some(tile) = self.primary_simulation.tile else {
+            return;
+        };
+
+        let local = position.and_then(|position| {
+            let bounds = self.tile_manager.get_aabb_clipped(tile)?;
+            let (min, max) = (bounds.min(), bounds.max());
+            let inside = position.cmpge(min).all() && position.cmplt(max).all();
+            inside.then(|| position - min)
+        });
+        self.cursor = local;
+        self.tile_manager.dispatch_event(tile, TileEvent::CursorMoved(local));
+    }
+
+    /// Zooms the simulation tile about the cursor, by `ZOOM_STEP` per scrolled line.
+    fn handle_scroll(&mut self, delta: MouseScrollDelta) {
+        let (Some(tile), Some(anchor)) = (self.primary_simulation.tile, self.cursor) else {
+            return;
+        };
+
+        let lines = match delta {
+            MouseScrollDelta::LineDelta(_, y) => y,
+            MouseScrollDelta::PixelDelta(position) => position.y as f32 / Self::PIXELS_PER_LINE,
+        };
+        let factor = Self::ZOOM_STEP.powf(lines);
+        self.tile_manager.dispatch_event(tile, TileEvent::Zoom { anchor, factor });
+    }
+
+    /// Renders the window at `SCREENSHOT_SCALE` times its size, downsamples it,
+    /// and saves it as a timestamped PNG in the working directory.
+    fn save_screenshot(&mut self) {
+        let Some(gpu_context) = &self.gpu_context else {
+            return;
+        };
+        let size = vec2(gpu_context.size.width as f32, gpu_context.size.height as f32);
+        let scale = gpu_context.capture_scale(Self::SCREENSHOT_SCALE);
+
+        // Lay the tiles out for the supersampled frame, then back for the window.
+        self.tile_manager.resize(size * scale as f32);
+        self.tile_manager.load_all(&self.primary_simulation.state.lock().unwrap(), gpu_context);
+        let tile_manager = &self.tile_manager;
+        let image = gpu_context.capture_supersampled(scale, |render_pass| {
+            tile_manager.render_all(render_pass)
+        });
+        self.tile_manager.resize(size);
+
+        let Some(image) = image else {
+            return;
+        };
+        let timestamp = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map_or(0, |elapsed| elapsed.as_secs());
+        let path = format!("screenshot_{timestamp}.png");
+        match image.save(&path) {
+            Ok(()) => println!("Saved screenshot to {path}"),
+            Err(err) => eprintln!("Failed to save screenshot: {err}"),
+        }
+    }
+
+    /// Saves the primary simulation's connection topology as a timestamped Graphviz DOT file
+    /// in the working directory.
+    fn save_topology(&self) {
+        let dot = self.primary_simulation.state.lock().unwrap().topology_dot();
+        let timestamp = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map_or(0, |elapsed| elapsed.as_secs());
+        let path = format!("topology_{timestamp}.dot");
+        match std::fs::write(&path, dot) {
+            Ok(()) => println!("Saved topology to {path}"),
+            Err(err) => eprintln!("Failed to save topology: {err}"),
+        }
+    }
+
+    /// Records the primary simulation so the edit about to be made can be undone.
+    fn begin_edit(&mut self, state: &SimulationState) {
+        self.undo_stack.lock().unwrap().push(state);
+    }
+
+    /// Returns the primary simulation's cell under the cursor, if any.
+    fn hovered_cell(&self, state: &SimulationState) -> Option<CellId> {
+        let world = self.sim_view.lock().unwrap().screen_to_world(self.cursor?);
+        state.pick_at(world.into())
+    }
+
+    /// Removes the cell under the cursor along with its connections,
+    /// then compacts the heap so the freed slot isn't left behind.
+    fn remove_hovered(&mut self) {
+        let state = self.primary_simulation.state.clone();
+        let mut state = state.lock().unwrap();
+        let Some(id) = self.hovered_cell(&state) else {
+            return;
+        };
+        self.begin_edit(&state);
+        state.remove(id);
+        state.compact();
+    }
+
+    /// Marks the cell under the cursor, or, if one is already marked, joins its organism
+    /// with the hovered cell's. Pressing over empty space clears the mark.
+    fn merge_hovered(&mut self) {
+        let state = self.primary_simulation.state.clone();
+        let mut state = state.lock().unwrap();
+        let Some(id) = self.hovered_cell(&state) else {
+            self.merge_mark = None;
+            return;
+        };
+        let Some(marked) = self.merge_mark.take().and_then(|uid| state.id_of(uid)) else {
+            self.merge_mark = Some(state.cells.get(id).uid);
+            return;
+        };
+
+        self.begin_edit(&state);
+        if let Err(err) = state.merge(marked, id) {
+            // Nothing changed, so drop the snapshot just taken.
+            self.undo_stack.lock().unwrap().undo(&mut state);
+            match err {
+                ConnectError::SameComponent => eprintln!("Cells already belong to the same organism."),
+                err => eprintln!("Failed to merge cells: {err:?}"),
+            }
+        }
+    }
+
+    /// Changes the primary simulation's viscosity by `delta`, never going below zero.
+    fn nudge_viscosity(&mut self, delta: f64) {
+        let mut state = self.primary_simulation.state.lock().unwrap();
+        let viscosity = state.viscosity() + delta;
+        state.set_viscosity(viscosity);
+    }
+
+    /// Handles key presses bound to application commands.
+    fn handle_key(&mut self, key: Key) {
+        match key.as_ref() {
+            // Undo the last edit.
+            Key::Character("z") if self.modifiers.control_key() => {
+                let mut state = self.primary_simulation.state.lock().unwrap();
+                self.undo_stack.lock().unwrap().undo(&mut state);
+            }
+            // Restart the simulation from its initial state.
+            Key::Character("r") => {
+                let state = self.primary_simulation.state.clone();
+                let mut state = state.lock().unwrap();
+                self.begin_edit(&state);
+                state.reset();
+            }
+            // Delete the cell under the cursor.
+            Key::Character("x") => self.remove_hovered(),
+            // Join two organisms: mark a cell, then press again over a cell of the other.
+            Key::Character("j") => self.merge_hovered(),
+            // Stimulate every neural cell, sending a signal rippling through the organism.
+            Key::Character("n") => {
+                let mut state = self.primary_simulation.state.lock().unwrap();
+                let neural: Vec<_> = state
+                    .cells
+                    .iter_indexed()
+                    .filter(|(_, cell)| cell.typ == CellType::Neural)
+                    .map(|(id, _)| id)
+                    .collect();
+                for id in neural {
+                    state.inject_signal(id, 1.0);
+                }
+            }
+            // Fit the view to the whole organism, keeping it in place if no cells are left.
+            Key::Character("f") => {
+                let state = self.primary_simulation.state.lock().unwrap();
+                let bounds = (!state.cells.is_empty()).then(|| state.world_bounds());
+                drop(state);
+                if let (Some(tile), Some(bounds)) = (self.primary_simulation.tile, bounds) {
+                    self.tile_manager.dispatch_event(tile, TileEvent::FrameBounds(bounds));
+                }
+            }
+            // Save a supersampled screenshot.
+            Key::Character("p") => self.save_screenshot(),
+            // Export the connection graph for viewing with Graphviz.
+            Key::Character("g") => self.save_topology(),
+            // Toggle between coloring cells by type and by organism.
+            Key::Character("c") => {
+                if let Some(tile) = self.primary_simulation.tile {
+                    self.tile_manager.dispatch_event(tile, TileEvent::CycleColorMode);
+                }
+            }
+            // Switch how the world is fitted to the tile: letterboxed, cropped, or stretched.
+            Key::Character("m") => {
+                if let Some(tile) = self.primary_simulation.tile {
+                    self.tile_manager.dispatch_event(tile, TileEvent::CycleFitMode);
+                }
+            }
+            // Thin or thicken the fluid the organism moves through.
+            Key::Character("[") => self.nudge_viscosity(-Self::VISCOSITY_STEP),
+            Key::Character("]") => self.nudge_viscosity(Self::VISCOSITY_STEP),
+            // Check that the next frame's primitives reach the GPU intact.
+            Key::Character("u") => {
+                if let Some(tile) = self.primary_simulation.tile {
+                    self.tile_manager.dispatch_event(tile, TileEvent::CheckUpload);
+                }
+            }
+            // Show or hide live stats in the window title.
+            Key::Character("t") => self.set_title_stats(!self.title_stats.enabled),
+            // Show or hide the velocity and force arrows and the connection handles.
+            Key::Character("v") => {
+                self.debug_visible = !self.debug_visible;
+                if let Some(tile) = self.primary_simulation.tile {
+                    self.tile_manager.dispatch_event(tile, TileEvent::ToggleDebug);
+                }
+            }
+            _ => {}
         }
     }
 }
@@ -146,12 +721,51 @@ impl ApplicationHandler for App {
                 println!("Close requested. Exiting application.");
                 event_loop.exit();
             }
+            WindowEvent::RedrawRequested if self.minimized => {}
             WindowEvent::RedrawRequested => {
+                let frame_start = Instant::now();
                 self.update_and_render();
+                self.pace_frame(frame_start);
             }
             WindowEvent::Resized(new_size) => {
                 self.handle_resize(new_size);
             }
+            WindowEvent::KeyboardInput {
+                event: KeyEvent { physical_key, logical_key, state, .. },
+                ..
+            } => {
+                if let PhysicalKey::Code(code) = physical_key {
+                    match state {
+                        ElementState::Pressed => self.held_keys.insert(code),
+                        ElementState::Released => self.held_keys.remove(&code),
+                    };
+                }
+                if state == ElementState::Pressed {
+                    self.handle_key(logical_key);
+                }
+            }
+            WindowEvent::CursorMoved { position, .. } => {
+                self.handle_cursor(Some(vec2(position.x as f32, position.y as f32)));
+            }
+            WindowEvent::MouseInput { state, button: MouseButton::Left, .. } => {
+                if let Some(tile) = self.primary_simulation.tile {
+                    let pressed = state == ElementState::Pressed;
+                    self.tile_manager.dispatch_event(tile, TileEvent::MouseButton { pressed });
+                }
+            }
+            WindowEvent::MouseWheel { delta, .. } => {
+                self.handle_scroll(delta);
+            }
+            WindowEvent::ModifiersChanged(modifiers) => {
+                self.modifiers = modifiers.state();
+            }
+            WindowEvent::CursorLeft { .. } => {
+                self.handle_cursor(None);
+            }
+            WindowEvent::Focused(false) => {
+                // Release events are not delivered while unfocused.
+                self.held_keys.clear();
+            }
             _ => {}
         }
     }
@@ -1,8 +1,283 @@
-use crate::core::sim::{SimulationState};
+use crate::core::sim::{SimulationSnapshot, SimulationState};
+use std::collections::VecDeque;
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::{Arc, Mutex};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
 use taffy::NodeId;
 
 pub struct Simulation {
     pub state: Arc<Mutex<SimulationState>>,
     pub tile: Option<NodeId>,
-}
\ No newline at end of file
+}
+
+/// Per-frame statistics tracked by the application for display.
+pub struct FrameStats {
+    pub fps: f32,
+    pub last_frame: Instant,
+}
+
+impl FrameStats {
+    /// Smoothing factor applied to the running FPS estimate.
+    const FPS_SMOOTHING: f32 = 0.1;
+
+    pub fn new() -> Self {
+        Self {
+            fps: 0.0,
+            last_frame: Instant::now(),
+        }
+    }
+
+    /// Records a finished frame, updating the smoothed FPS.
+    /// Returns the seconds elapsed since the previous frame.
+    pub fn record_frame(&mut self) -> f32 {
+        let now = Instant::now();
+        let elapsed = now.duration_since(self.last_frame).as_secs_f32();
+        self.last_frame = now;
+
+        if elapsed > 0.0 {
+            self.fps += (1.0 / elapsed - self.fps) * Self::FPS_SMOOTHING;
+        }
+        elapsed
+    }
+}
+
+/// Averages the frame rate over fixed intervals for the window title.
+pub struct TitleStats {
+    /// Whether the window title shows live stats.
+    pub enabled: bool,
+    interval: Duration,
+    interval_start: Instant,
+    frames: u32,
+}
+
+impl TitleStats {
+    /// Time between window title updates.
+    pub const DEFAULT_INTERVAL: Duration = Duration::from_secs(1);
+
+    pub fn new(enabled: bool, interval: Duration) -> Self {
+        Self {
+            enabled,
+            interval,
+            interval_start: Instant::now(),
+            frames: 0,
+        }
+    }
+
+    /// Records a frame finished at `now`.
+    /// Once an interval has passed, returns the average FPS over it and starts the next.
+    pub fn record_frame(&mut self, now: Instant) -> Option<f32> {
+        self.frames += 1;
+        let elapsed = now.saturating_duration_since(self.interval_start);
+        if elapsed < self.interval {
+            return None;
+        }
+
+        let fps = self.frames as f32 / elapsed.as_secs_f32();
+        self.interval_start = now;
+        self.frames = 0;
+        Some(fps)
+    }
+}
+
+/// Appends simulation snapshots to a CSV file at a fixed tick interval.
+pub struct CsvLog {
+    writer: BufWriter<File>,
+    interval: u64,
+}
+
+impl CsvLog {
+    /// Creates the file at `path` and writes the column header.
+    pub fn create(path: impl AsRef<Path>, interval: u64) -> io::Result<Self> {
+        let mut writer = BufWriter::new(File::create(path)?);
+        writeln!(writer, "{}", SimulationState::SNAPSHOT_CSV_HEADER)?;
+        Ok(Self {
+            writer,
+            interval: interval.max(1),
+        })
+    }
+
+    /// Writes a snapshot if the state's tick count falls on the logging interval.
+    pub fn record(&mut self, state: &SimulationState) -> io::Result<()> {
+        if !state.tick_count().is_multiple_of(self.interval) {
+            return Ok(());
+        }
+        state.export_snapshot_csv(&mut self.writer)?;
+        self.writer.flush()
+    }
+}
+
+/// Converts elapsed wall time into whole fixed-timestep ticks, carrying the remainder over.
+///
+/// At most `max_substeps` ticks are due per call; beyond that the excess time is dropped,
+/// so a long stall can't demand ever more catch-up work (the "spiral of death").
+pub struct FixedStepClock {
+    step: Duration,
+    max_substeps: u32,
+    accumulator: Duration,
+}
+
+impl FixedStepClock {
+    /// Default cap on ticks run to catch up after a single stall.
+    pub const DEFAULT_MAX_SUBSTEPS: u32 = 5;
+
+    /// Creates a clock ticking every `step`, running at most `max_substeps` ticks per `advance`.
+    pub fn new(step: Duration, max_substeps: u32) -> Self {
+        Self {
+            step: step.max(Duration::from_nanos(1)),
+            max_substeps: max_substeps.max(1),
+            accumulator: Duration::ZERO,
+        }
+    }
+
+    /// Adds `elapsed` time and returns how many ticks are now due,
+    /// and the time dropped to honor the substep cap.
+    pub fn advance(&mut self, elapsed: Duration) -> (u32, Duration) {
+        self.accumulator += elapsed;
+
+        let mut steps = 0;
+        while self.accumulator >= self.step && steps < self.max_substeps {
+            self.accumulator -= self.step;
+            steps += 1;
+        }
+
+        // Over the cap, keep only the fraction of a step still owed.
+        let remainder = Duration::from_nanos((self.accumulator.as_nanos() % self.step.as_nanos()) as u64);
+        let dropped = self.accumulator - remainder;
+        self.accumulator = remainder;
+        (steps, dropped)
+    }
+
+    /// Returns the time accumulated towards the next tick.
+    pub fn accumulated(&self) -> Duration {
+        self.accumulator
+    }
+
+    /// Returns the time left until the next tick is due.
+    pub fn until_next(&self) -> Duration {
+        self.step.saturating_sub(self.accumulator)
+    }
+}
+
+/// Advances a simulation at a fixed timestep on a dedicated thread,
+/// holding the state lock only for the duration of each tick.
+pub struct SimRunner {
+    stop: Arc<AtomicBool>,
+    handle: Option<JoinHandle<()>>,
+    /// Wall time between ticks.
+    step: Duration,
+    /// When the latest tick was due, updated while the state is still locked for it.
+    last_due: Arc<Mutex<Instant>>,
+}
+
+impl SimRunner {
+    /// Starts ticking `state` every `dt` seconds, recording each tick to `csv_log` if given.
+    pub fn spawn(state: Arc<Mutex<SimulationState>>, dt: f64, mut csv_log: Option<CsvLog>) -> Self {
+        let stop = Arc::new(AtomicBool::new(false));
+        let step = Duration::from_secs_f64(dt);
+        let mut clock = FixedStepClock::new(step, FixedStepClock::DEFAULT_MAX_SUBSTEPS);
+        let last_due = Arc::new(Mutex::new(Instant::now()));
+
+        let handle = thread::Builder::new()
+            .name("simulation".into())
+            .spawn({
+                let stop = Arc::clone(&stop);
+                let last_due = Arc::clone(&last_due);
+                move || {
+                    let mut last = Instant::now();
+                    while !stop.load(Ordering::Relaxed) {
+                        let now = Instant::now();
+                        let (steps, dropped) = clock.advance(now - last);
+                        last = now;
+
+                        if !dropped.is_zero() {
+                            eprintln!("Simulation can't keep up, dropping {:.0} ms", dropped.as_secs_f64() * 1000.0);
+                        }
+
+                        for _ in 0..steps {
+                            let mut state = state.lock().expect("Failed to lock SimulationState");
+                            state.tick(dt);
+                            *last_due.lock().unwrap() = now - clock.accumulated();
+
+                            if let Some(log) = &mut csv_log
+                                && let Err(err) = log.record(&state)
+                            {
+                                eprintln!("CSV export failed, disabling: {err}");
+                                csv_log = None;
+                            }
+                        }
+
+                        thread::sleep(clock.until_next());
+                    }
+                }
+            })
+            .expect("Failed to spawn simulation thread");
+
+        Self {
+            stop,
+            handle: Some(handle),
+            step,
+            last_due,
+        }
+    }
+
+    /// Returns how far wall time has moved from the latest tick towards the next, in `[0, 1]`.
+    /// Read it while holding the state lock to match the ticked state.
+    pub fn alpha(&self) -> f64 {
+        let since = self.last_due.lock().unwrap().elapsed();
+        (since.as_secs_f64() / self.step.as_secs_f64()).min(1.0)
+    }
+}
+
+impl Drop for SimRunner {
+    fn drop(&mut self) {
+        self.stop.store(true, Ordering::Relaxed);
+        if let Some(handle) = self.handle.take() {
+            let _ = handle.join();
+        }
+    }
+}
+
+/// Bounded history of simulation snapshots taken before editing operations.
+///
+/// Only cells and connections are captured, so undo doesn't rewind time or drop listeners.
+pub struct UndoStack {
+    snapshots: VecDeque<SimulationSnapshot>,
+    depth: usize,
+}
+
+impl UndoStack {
+    /// Default number of edits that can be undone.
+    pub const DEFAULT_DEPTH: usize = 32;
+
+    /// Creates an empty stack keeping at most `depth` snapshots.
+    pub fn new(depth: usize) -> Self {
+        Self {
+            snapshots: VecDeque::with_capacity(depth),
+            depth: depth.max(1),
+        }
+    }
+
+    /// Records `state` before an edit, discarding the oldest snapshot when full.
+    /// Continuous edits such as drags should call this once when they begin.
+    pub fn push(&mut self, state: &SimulationState) {
+        if self.snapshots.len() == self.depth {
+            self.snapshots.pop_front();
+        }
+        self.snapshots.push_back(state.snapshot());
+    }
+
+    /// Restores the most recent snapshot into `state`. Returns `false` if there was nothing to undo.
+    pub fn undo(&mut self, state: &mut SimulationState) -> bool {
+        match self.snapshots.pop_back() {
+            Some(snapshot) => {
+                state.restore(&snapshot);
+                true
+            }
+            None => false,
+        }
+    }
+}
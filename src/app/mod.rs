@@ -1,4 +1,4 @@
 pub mod tile;
 pub mod app;
-mod components;
+pub mod components;
 mod utils;
\ No newline at end of file
@@ -1,16 +1,17 @@
 use crate::core::sim::SimulationState;
+use crate::gpu::context::GpuContext;
 use crate::graphics::models::space::AABB;
-use crate::graphics::renderer::TileRenderer;
+use crate::graphics::renderer::{TileEvent, TileRenderer};
 
 use glam::{vec2, Vec2};
 use std::collections::HashMap;
-use std::sync::{Arc, Mutex};
 use taffy::prelude::*;
 use taffy::TaffyTree;
 use wgpu::RenderPass;
 
 /// Represents a single tile that holds multiple render layers.
 pub struct Tile {
+    /// Layers sorted by `RenderPhase`, then by the order they were added.
     pub render_layers: Vec<Box<dyn TileRenderer>>,
 }
 
@@ -24,10 +25,15 @@ impl Tile {
 }
 
 /// Manages layout and rendering of tiles using Taffy for layout and WGPU for drawing.
+///
+/// Tiles are updated and drawn in the order they were added, never in hash order,
+/// so identical runs produce identical frames.
 pub struct TileViewManager {
     taffy: TaffyTree,
     root: NodeId,
     tiles: HashMap<NodeId, Tile>,
+    /// Tile nodes in the order they were added; the order tiles are updated and drawn in.
+    tile_order: Vec<NodeId>,
     aabb_cache: HashMap<NodeId, AABB>,
 }
 
@@ -41,6 +47,7 @@ impl TileViewManager {
             taffy,
             root,
             tiles: HashMap::new(),
+            tile_order: Vec::new(),
             aabb_cache: HashMap::new(),
         }
     }
@@ -68,9 +75,57 @@ impl TileViewManager {
         let node = self.taffy.new_leaf(style).unwrap();
         self.taffy.add_child(parent, node).unwrap();
         self.tiles.insert(node, Tile::empty());
+        self.tile_order.push(node);
         node
     }
 
+    /// Splits `parent` into side-by-side children, each taking its share of `ratios` of the width.
+    pub fn split_horizontal(&mut self, parent: NodeId, ratios: &[f32]) -> Vec<NodeId> {
+        self.split(parent, ratios, FlexDirection::Row)
+    }
+
+    /// Splits `parent` into stacked children, each taking its share of `ratios` of the height.
+    pub fn split_vertical(&mut self, parent: NodeId, ratios: &[f32]) -> Vec<NodeId> {
+        self.split(parent, ratios, FlexDirection::Column)
+    }
+
+    /// Splits `parent` into a `rows` by `cols` grid of equally sized children.
+    /// Returns the cells in row-major order.
+    pub fn grid(&mut self, parent: NodeId, rows: usize, cols: usize) -> Vec<NodeId> {
+        self.split_vertical(parent, &vec![1.0; rows])
+            .into_iter()
+            .flat_map(|row| self.split_horizontal(row, &vec![1.0; cols]))
+            .collect()
+    }
+
+    /// Lays `parent` out as a flex container along `direction` and adds one child per ratio,
+    /// sized by its ratio's fraction of the total along that axis and filling the other axis.
+    fn split(&mut self, parent: NodeId, ratios: &[f32], direction: FlexDirection) -> Vec<NodeId> {
+        let mut parent_style = self.taffy.style(parent).cloned().unwrap_or_default();
+        parent_style.display = Display::Flex;
+        parent_style.flex_direction = direction;
+        self.set_style(parent, parent_style);
+
+        let total: f32 = ratios.iter().sum();
+        ratios
+            .iter()
+            .map(|ratio| {
+                let share = Dimension::percent(ratio / total);
+                let full = Dimension::percent(1.0);
+                let size = match direction {
+                    FlexDirection::Row | FlexDirection::RowReverse => Size { width: share, height: full },
+                    FlexDirection::Column | FlexDirection::ColumnReverse => Size { width: full, height: share },
+                };
+
+                self.add_leaf(parent, Style {
+                    size,
+                    flex_shrink: 0.0,
+                    ..Default::default()
+                })
+            })
+            .collect()
+    }
+
     /// Sets a new style for a given node.
     pub fn set_style(&mut self, node: NodeId, style: Style) {
         if let Err(e) = self.taffy.set_style(node, style) {
@@ -85,20 +140,31 @@ impl TileViewManager {
             .unwrap_or(Vec2::ZERO)
     }
 
-    /// Computes and returns the axis-aligned bounding box of a node.
+    /// Computes and returns the axis-aligned bounding box of a node, in window coordinates.
     pub fn get_aabb(&self, node: NodeId) -> AABB {
         let layout = self.taffy.layout(node).unwrap();
         let size = vec2(layout.size.width, layout.size.height);
-        let position = vec2(layout.location.x, layout.location.y);
+
+        // Layout locations are relative to the parent, so accumulate up to the root.
+        let mut position = vec2(layout.location.x, layout.location.y);
+        let mut ancestor = self.taffy.parent(node);
+        while let Some(parent) = ancestor {
+            let location = self.taffy.layout(parent).unwrap().location;
+            position += vec2(location.x, location.y);
+            ancestor = self.taffy.parent(parent);
+        }
+
         AABB::from_edges(position, position + size)
     }
 
-    /// Returns the clipped AABB of a node, intersected with the root node's bounds.
-    pub fn get_aabb_clipped(&self, node: NodeId) -> AABB {
+    /// Returns the clipped AABB of a node, intersected with the root node's bounds,
+    /// or `None` if the node lies entirely outside the root.
+    pub fn get_aabb_clipped(&self, node: NodeId) -> Option<AABB> {
         self.get_aabb(node) & self.get_aabb(self.root)
     }
 
     /// Adds a renderer layer to the specified node and initializes it.
+    /// It is drawn after the node's layers of earlier or equal phase and before those of later phases.
     pub fn add_renderer<R: TileRenderer + 'static>(
         &mut self,
         node: NodeId,
@@ -107,12 +173,35 @@ impl TileViewManager {
     ) {
         layer.init(queue);
         if let Some(tile) = self.tiles.get_mut(&node) {
-            tile.render_layers.push(Box::new(layer));
+            let index = tile.render_layers.partition_point(|other| other.phase() <= layer.phase());
+            tile.render_layers.insert(index, Box::new(layer));
+        }
+    }
+
+    /// Removes and returns all renderer layers of the specified node.
+    pub fn take_renderers(&mut self, node: NodeId) -> Vec<Box<dyn TileRenderer>> {
+        self.tiles
+            .get_mut(&node)
+            .map(|tile| std::mem::take(&mut tile.render_layers))
+            .unwrap_or_default()
+    }
+
+    /// Replaces the renderer layers of the specified node with already initialized ones,
+    /// ordered by phase and otherwise as given.
+    pub fn set_renderers(&mut self, node: NodeId, mut layers: Vec<Box<dyn TileRenderer>>) {
+        layers.sort_by_key(|layer| layer.phase());
+        if let Some(tile) = self.tiles.get_mut(&node) {
+            tile.render_layers = layers;
         }
     }
 
     /// Recomputes layout and AABB cache for all tiles based on the available window size.
+    /// Zero-sized areas are ignored, keeping the previous layout.
     pub fn resize(&mut self, available: Vec2) {
+        if available.x <= 0.0 || available.y <= 0.0 {
+            return;
+        }
+
         self.taffy.set_style(self.root, Self::root_style()).unwrap();
 
         let size = Size {
@@ -124,37 +213,58 @@ impl TileViewManager {
             eprintln!("Failed to compute layout: {:?}", e);
         }
 
+        // Tiles clipped away entirely get no cache entry, so they are neither updated nor drawn.
         self.aabb_cache.clear();
-        let root_bounds = self.get_aabb(self.root);
-
-        for node in self.tiles.keys() {
-            let node_bounds = self.get_aabb(*node);
-            let clipped = node_bounds & root_bounds;
-            self.aabb_cache.insert(*node, clipped);
+        for &node in &self.tile_order {
+            if let Some(clipped) = self.get_aabb_clipped(node) {
+                self.aabb_cache.insert(node, clipped);
+            }
         }
     }
 
-    /// Updates all tiles with simulation state and resizes layers.
-    pub fn load_all(&mut self, sim_state: Arc<Mutex<SimulationState>>, queue: &wgpu::Queue) {
-        for (node_id, tile) in &mut self.tiles {
-            if let Some(aabb) = self.aabb_cache.get(node_id) {
+    /// Returns the tile nodes in the order they are updated and drawn.
+    pub fn tile_order(&self) -> &[NodeId] {
+        &self.tile_order
+    }
+
+    /// Resizes layers and updates their views, without touching the simulation.
+    ///
+    /// Layers get the size of their clipped AABB, the same region `render_all` uses as their viewport,
+    /// so projections built from it keep world space undistorted even when the window cuts a tile off.
+    pub fn update_views(&mut self, queue: &wgpu::Queue) {
+        for node_id in &self.tile_order {
+            if let (Some(tile), Some(aabb)) = (self.tiles.get_mut(node_id), self.aabb_cache.get(node_id)) {
                 for layer in tile.render_layers.iter_mut() {
                     layer.resize(aabb.wh(), queue);
-                    layer.update_render_data(Arc::clone(&sim_state), queue);
+                    layer.update_view(queue);
                 }
             }
         }
     }
 
-    /// Renders all tiles using the current AABB layout and render layers.
-    pub fn render_all<'a>(&'a self, render_pass: &mut RenderPass<'a>) {
-        for (node_id, tile) in &self.tiles {
-            if let Some(aabb) = self.aabb_cache.get(node_id) {
-                let size = aabb.wh();
-                if size.x <= 0.0 || size.y <= 0.0 {
-                    continue; // Skip invisible tiles
+    /// Updates all layers' render data from the simulation state.
+    pub fn update_render_data(&mut self, sim_state: &SimulationState, context: &GpuContext) {
+        for node_id in &self.tile_order {
+            if let Some(tile) = self.tiles.get_mut(node_id) {
+                for layer in tile.render_layers.iter_mut() {
+                    layer.update_render_data(sim_state, context);
                 }
+            }
+        }
+    }
+
+    /// Resizes layers, updates their views, and updates them with simulation state.
+    pub fn load_all(&mut self, sim_state: &SimulationState, context: &GpuContext) {
+        self.update_views(&context.queue);
+        self.update_render_data(sim_state, context);
+    }
 
+    /// Renders all tiles using the current AABB layout and render layers,
+    /// drawing each tile's layers in phase order.
+    pub fn render_all<'a>(&'a self, render_pass: &mut RenderPass<'a>) {
+        for node_id in &self.tile_order {
+            if let (Some(tile), Some(aabb)) = (self.tiles.get(node_id), self.aabb_cache.get(node_id)) {
+                let size = aabb.wh();
                 render_pass.set_viewport(
                     aabb.min().x,
                     aabb.min().y,
@@ -171,5 +281,12 @@ impl TileViewManager {
         }
     }
 
-    // Future: pub fn dispatch_event(...) {}
+    /// Sends an event to every render layer of the given tile.
+    pub fn dispatch_event(&mut self, node: NodeId, event: TileEvent) {
+        if let Some(tile) = self.tiles.get_mut(&node) {
+            for layer in tile.render_layers.iter_mut() {
+                layer.handle_event(&event);
+            }
+        }
+    }
 }
@@ -0,0 +1,60 @@
+use super::elements::Cell;
+use super::features::CellType;
+use super::signals::SIGNAL_THRESHOLD;
+use super::sim::{SimContext, SimulationState};
+use std::f64::consts::TAU;
+
+/// Per-type logic run on every cell each tick, making cell types functionally distinct.
+pub trait CellBehavior: Sync {
+    /// Updates a single cell for a time step `dt`.
+    fn update(&self, cell: &mut Cell, ctx: &SimContext, dt: f64);
+}
+
+/// Periodically contracts the cell, shortening the rest length of its connections.
+/// A strong nerve signal forces a contraction regardless of the cycle.
+pub struct MuscleBehavior {
+    /// Seconds per full contraction cycle.
+    pub period: f64,
+    /// Peak contraction, as a fraction of the cell's radius.
+    pub amplitude: f64,
+}
+
+impl CellBehavior for MuscleBehavior {
+    /// Advances the contraction cycle and eases the contraction in and out along a cosine.
+    fn update(&self, cell: &mut Cell, _ctx: &SimContext, dt: f64) {
+        cell.cycle = (cell.cycle + dt / self.period).fract();
+        cell.contraction = self.amplitude * 0.5 * (1.0 - (cell.cycle * TAU).cos());
+
+        if cell.signal > SIGNAL_THRESHOLD {
+            let stimulated = self.amplitude * f64::from(cell.signal.min(1.0));
+            cell.contraction = cell.contraction.max(stimulated);
+        }
+    }
+}
+
+/// Behavior shared by all muscle cells.
+static MUSCLE: MuscleBehavior = MuscleBehavior {
+    period: 2.0,
+    amplitude: 0.4,
+};
+
+impl CellType {
+    /// Returns the behavior run by cells of this type, if any.
+    pub fn behavior(&self) -> Option<&'static dyn CellBehavior> {
+        match self {
+            CellType::Muscle => Some(&MUSCLE),
+            _ => None,
+        }
+    }
+}
+
+impl SimulationState {
+    /// Runs each cell's type-specific behavior for a time step `dt`.
+    pub fn behavior_pass(&mut self, dt: f64) {
+        for cell in self.cells.flatten_iter_mut() {
+            if let Some(behavior) = cell.typ.behavior() {
+                behavior.update(cell, &self.context, dt);
+            }
+        }
+    }
+}
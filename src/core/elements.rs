@@ -1,23 +1,72 @@
 use super::features::CellType;
+use super::resources::LocalResources;
 use crate::graphics::models::space::SrtTransform;
+use crate::physics::collision::{CollisionShape, Placed};
 use crate::physics::objects;
 use crate::physics::objects::ObjectData2D;
-use crate::utils::vector::Vec2d;
+use crate::utils::vector::{wrap_angle, Vec2d};
 use glam::Vec2;
+use std::f64::consts::PI;
 
-/// Type alias for identifying a cell.
+/// Type alias for identifying a cell by its heap slot.
 pub type CellId = usize;
 
+/// Stable identifier of a cell that survives removal of other cells and heap compaction.
+/// Assigned by `SimulationState` when the cell is spawned.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Uid(pub u64);
+
+impl Uid {
+    /// Placeholder held by cells that have not been spawned into a simulation yet.
+    pub const UNASSIGNED: Uid = Uid(0);
+}
+
+/// Constraint holding a connection's two cells together.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ConnectionKind {
+    /// Springs between the centers and edge points, resisting both stretching and compression.
+    #[default]
+    Spring,
+    /// A single center-to-center rope that only pulls while stretched past the rest length.
+    Rope,
+}
+
 /// Represents a directional connection between two cells.
+#[derive(Clone, Debug)]
 pub struct CellConnection {
     pub id_a: CellId,
     pub angle_a: f64,
 
     pub id_b: CellId,
     pub angle_b: f64,
+
+    /// Extra distance kept between the two cell edges at rest.
+    pub gap: f64,
+
+    /// Stiffness of the center-to-center spring, or of the rope for rope connections.
+    pub primary_k: f64,
+    /// Fixed center-to-center rest length, replacing the size-based one if set.
+    pub primary_length: Option<f64>,
+    /// Stiffness of the spring between the two edge points.
+    pub edge_k: f64,
+    /// Damping of both springs as a fraction of critical damping; 1.0 settles without ringing.
+    pub damping_ratio: f64,
+
+    /// Lower bound on `joint_angle`, if the joint is limited.
+    pub angle_min: Option<f64>,
+    /// Upper bound on `joint_angle`, if the joint is limited.
+    pub angle_max: Option<f64>,
+
+    /// Constraint used to hold the cells together.
+    pub kind: ConnectionKind,
 }
 
 impl CellConnection {
+    /// Default stiffness of both connection springs.
+    pub const DEFAULT_STIFFNESS: f64 = 50.0;
+    /// Default damping ratio of both connection springs, critically damped.
+    pub const DEFAULT_DAMPING_RATIO: f64 = 1.0;
+
     /// Creates a new connection between two cells with specified angles.
     pub fn new(id_a: CellId, angle_a: f64, id_b: CellId, angle_b: f64) -> Self {
         Self {
@@ -25,6 +74,72 @@ impl CellConnection {
             angle_a,
             id_b,
             angle_b,
+            gap: 0.0,
+            primary_k: Self::DEFAULT_STIFFNESS,
+            primary_length: None,
+            edge_k: Self::DEFAULT_STIFFNESS,
+            damping_ratio: Self::DEFAULT_DAMPING_RATIO,
+            angle_min: None,
+            angle_max: None,
+            kind: ConnectionKind::Spring,
+        }
+    }
+
+    /// Sets the stiffness of the center-to-center and edge springs.
+    pub fn with_stiffness(mut self, primary_k: f64, edge_k: f64) -> Self {
+        self.primary_k = primary_k;
+        self.edge_k = edge_k;
+        self
+    }
+
+    /// Sets the spring damping as a fraction of critical damping; 0.0 leaves the springs undamped.
+    pub fn with_damping_ratio(mut self, damping_ratio: f64) -> Self {
+        self.damping_ratio = damping_ratio;
+        self
+    }
+
+    /// Fixes the center-to-center rest length instead of deriving it from the cell sizes.
+    pub fn with_rest_length(mut self, length: f64) -> Self {
+        self.primary_length = Some(length);
+        self
+    }
+
+    /// Sets the constraint used to hold the cells together.
+    pub fn with_kind(mut self, kind: ConnectionKind) -> Self {
+        self.kind = kind;
+        self
+    }
+
+    /// Limits the joint to bend only between `min` and `max` radians.
+    pub fn with_angle_limits(mut self, min: f64, max: f64) -> Self {
+        self.angle_min = Some(min);
+        self.angle_max = Some(max);
+        self
+    }
+
+    /// Returns how far cell B is rotated relative to cell A, in `[-PI, PI)`,
+    /// measured from the pose where both edge points face each other.
+    pub fn joint_angle(&self, cell_a: &Cell, cell_b: &Cell) -> f64 {
+        wrap_angle(cell_b.angle - cell_a.angle - self.angle_a + self.angle_b - PI)
+    }
+
+    /// Returns by how many radians `joint_angle` exceeds the limits,
+    /// negative below `angle_min`, positive above `angle_max`, and zero within range.
+    pub fn joint_limit_excess(&self, cell_a: &Cell, cell_b: &Cell) -> f64 {
+        let angle = self.joint_angle(cell_a, cell_b);
+        match (self.angle_min, self.angle_max) {
+            (Some(min), _) if angle < min => angle - min,
+            (_, Some(max)) if angle > max => angle - max,
+            _ => 0.0,
+        }
+    }
+
+    /// Returns the center-to-center rest length for two connected cells: `primary_length` if set,
+    /// otherwise so that their edges touch, separated by `gap`. Contracting cells pull their neighbors closer.
+    pub fn rest_length(&self, cell_a: &Cell, cell_b: &Cell) -> f64 {
+        match self.primary_length {
+            Some(length) => length - cell_a.radius() * cell_a.contraction - cell_b.radius() * cell_b.contraction,
+            None => cell_a.radius() * (1.0 - cell_a.contraction) + cell_b.radius() * (1.0 - cell_b.contraction) + self.gap,
         }
     }
 
@@ -32,6 +147,11 @@ impl CellConnection {
     pub fn points_toward(&self, id: CellId) -> bool {
         self.id_a == id || self.id_b == id
     }
+
+    /// Returns `true` if this connection links the two given cells, in either order.
+    pub fn links(&self, a: CellId, b: CellId) -> bool {
+        (self.id_a == a && self.id_b == b) || (self.id_a == b && self.id_b == a)
+    }
 }
 
 /// A single cell in a physics-based simulation.
@@ -39,44 +159,141 @@ impl CellConnection {
 #[derive(Clone, Debug)]
 pub struct Cell {
     pub force: Vec2d,
+    /// Net force integrated in the last physics sub-step, kept after `force` is cleared.
+    pub last_force: Vec2d,
     pub mass: f64,
     pub position: Vec2d,
     pub velocity: Vec2d,
+    /// Position at the start of the last tick, for interpolating rendering between ticks.
+    pub previous_position: Vec2d,
 
     pub torque: f64,
     pub angular_inertia: f64,
     pub angle: f64,
     pub angular_velocity: f64,
+    /// Angle at the start of the last tick, for interpolating rendering between ticks.
+    pub previous_angle: f64,
 
     pub size: f64,
     pub typ: CellType,
+    pub resources: LocalResources,
+
+    /// Fraction of its radius by which the cell currently pulls its connections in.
+    pub contraction: f64,
+    /// Progress through the cell's behavior cycle, in `[0, 1)`.
+    pub cycle: f64,
+    /// Nerve signal currently held by the cell, relayed by neural cells.
+    pub signal: f32,
+    /// Vitality in `[0, 1]`, derived from stored energy each tick; rendering fades the cell out as it drops to zero.
+    pub health: f32,
+    /// Simulated seconds since the cell was spawned; rendering washes older cells out to gray.
+    pub age: f64,
+
+    pub uid: Uid,
 }
 
 impl Cell {
     /// Creates a new cell at a given position with a given type.
-    /// Initializes with default physics and size.
+    /// Initializes size and physics from the type's `CellProperties`.
     pub fn new(pos: Vec2d, typ: CellType) -> Self {
-        let disk = objects::Disk::from_mass(1.0, 1.0); // Approximate circular object
+        let properties = typ.properties();
+        let (mass, angular_inertia) = Self::mass_properties(typ, properties.size);
 
         Self {
-            mass: disk.mass(),
-            angular_inertia: disk.rotational_inertia(),
+            mass,
+            angular_inertia,
 
             force: Vec2d::ZERO,
+            last_force: Vec2d::ZERO,
             position: pos,
             velocity: Vec2d::ZERO,
+            previous_position: pos,
             torque: 0.0,
             angle: 0.0,
             angular_velocity: 0.0,
+            previous_angle: 0.0,
 
-            size: 1.0,
+            size: properties.size,
             typ,
+            resources: LocalResources::new(LocalResources::MAX_ENERGY, 0.0),
+
+            contraction: 0.0,
+            cycle: 0.0,
+            signal: 0.0,
+            health: 1.0,
+            age: 0.0,
+
+            uid: Uid::UNASSIGNED,
+        }
+    }
+
+    /// Returns the mass and angular inertia of a solid body of the given size (diameter)
+    /// matching the type's membrane shape, so inertia differs by type.
+    fn mass_properties(typ: CellType, size: f64) -> (f64, f64) {
+        let properties = typ.properties();
+        let radius = size * 0.5;
+        match properties.shape.sides() {
+            Some(sides) => {
+                let body = objects::RegularPolygon::new(sides, radius, properties.density);
+                (body.mass(), body.rotational_inertia())
+            }
+            None => {
+                let body = objects::Disk::new(radius, properties.density);
+                (body.mass(), body.rotational_inertia())
+            }
         }
     }
 
+    /// Returns the cell's membrane as a collision shape placed at its position and rotation.
+    /// Sized by `radius`, with polygon vertices on it, matching the rendered membrane.
+    pub fn collision_shape(&self) -> Placed<Box<dyn CollisionShape>> {
+        let properties = self.typ.properties();
+        let (shape, angle): (Box<dyn CollisionShape>, f64) = match properties.shape.sides() {
+            // The rendered square has its corners on the axes, so the rectangle is turned by an eighth.
+            Some(4) => {
+                let side = self.radius() * std::f64::consts::SQRT_2;
+                (Box::new(objects::Rectangle::new(side, side, properties.density)), PI / 4.0)
+            }
+            Some(sides) => (Box::new(objects::RegularPolygon::new(sides, self.radius(), properties.density)), 0.0),
+            None => (Box::new(objects::Disk::new(self.radius(), properties.density)), 0.0),
+        };
+        Placed {
+            shape,
+            position: self.position,
+            angle: self.angle + angle,
+        }
+    }
+
+    /// Resizes the cell, recomputing its mass and angular inertia at the type's density.
+    pub fn set_size(&mut self, size: f64) {
+        self.size = size;
+        (self.mass, self.angular_inertia) = Self::mass_properties(self.typ, size);
+    }
+
+    /// Wraps `angle` into `[-PI, PI)`, keeping it precise over long runs.
+    pub fn normalize_angle(&mut self) {
+        self.angle = wrap_angle(self.angle);
+    }
+
+    /// Returns the physical radius of the cell, measured from its center to its edge points.
+    pub fn radius(&self) -> f64 {
+        self.size * 0.5
+    }
+
     /// Returns the 2D position as a `Vec2` for rendering.
     pub fn position(&self) -> Vec2 {
-        Vec2::new(self.position.x as f32, self.position.y as f32)
+        self.position.to_glam()
+    }
+
+    /// Returns the offset from the cell's center to its edge point at `angle` relative to its rotation.
+    pub fn edge_offset(&self, angle: f64) -> Vec2d {
+        Vec2d::from_angle(self.angle + angle) * self.radius()
+    }
+
+    /// Returns the world-space position of the edge point at `angle` relative to the cell's rotation,
+    /// where connections attach, without the mutable borrow `edge_lever` needs.
+    pub fn edge_point(&self, angle: f64) -> Vec2d {
+        self.position + self.edge_offset(angle)
     }
 
     /// Returns the rotation angle as a `f32` in radians.
@@ -86,8 +303,32 @@ impl Cell {
 
     /// Returns the current transform of the cell (position, rotation, scale).
     pub fn get_transform(&self) -> SrtTransform {
+        self.get_transform_relative(Vec2d::ZERO)
+    }
+
+    /// Returns the transform of the cell relative to `origin`, interpolated between the start
+    /// of the last tick at `alpha` 0 and now at `alpha` 1, turning along the shorter arc.
+    pub fn get_transform_interpolated(&self, origin: Vec2d, alpha: f64) -> SrtTransform {
+        if alpha >= 1.0 {
+            return self.get_transform_relative(origin);
+        }
+
+        let alpha = alpha.max(0.0);
+        let position = self.previous_position + (self.position - self.previous_position) * alpha;
+        let angle = self.previous_angle + wrap_angle(self.angle - self.previous_angle) * alpha;
+        SrtTransform {
+            translate: (position - origin).to_glam(),
+            rotate: angle as f32,
+            scale: Vec2::splat(self.size as f32),
+        }
+    }
+
+    /// Returns the current transform of the cell with its position measured from `origin`.
+    /// The offset is taken in `f64` before the cast to `f32`, so it stays precise near `origin`
+    /// however far that lies from the world origin.
+    pub fn get_transform_relative(&self, origin: Vec2d) -> SrtTransform {
         SrtTransform {
-            translate: self.position(),
+            translate: (self.position - origin).to_glam(),
             rotate: self.rotation(),
             scale: Vec2::splat(self.size as f32),
         }
@@ -0,0 +1,28 @@
+use std::io::{self, Write};
+use crate::core::sim::SimulationState;
+
+impl SimulationState {
+    /// Column names matching the rows written by `export_snapshot_csv`.
+    pub const SNAPSHOT_CSV_HEADER: &'static str = "tick,uid,type,x,y,angle,energy,fat,speed";
+
+    /// Writes one CSV row per live cell, without a header.
+    /// Rows are prefixed with the current tick so repeated exports can share a file.
+    pub fn export_snapshot_csv(&self, mut writer: impl Write) -> io::Result<()> {
+        for cell in self.cells.flatten_iter() {
+            writeln!(
+                writer,
+                "{},{},{:?},{},{},{},{},{},{}",
+                self.tick_count(),
+                cell.uid.0,
+                cell.typ,
+                cell.position.x,
+                cell.position.y,
+                cell.angle,
+                cell.resources.energy(),
+                cell.resources.fat(),
+                cell.velocity.length(),
+            )?;
+        }
+        Ok(())
+    }
+}
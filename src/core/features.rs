@@ -1,10 +1,11 @@
 use crate::graphics::models::cpu::{Color, Primitive, ShapeDesc};
-use crate::graphics::models::space::SrtTransform;
-use glam::Vec2;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fmt;
 
 /// Represents the biological or functional type of a cell.
 /// Used for rendering and simulation classification.
-#[derive(Clone, Copy, Debug)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum CellType {
     Neural,
     Muscle,
@@ -16,6 +17,13 @@ pub enum CellType {
     Spore,
 }
 
+impl fmt::Display for CellType {
+    /// Writes the cell type's `name`.
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
 impl CellType {
     /// A static list of all possible cell types.
     pub const LIST: &'static [CellType] = &[
@@ -29,52 +37,103 @@ impl CellType {
         CellType::Spore,
     ];
 
-    /// Returns the visual membrane primitive used to render this cell type.
-    pub fn get_membrane_primitive(&self) -> Primitive {
-        // All primitives use default transform; only shape and color vary.
-        let default_transform = SrtTransform::default();
-
+    /// Returns the human-readable name of this cell type, for UI such as pickers and legends.
+    pub fn name(&self) -> &'static str {
         match self {
-            CellType::Neural => Primitive {
-                shape: ShapeDesc::Circle,
-                color: Color::BLUE,
-                transform: default_transform,
-            },
-            CellType::Muscle => Primitive {
-                shape: ShapeDesc::Hexagon,
-                color: Color::RED,
-                transform: default_transform,
-            },
-            CellType::Fat => Primitive {
-                shape: ShapeDesc::Pentagon,
-                color: Color::YELLOW,
-                transform: default_transform,
-            },
-            CellType::Liver => Primitive {
-                shape: ShapeDesc::Decagon,
-                color: Color::BROWN,
-                transform: default_transform,
-            },
-            CellType::Intestinal => Primitive {
-                shape: ShapeDesc::Triangle,
-                color: Color::GREEN,
-                transform: default_transform,
-            },
-            CellType::Kidney => Primitive {
-                shape: ShapeDesc::Heptagon,
-                color: Color::PURPLE,
-                transform: default_transform,
-            },
-            CellType::HairFollicle => Primitive {
-                shape: ShapeDesc::Triangle,
-                color: Color::BLACK,
-                transform: default_transform,
-            },
-            CellType::Spore => Primitive {
-                shape: ShapeDesc::Square,
-                color: Color::GRAY,
-                transform: default_transform,
-            },
+            CellType::Neural => "Neural",
+            CellType::Muscle => "Muscle",
+            CellType::Fat => "Fat",
+            CellType::Liver => "Liver",
+            CellType::Intestinal => "Intestinal",
+            CellType::Kidney => "Kidney",
+            CellType::HairFollicle => "Hair Follicle",
+            CellType::Spore => "Spore",
+        }
+    }
+
+    /// Returns the cell type with the given `name`, ignoring ASCII case.
+    pub fn from_name(name: &str) -> Option<CellType> {
+        Self::LIST.iter().copied().find(|typ| typ.name().eq_ignore_ascii_case(name))
+    }
+
+    /// Returns the physical and visual properties of this cell type.
+    pub fn properties(&self) -> CellProperties {
+        let (density, shape, color) = match self {
+            CellType::Neural => (1.20, ShapeDesc::Circle, Color::BLUE),
+            CellType::Muscle => (1.80, ShapeDesc::Hexagon, Color::RED),
+            CellType::Fat => (0.80, ShapeDesc::Pentagon, Color::YELLOW),
+            CellType::Liver => (1.40, ShapeDesc::Decagon, Color::BROWN),
+            CellType::Intestinal => (1.20, ShapeDesc::Triangle, Color::GREEN),
+            CellType::Kidney => (1.40, ShapeDesc::Heptagon, Color::PURPLE),
+            CellType::HairFollicle => (1.00, ShapeDesc::Triangle, Color::BLACK),
+            CellType::Spore => (1.60, ShapeDesc::Square, Color::GRAY),
+        };
+
+        CellProperties {
+            density,
+            size: 1.0,
+            color,
+            shape,
         }
     }
+
+    /// Returns the visual membrane primitive used to render this cell type,
+    /// with its color and shape taken from `theme` where it overrides them.
+    pub fn get_membrane_primitive(&self, theme: &ThemeConfig) -> Primitive {
+        let properties = self.properties();
+        Primitive::new(
+            theme.type_shapes.get(self).copied().unwrap_or(properties.shape),
+            theme.type_colors.get(self).copied().unwrap_or(properties.color),
+        )
+    }
+}
+
+/// Runtime overrides of how cell types are drawn.
+///
+/// Types missing from a map keep their `CellProperties` color or shape.
+/// Only rendering is affected; collisions still use the type's own shape.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ThemeConfig {
+    pub type_colors: HashMap<CellType, Color>,
+    pub type_shapes: HashMap<CellType, ShapeDesc>,
+}
+
+impl ThemeConfig {
+    /// Returns a theme using the Okabe-Ito palette, which stays distinct under common color blindness,
+    /// with every type given a different shape as well.
+    pub fn colorblind() -> Self {
+        let type_colors = HashMap::from([
+            (CellType::Neural, Color::rgb(86, 180, 233)),
+            (CellType::Muscle, Color::rgb(213, 94, 0)),
+            (CellType::Fat, Color::rgb(240, 228, 66)),
+            (CellType::Liver, Color::rgb(230, 159, 0)),
+            (CellType::Intestinal, Color::rgb(0, 158, 115)),
+            (CellType::Kidney, Color::rgb(204, 121, 167)),
+            (CellType::HairFollicle, Color::rgb(0, 114, 178)),
+            (CellType::Spore, Color::rgb(200, 200, 200)),
+        ]);
+        // Intestinal and hair follicle cells are both triangles by default.
+        let type_shapes = HashMap::from([(CellType::HairFollicle, ShapeDesc::Octagon)]);
+
+        Self { type_colors, type_shapes }
+    }
+
+    /// Parses a theme from a RON string, such as `(type_colors: {Neural: (r: 0, g: 114, b: 178, a: 255)})`.
+    pub fn from_ron(source: &str) -> Result<ThemeConfig, ron::error::SpannedError> {
+        ron::from_str(source)
+    }
+}
+
+/// Per-type metadata describing how a cell behaves physically and how it is drawn.
+#[derive(Clone, Copy, Debug)]
+pub struct CellProperties {
+    /// Mass per unit area of the cell body.
+    pub density: f64,
+    /// Default size (diameter) of a newly created cell, twice its `Cell::radius`.
+    pub size: f64,
+    /// Base membrane color.
+    pub color: Color,
+    /// Membrane shape.
+    pub shape: ShapeDesc,
 }
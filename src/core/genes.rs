@@ -1,10 +1,16 @@
+use super::elements::{Cell, CellId};
 use super::features::CellType;
+use super::sim::{SimulationState, SpawnError};
+use crate::utils::vector::Vec2d;
+use serde::{Deserialize, Serialize};
+use std::f64::consts::{PI, TAU};
 
 /// Placeholder for a full genetic code structure.
 struct GeneticCode {}
 
 /// Represents a single gene, which may branch into other genes (stems).
 /// Conceptually forms a tree structure, where leaves represent terminal cell types.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub struct Gene {
     pub stems: Vec<Gene>,
     pub typ: CellType,
@@ -18,4 +24,69 @@ impl Gene {
             typ,
         }
     }
-}
\ No newline at end of file
+
+    /// Serializes the gene tree into a human-readable RON string.
+    pub fn to_ron(&self) -> String {
+        ron::ser::to_string_pretty(self, ron::ser::PrettyConfig::default())
+            .expect("Gene tree should always be serializable")
+    }
+
+    /// Parses a gene tree from a RON string.
+    pub fn from_ron(source: &str) -> Result<Gene, ron::error::SpannedError> {
+        ron::from_str(source)
+    }
+}
+
+impl SimulationState {
+    /// Grows an organism from a gene tree, placing the root cell at `root_pos`.
+    ///
+    /// Each stem is placed `radius` away from its parent. The root's stems are spread
+    /// evenly over `TAU`; deeper stems split their parent's arc, at most a half-plane facing away from it.
+    /// Stems deeper than `max_depth` are not grown. Returns the root cell's ID.
+    ///
+    /// If the population reaches `max_cells`, growth stops with `PopulationFull`,
+    /// keeping the cells grown so far.
+    pub fn grow_from_gene(
+        &mut self,
+        root_pos: Vec2d,
+        gene: &Gene,
+        max_depth: usize,
+        radius: f64,
+    ) -> Result<CellId, SpawnError> {
+        let root = self.spawn(Cell::new(root_pos, gene.typ))?;
+        self.grow_stems(root, gene, 0.0, TAU, max_depth, radius)?;
+        Ok(root)
+    }
+
+    /// Places the stems of `gene` around `parent` within the arc of width `arc` centered on `heading`.
+    fn grow_stems(
+        &mut self,
+        parent: CellId,
+        gene: &Gene,
+        heading: f64,
+        arc: f64,
+        depth: usize,
+        radius: f64,
+    ) -> Result<(), SpawnError> {
+        if depth == 0 || gene.stems.is_empty() {
+            return Ok(());
+        }
+
+        let step = arc / gene.stems.len() as f64;
+        let parent_pos = self.cells.get(parent).position;
+
+        for (i, stem) in gene.stems.iter().enumerate() {
+            // Center each stem in its share of the arc.
+            let angle = heading - arc * 0.5 + step * (i as f64 + 0.5);
+            let pos = parent_pos + Vec2d::from_angle(angle) * radius;
+
+            let child = self.spawn(Cell::new(pos, stem.typ))?;
+            self.connect_auto(parent, child)
+                .expect("Freshly spawned cells should always connect");
+
+            // Limit the arc to a half-plane so grandchildren never fold back onto the parent.
+            self.grow_stems(child, stem, angle, step.min(PI), depth - 1, radius)?;
+        }
+        Ok(())
+    }
+}
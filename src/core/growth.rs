@@ -0,0 +1,39 @@
+use super::resources::Fat;
+use super::sim::SimulationState;
+
+/// Stored fat a cell keeps in reserve; only the surplus above it feeds growth.
+pub const GROWTH_FAT_THRESHOLD: Fat = 1.0;
+
+/// Size gained per second for each unit of surplus fat.
+pub const GROWTH_RATE: f64 = 0.05;
+
+/// Fat consumed per unit of size gained.
+pub const FAT_PER_SIZE: Fat = 10.0;
+
+/// Largest size a cell can grow to, as a multiple of its type's default size.
+pub const MAX_GROWTH_FACTOR: f64 = 2.0;
+
+impl SimulationState {
+    /// Grows every cell holding fat above `GROWTH_FAT_THRESHOLD`, at a rate proportional
+    /// to the surplus and capped at `MAX_GROWTH_FACTOR` times its type's size.
+    /// Growth is paid for with stored fat, and connection rest lengths follow the new size.
+    pub fn growth_pass(&mut self, dt: f64) {
+        for cell in self.cells.flatten_iter_mut() {
+            let surplus = cell.resources.fat() - GROWTH_FAT_THRESHOLD;
+            if surplus <= 0.0 {
+                continue;
+            }
+
+            let max_size = cell.typ.properties().size * MAX_GROWTH_FACTOR;
+            let growth = (GROWTH_RATE * f64::from(surplus) * dt)
+                .min(max_size - cell.size)
+                .min(f64::from(surplus / FAT_PER_SIZE));
+            if growth <= 0.0 {
+                continue;
+            }
+
+            cell.resources.consume_fat(growth as Fat * FAT_PER_SIZE);
+            cell.set_size(cell.size + growth);
+        }
+    }
+}
@@ -1,6 +1,10 @@
+pub mod behaviors;
 pub mod elements;
+pub mod export;
 pub mod features;
 pub mod genes;
+pub mod growth;
 pub mod physics;
 pub mod sim;
-mod resources;
+pub mod resources;
+pub mod signals;
@@ -1,58 +1,187 @@
-use crate::core::elements::{Cell, CellConnection};
-use crate::core::sim::SimulationState;
-use crate::physics::forces::{ForceApplier, ForceAppl, Lever, LinearSpring};
+use crate::core::elements::{Cell, CellId, ConnectionKind};
+use crate::core::sim::{SimContext, SimulationState};
+use crate::physics::collision::collide;
+use crate::physics::forces::{ForceApplier, ForceAppl, Lever, LinearSpring, Rope};
 use crate::utils::vector::Vec2d;
 
+/// Largest force a connection spring may apply, keeping the integrator stable
+/// when connected cells start far from their rest length.
+const MAX_SPRING_FORCE: f64 = 500.0;
+
+/// Torque per radian pushing a joint bent past its angle limits back into range.
+const JOINT_LIMIT_STIFFNESS: f64 = 200.0;
+
 impl SimulationState {
-    /// Performs one physics step for the entire simulation.
-    /// Applies spring constraints, viscous damping, and integrates cell motion.
+    /// Performs one physics step for the entire simulation,
+    /// split into `spring_iterations` equal sub-steps.
+    ///
+    /// Deterministic: connections are visited in `Vec` order and cells in slot order,
+    /// so the same state always produces bit-identical results. Any parallel force
+    /// accumulation must keep this by summing each cell's contributions in connection order.
     pub fn physics_pass(&mut self, dt: f64) {
+        let iterations = self.context.spring_iterations.max(1);
+        let sub_dt = dt / iterations as f64;
+        for _ in 0..iterations {
+            self.physics_sub_step(sub_dt);
+        }
+    }
+
+    /// Applies spring constraints, viscous damping, and integrates cell motion over `dt`.
+    fn physics_sub_step(&mut self, dt: f64) {
         // Apply spring forces between all connected cell pairs.
-        for connection in self.connections.iter() {
-            let (cell_a, cell_b) = self
-                .cells
-                .get_mut_pair(connection.id_a, connection.id_b);
-
-            // Primary spring connects the cell centers.
-            LinearSpring {
-                length: 2.0,
-                k: 50.0,
-            }
-                .tick(cell_a, cell_b);
+        let mut found_stale = false;
+        for i in 0..self.connections.len() {
+            let connection = self.connections[i].clone();
+            let Some((cell_a, cell_b)) = self.connection_pair(i) else {
+                found_stale = true;
+                continue;
+            };
+
+            match connection.kind {
+                ConnectionKind::Spring => {
+                    // Springs are damped relative to critical damping for the pair's reduced mass.
+                    let reduced_mass = cell_a.mass * cell_b.mass / (cell_a.mass + cell_b.mass);
+
+                    // Primary spring connects the cell centers, resting with their edges touching.
+                    LinearSpring::damped(connection.rest_length(cell_a, cell_b), connection.primary_k, reduced_mass, connection.damping_ratio)
+                        .with_max_force(MAX_SPRING_FORCE)
+                        .tick(cell_a, cell_b);
 
-            // Secondary spring connects the edge points (angled offset from center).
-            LinearSpring {
-                length: 0.0,
-                k: 50.0,
+                    // Secondary spring connects the edge points (angled offset from center).
+                    LinearSpring::damped(0.0, connection.edge_k, reduced_mass, connection.damping_ratio)
+                        .with_max_force(MAX_SPRING_FORCE)
+                        .tick(
+                            &mut cell_a.edge_lever(connection.angle_a),
+                            &mut cell_b.edge_lever(connection.angle_b),
+                        );
+                }
+                ConnectionKind::Rope => {
+                    // Ropes tie the centers only, leaving both cells free to turn.
+                    Rope { length: connection.rest_length(cell_a, cell_b), k: connection.primary_k }.tick(cell_a, cell_b);
+                }
             }
-                .tick(
-                    &mut cell_a.edge_lever(connection.angle_a),
-                    &mut cell_b.edge_lever(connection.angle_b),
-                );
+
+            // One-sided torsional spring, only acting while the joint is outside its limits.
+            let torque = JOINT_LIMIT_STIFFNESS * connection.joint_limit_excess(cell_a, cell_b);
+            cell_a.apply_torque(torque);
+            cell_b.apply_torque(-torque);
+        }
+
+        if found_stale {
+            self.prune_stale_connections();
+        }
+
+        self.collision_pass();
+
+        if let Some(gravity) = &mut self.context.gravity {
+            gravity.apply_all(&mut self.cells);
         }
 
         // Apply viscous drag and update physics state for each cell.
         for cell in self.cells.flatten_iter_mut() {
-            apply_viscous_force(cell, self.context.viscosity);
+            apply_viscous_force(cell, &self.context);
             cell.apply_force_integrate(dt);
         }
     }
+
+    /// Pushes apart every pair of unconnected cells whose membrane shapes overlap.
+    /// Does nothing unless `collision_stiffness` is positive.
+    ///
+    /// Close pairs come from `close_pairs` and are confirmed by GJK on their collision shapes,
+    /// built only for those pairs. The push itself is radius-based: it grows with how far the
+    /// cells' circumscribed circles overlap, not the shapes' true penetration, and acts along
+    /// the line between their centers. Connected cells are left to their springs.
+    fn collision_pass(&mut self) {
+        if self.context.collision_stiffness <= 0.0 {
+            return;
+        }
+
+        let reach = self.cells.flatten_iter().map(|cell| cell.size).fold(0.0, f64::max);
+        let colliding: Vec<(CellId, CellId)> = self
+            .close_pairs(reach)
+            .into_iter()
+            .filter(|&(a, b)| !self.neighbors(a).any(|(other, _)| other == b))
+            .filter(|&(a, b)| collide(&self.cells.get(a).collision_shape(), &self.cells.get(b).collision_shape()))
+            .collect();
+
+        for (a, b) in colliding {
+            let (cell_a, cell_b) = self.cells.get_mut_pair(a, b);
+            let delta = cell_b.position - cell_a.position;
+            let distance = delta.length();
+            let depth = cell_a.radius() + cell_b.radius() - distance;
+            // Cells on the same spot have no direction to separate in.
+            if distance == 0.0 || depth <= 0.0 {
+                continue;
+            }
+
+            let force = delta * (self.context.collision_stiffness * depth / distance);
+            cell_a.apply_force(-force);
+            cell_b.apply_force(force);
+        }
+    }
+
+    /// Returns the summed kinetic energy of all cells.
+    pub fn total_kinetic_energy(&self) -> f64 {
+        self.cells.flatten_iter().map(Cell::kinetic_energy).sum()
+    }
+
+    /// Returns the elastic energy stored in the connection springs and bent joint limits,
+    /// ignoring force clamping and connections to freed cells.
+    pub fn total_potential_energy(&self) -> f64 {
+        self.connections
+            .iter()
+            .filter_map(|connection| {
+                let cell_a = self.cells.get_checked(connection.id_a)?;
+                let cell_b = self.cells.get_checked(connection.id_b)?;
+
+                let center_stretch = cell_a.position.distance(cell_b.position) - connection.rest_length(cell_a, cell_b);
+                let (center_stretch, edge_stretch) = match connection.kind {
+                    ConnectionKind::Spring => (
+                        center_stretch,
+                        cell_a
+                            .edge_point(connection.angle_a)
+                            .distance(cell_b.edge_point(connection.angle_b)),
+                    ),
+                    // A slack rope stores nothing.
+                    ConnectionKind::Rope => (center_stretch.max(0.0), 0.0),
+                };
+
+                let excess = connection.joint_limit_excess(cell_a, cell_b);
+
+                Some(
+                    0.5 * connection.primary_k * center_stretch * center_stretch
+                        + 0.5 * connection.edge_k * edge_stretch * edge_stretch
+                        + 0.5 * JOINT_LIMIT_STIFFNESS * excess * excess,
+                )
+            })
+            .sum()
+    }
 }
 
 /// Applies viscous damping force and torque based on velocity and angular velocity.
-fn apply_viscous_force(cell: &mut Cell, viscosity: f64) {
-    let force = -cell.velocity * cell.size * viscosity;
-    let torque = -cell.angular_velocity * cell.size * viscosity;
+///
+/// Angular drag scales with the cell's angular inertia, so rotation decays
+/// at a rate set by `angular_viscosity` regardless of cell size.
+fn apply_viscous_force(cell: &mut Cell, context: &SimContext) {
+    let force = -cell.velocity * cell.size * context.linear_viscosity;
+    let torque = -cell.angular_velocity * cell.angular_inertia * context.angular_viscosity;
 
     cell.apply_force(force);
     cell.apply_torque(torque);
 }
 
 impl Cell {
+    /// Returns the kinetic energy of the cell's translation and rotation.
+    pub fn kinetic_energy(&self) -> f64 {
+        let linear = 0.5 * self.mass * self.velocity.dot(self.velocity);
+        let angular = 0.5 * self.angular_inertia * self.angular_velocity * self.angular_velocity;
+        linear + angular
+    }
+
     /// Returns a lever arm from the center of mass to a rotated edge point on the cell.
+    /// Use `edge_point` when only the position is needed, e.g. while iterating cells immutably.
     pub fn edge_lever(&mut self, angle: f64) -> Lever<Self> {
-        let direction = Vec2d::from_angle(self.angle + angle);
-        let application = direction * self.size * 0.5;
+        let application = self.edge_offset(angle);
 
         Lever {
             body: self,
@@ -69,8 +198,10 @@ impl Cell {
         // Angular motion
         self.angular_velocity += self.torque * dt / self.angular_inertia;
         self.angle += self.angular_velocity * dt;
+        self.normalize_angle();
 
         // Reset accumulated forces and torque
+        self.last_force = self.force;
         self.force = Vec2d::ZERO;
         self.torque = 0.0;
     }
@@ -2,10 +2,10 @@ use std::ops::Sub;
 use crate::core::sim::SimulationState;
 
 /// Type alias representing units of energy (abstract scale).
-type Energy = f32;
+pub type Energy = f32;
 
 /// Type alias representing units of stored fat (abstract scale).
-type Fat = f32;
+pub type Fat = f32;
 
 /// Represents localized, shareable resources stored in a cell.
 #[derive(Clone, Copy, Debug, Default)]
@@ -14,6 +14,38 @@ pub struct LocalResources {
     fat: Fat,
 }
 
+impl LocalResources {
+    /// Energy a newly created cell starts with, at which it is fully healthy.
+    pub const MAX_ENERGY: Energy = 100.0;
+
+    /// Creates a resource set holding the given amounts.
+    pub fn new(energy: Energy, fat: Fat) -> Self {
+        Self { energy, fat }
+    }
+
+    /// Returns the stored energy.
+    pub fn energy(&self) -> Energy {
+        self.energy
+    }
+
+    /// Returns the stored energy as a fraction of `MAX_ENERGY`, clamped to `[0, 1]`.
+    pub fn health(&self) -> f32 {
+        (self.energy / Self::MAX_ENERGY).clamp(0.0, 1.0)
+    }
+
+    /// Returns the stored fat.
+    pub fn fat(&self) -> Fat {
+        self.fat
+    }
+
+    /// Removes up to `amount` of stored fat, returning how much was actually taken.
+    pub fn consume_fat(&mut self, amount: Fat) -> Fat {
+        let taken = amount.clamp(0.0, self.fat.max(0.0));
+        self.fat -= taken;
+        taken
+    }
+}
+
 impl Sub for LocalResources {
     type Output = Self;
 
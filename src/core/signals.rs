@@ -0,0 +1,61 @@
+use super::elements::CellId;
+use super::features::CellType;
+use super::sim::SimulationState;
+
+/// Signal level a neural cell must exceed before it passes the signal on.
+pub const SIGNAL_THRESHOLD: f32 = 0.1;
+
+/// Rate at which a firing neural cell pushes signal down the gradient to each neighbor, per second.
+const SIGNAL_TRANSMISSION: f32 = 8.0;
+
+/// Rate at which every cell's signal decays, per second.
+const SIGNAL_DECAY: f32 = 1.0;
+
+impl SimulationState {
+    /// Adds `amount` of signal to a cell, e.g. to stimulate a neural cell.
+    /// Does nothing if `id` doesn't refer to a live cell.
+    pub fn inject_signal(&mut self, id: CellId, amount: f32) {
+        if let Some(cell) = self.cells.get_mut_checked(id) {
+            cell.signal += amount;
+        }
+    }
+
+    /// Propagates signals outward from neural cells, then decays all signals.
+    ///
+    /// A neural cell above `SIGNAL_THRESHOLD` transfers signal to each connected cell
+    /// holding less, in proportion to the difference, so signals flow away from their
+    /// source instead of bouncing back. Only neural cells relay what they receive.
+    pub fn signal_pass(&mut self, dt: f64) {
+        let dt = dt as f32;
+        let mut deltas = vec![0.0_f32; self.cells.capacity()];
+
+        for connection in self.connections.iter() {
+            let (id_a, id_b) = (connection.id_a, connection.id_b);
+            // Stale connections are pruned by `physics_pass`.
+            if !self.cells.is_initialized(id_a) || !self.cells.is_initialized(id_b) {
+                continue;
+            }
+            let (cell_a, cell_b) = (self.cells.get(id_a), self.cells.get(id_b));
+
+            // Orient the connection from the stronger signal to the weaker one.
+            let (from, to, strong, weak) = if cell_a.signal >= cell_b.signal {
+                (id_a, id_b, cell_a, cell_b)
+            } else {
+                (id_b, id_a, cell_b, cell_a)
+            };
+
+            if strong.typ != CellType::Neural || strong.signal <= SIGNAL_THRESHOLD {
+                continue;
+            }
+
+            let transfer = (strong.signal - weak.signal) * (SIGNAL_TRANSMISSION * dt).min(0.5);
+            deltas[from] -= transfer;
+            deltas[to] += transfer;
+        }
+
+        let decay = (-SIGNAL_DECAY * dt).exp();
+        for (id, cell) in self.cells.iter_indexed_mut() {
+            cell.signal = ((cell.signal + deltas[id]) * decay).max(0.0);
+        }
+    }
+}
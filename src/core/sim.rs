@@ -1,9 +1,157 @@
-use super::elements::{Cell, CellConnection, CellId};
-use crate::utils::data::Heap;
+use super::elements::{Cell, CellConnection, CellId, ConnectionKind, Uid};
+use crate::graphics::models::space::AABB;
+use crate::physics::forces::GravityField;
+use crate::utils::algorithms::CSR;
+use crate::utils::data::{Heap, IdxPair};
+use crate::utils::grid::SpatialHash;
+use crate::utils::vector::{wrap_angle, Vec2d};
+use std::cell::OnceCell;
+use std::collections::HashMap;
 
 /// Stores global simulation parameters.
+#[derive(Clone)]
 pub struct SimContext {
-    pub viscosity: f64,
+    /// Drag coefficient opposing linear motion, scaled by cell size.
+    pub linear_viscosity: f64,
+    /// Drag coefficient opposing rotation, scaled by each cell's angular inertia.
+    pub angular_viscosity: f64,
+    /// Physics sub-steps per tick, each advancing `dt / spring_iterations`.
+    ///
+    /// More sub-steps keep stiff springs stable at larger `dt`, but the whole physics
+    /// pass runs once per sub-step, so its cost grows linearly with this value.
+    pub spring_iterations: usize,
+    /// Mutual attraction between all cells, if enabled.
+    pub gravity: Option<GravityField>,
+    /// Force per unit of overlap pushing apart unconnected cells whose shapes intersect; zero disables collisions.
+    pub collision_stiffness: f64,
+    /// Carrying capacity: the most live cells allowed at once, or `None` for no limit.
+    pub max_cells: Option<usize>,
+    /// Whether cells whose state turns NaN or infinite are reset at the start of each tick,
+    /// before springs spread the bad values to their neighbors.
+    pub quarantine: bool,
+}
+
+impl Default for SimContext {
+    /// Returns moderate viscosity, one physics sub-step, no gravity,
+    /// collisions off, and no population limit.
+    fn default() -> Self {
+        Self {
+            linear_viscosity: 25.0,
+            angular_viscosity: 50.0,
+            spring_iterations: 1,
+            gravity: None,
+            collision_stiffness: 0.0,
+            max_cells: None,
+            quarantine: false,
+        }
+    }
+}
+
+impl SimContext {
+    /// Angular drag per unit of linear drag in the defaults, kept when viscosity is changed at runtime.
+    pub const ANGULAR_VISCOSITY_RATIO: f64 = 2.0;
+
+    /// Starts building a context from the defaults.
+    pub fn builder() -> SimContextBuilder {
+        SimContextBuilder::default()
+    }
+}
+
+/// Builds a `SimContext`, leaving every parameter not set at its default.
+#[derive(Default)]
+pub struct SimContextBuilder {
+    context: SimContext,
+}
+
+impl SimContextBuilder {
+    /// Sets the drag coefficients opposing linear and angular motion.
+    pub fn viscosity(mut self, linear: f64, angular: f64) -> Self {
+        self.context.linear_viscosity = linear;
+        self.context.angular_viscosity = angular;
+        self
+    }
+
+    /// Sets the number of physics sub-steps per tick.
+    pub fn spring_iterations(mut self, spring_iterations: usize) -> Self {
+        self.context.spring_iterations = spring_iterations;
+        self
+    }
+
+    /// Enables mutual attraction between all cells.
+    pub fn gravity(mut self, gravity: GravityField) -> Self {
+        self.context.gravity = Some(gravity);
+        self
+    }
+
+    /// Sets the force per unit of overlap pushing colliding cells apart; zero disables collisions.
+    pub fn collision_stiffness(mut self, collision_stiffness: f64) -> Self {
+        self.context.collision_stiffness = collision_stiffness;
+        self
+    }
+
+    /// Limits the number of live cells.
+    pub fn max_cells(mut self, max_cells: usize) -> Self {
+        self.context.max_cells = Some(max_cells);
+        self
+    }
+
+    /// Resets cells whose state turns non-finite instead of letting them run.
+    pub fn quarantine(mut self, enabled: bool) -> Self {
+        self.context.quarantine = enabled;
+        self
+    }
+
+    /// Returns the built context.
+    pub fn build(self) -> SimContext {
+        self.context
+    }
+}
+
+/// Reasons a connection between two cells can be rejected.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConnectError {
+    /// The given cell ID does not refer to a live cell.
+    MissingCell(CellId),
+    /// Both ends of the connection refer to the same cell.
+    SelfConnection(CellId),
+    /// The two cells are already connected.
+    AlreadyConnected,
+    /// The two cells already belong to the same connected organism.
+    SameComponent,
+}
+
+/// Reasons spawning cells can be rejected.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SpawnError {
+    /// Spawning would take the live cell count past `SimContext::max_cells`.
+    PopulationFull,
+}
+
+/// Notable changes to a simulation, reported to subscribed listeners.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SimEvent {
+    /// A cell was added to the simulation.
+    Spawned { id: CellId, uid: Uid },
+    /// A cell was removed from the simulation.
+    Removed { id: CellId, uid: Uid },
+    /// Two cells were connected.
+    Connected { a: CellId, b: CellId },
+    /// The connection between two cells was removed.
+    Disconnected { a: CellId, b: CellId },
+    /// A cell with non-finite state was reset to a safe state.
+    Quarantined { id: CellId, uid: Uid },
+    /// A cell's state turned NaN or infinite while quarantine is disabled.
+    NonFinite { id: CellId, uid: Uid },
+}
+
+/// Callback invoked for each `SimEvent`.
+pub type SimListener = Box<dyn FnMut(&SimEvent) + Send>;
+
+/// A copy of the cells and connections of a simulation at one point in time.
+#[derive(Clone)]
+pub struct SimulationSnapshot {
+    cells: Heap<Cell>,
+    connections: Vec<CellConnection>,
 }
 
 /// Represents the state of the simulation, including all cells and their connections.
@@ -11,6 +159,47 @@ pub struct SimulationState {
     pub context: SimContext,
     pub cells: Heap<Cell>,
     pub connections: Vec<CellConnection>,
+
+    /// Snapshot restored by `reset`, if one has been saved.
+    initial: Option<SimulationSnapshot>,
+
+    /// Number of ticks advanced since creation or the last reset.
+    tick_count: u64,
+    /// Simulated seconds elapsed since creation or the last reset.
+    sim_time: f64,
+
+    /// Callbacks notified of every `SimEvent`.
+    listeners: Vec<SimListener>,
+
+    /// Lookup from stable cell IDs to their current heap slots.
+    uid_index: HashMap<Uid, CellId>,
+    /// Connections touching each cell, built on demand by `neighbors`
+    /// and cleared whenever connections are added, removed, or remapped.
+    adjacency: OnceCell<CSR>,
+    /// Next stable ID handed out by `spawn`.
+    next_uid: u64,
+    /// Non-finite cells found by the last validation, so each is reported only once.
+    invalid: Vec<CellId>,
+}
+
+impl Clone for SimulationState {
+    /// Copies the whole simulation except its listeners, which can't be cloned;
+    /// the copy starts with none subscribed and rebuilds its caches on demand.
+    fn clone(&self) -> Self {
+        Self {
+            context: self.context.clone(),
+            cells: self.cells.clone(),
+            connections: self.connections.clone(),
+            initial: self.initial.clone(),
+            tick_count: self.tick_count,
+            sim_time: self.sim_time,
+            listeners: Vec::new(),
+            uid_index: self.uid_index.clone(),
+            adjacency: OnceCell::new(),
+            next_uid: self.next_uid,
+            invalid: self.invalid.clone(),
+        }
+    }
 }
 
 impl SimulationState {
@@ -20,27 +209,565 @@ impl SimulationState {
             context,
             cells: Heap::with_capacity(100),
             connections: Vec::with_capacity(100),
+            initial: None,
+            tick_count: 0,
+            sim_time: 0.0,
+            listeners: Vec::new(),
+            uid_index: HashMap::new(),
+            adjacency: OnceCell::new(),
+            next_uid: 1,
+            invalid: Vec::new(),
         }
     }
 
+    /// Registers a callback to be invoked for every subsequent `SimEvent`.
+    pub fn subscribe(&mut self, listener: impl FnMut(&SimEvent) + Send + 'static) {
+        self.listeners.push(Box::new(listener));
+    }
+
+    /// Notifies all listeners of an event.
+    fn emit(&mut self, event: SimEvent) {
+        for listener in self.listeners.iter_mut() {
+            listener(&event);
+        }
+    }
+
+    /// Adds a cell to the simulation, assigning it a fresh `Uid`. Returns its heap slot.
+    /// Fails without changing the simulation if the population is at `max_cells`.
+    pub fn spawn(&mut self, cell: Cell) -> Result<CellId, SpawnError> {
+        self.spawn_vec(vec![cell])
+    }
+
+    /// Adds cells in contiguous heap slots, assigning each a fresh `Uid`.
+    /// Returns the slot of the first cell. Fails without spawning any of them
+    /// if they would take the population past `max_cells`.
+    pub fn spawn_vec(&mut self, mut cells: Vec<Cell>) -> Result<CellId, SpawnError> {
+        if let Some(max_cells) = self.context.max_cells
+            && self.cells.len() + cells.len() > max_cells
+        {
+            return Err(SpawnError::PopulationFull);
+        }
+
+        for cell in cells.iter_mut() {
+            cell.uid = Uid(self.next_uid);
+            self.next_uid += 1;
+        }
+
+        let uids: Vec<Uid> = cells.iter().map(|cell| cell.uid).collect();
+        let start = self.cells.insert_alloc_vec(cells);
+        for (offset, uid) in uids.into_iter().enumerate() {
+            self.uid_index.insert(uid, start + offset);
+            self.emit(SimEvent::Spawned { id: start + offset, uid });
+        }
+        Ok(start)
+    }
+
+    /// Returns the current heap slot of the cell with the given stable ID.
+    pub fn id_of(&self, uid: Uid) -> Option<CellId> {
+        self.uid_index.get(&uid).copied()
+    }
+
+    /// Returns the cell with the given stable ID, if it is still alive.
+    pub fn by_uid(&self, uid: Uid) -> Option<&Cell> {
+        self.id_of(uid).and_then(|id| self.cells.get_checked(id))
+    }
+
+    /// Moves all cells to the front of the heap and remaps connections and stable IDs.
+    /// Connections to freed cells are dropped first, as they have nowhere to be remapped to.
+    pub fn compact(&mut self) {
+        self.prune_stale_connections();
+        let remap = self.cells.compact();
+
+        for connection in self.connections.iter_mut() {
+            connection.id_a = remap[connection.id_a].expect("Connection to a freed cell");
+            connection.id_b = remap[connection.id_b].expect("Connection to a freed cell");
+        }
+        self.invalidate_adjacency();
+        self.rebuild_uid_index();
+    }
+
+    /// Rebuilds the stable ID lookup from the cells currently in the heap.
+    fn rebuild_uid_index(&mut self) {
+        self.uid_index.clear();
+        for (id, cell) in self.cells.iter_indexed() {
+            self.uid_index.insert(cell.uid, id);
+        }
+    }
+
+    /// Captures the current cells and connections.
+    pub fn snapshot(&self) -> SimulationSnapshot {
+        SimulationSnapshot {
+            cells: self.cells.clone(),
+            connections: self.connections.clone(),
+        }
+    }
+
+    /// Replaces the current cells and connections with those of `snapshot`,
+    /// reusing existing allocations where possible.
+    pub fn restore(&mut self, snapshot: &SimulationSnapshot) {
+        self.cells.clone_from(&snapshot.cells);
+        self.connections.clone_from(&snapshot.connections);
+        self.invalidate_adjacency();
+        self.rebuild_uid_index();
+    }
+
+    /// Stores the current state as the one `reset` returns to.
+    pub fn save_initial(&mut self) {
+        self.initial = Some(self.snapshot());
+    }
+
+    /// Restores the state saved by `save_initial` and zeroes the tick count and time.
+    /// Does nothing if no initial state has been saved.
+    pub fn reset(&mut self) {
+        if let Some(initial) = self.initial.take() {
+            self.restore(&initial);
+            self.initial = Some(initial);
+            self.tick_count = 0;
+            self.sim_time = 0.0;
+        }
+    }
+
+    /// Returns the number of ticks advanced since creation or the last reset.
+    pub fn tick_count(&self) -> u64 {
+        self.tick_count
+    }
+
+    /// Returns the simulated seconds elapsed since creation or the last reset.
+    pub fn sim_time(&self) -> f64 {
+        self.sim_time
+    }
+
+    /// Returns the drag coefficient currently opposing linear motion.
+    pub fn viscosity(&self) -> f64 {
+        self.context.linear_viscosity
+    }
+
+    /// Sets the drag coefficient opposing linear motion, and the one opposing rotation
+    /// at `SimContext::ANGULAR_VISCOSITY_RATIO` times it, taking effect from the next tick.
+    /// Negative and NaN values are clamped to zero.
+    pub fn set_viscosity(&mut self, viscosity: f64) {
+        self.context.linear_viscosity = viscosity.max(0.0);
+        self.context.angular_viscosity = self.context.linear_viscosity * SimContext::ANGULAR_VISCOSITY_RATIO;
+    }
+
     /// Removes a cell from the simulation by its ID.
     /// Also removes all connections that include the removed cell.
     pub fn remove(&mut self, id: CellId) {
+        if self.cells.is_initialized(id) {
+            let uid = self.cells.get(id).uid;
+            self.uid_index.remove(&uid);
+            self.emit(SimEvent::Removed { id, uid });
+        }
         self.cells.free(id);
+        self.disconnect_all(id);
+    }
 
-        // Efficiently remove all connections pointing to the removed cell.
+    /// Removes every connection of a cell, leaving the cell itself in place.
+    /// Returns the number of connections removed.
+    pub fn disconnect_all(&mut self, id: CellId) -> usize {
+        // Walk backwards so `swap_remove` only moves connections already checked.
+        let mut removed_count = 0;
         let mut i = self.connections.len();
         while i > 0 {
             i -= 1;
             if self.connections[i].points_toward(id) {
+                let removed = self.connections.swap_remove(i);
+                self.invalidate_adjacency();
+                self.emit(SimEvent::Disconnected { a: removed.id_a, b: removed.id_b });
+                removed_count += 1;
+            }
+        }
+        removed_count
+    }
+
+    /// Connects two live cells at the given edge angles.
+    /// Fails if either cell is missing, both IDs are the same, or the pair is already connected.
+    pub fn connect(
+        &mut self,
+        a: CellId,
+        angle_a: f64,
+        b: CellId,
+        angle_b: f64,
+    ) -> Result<(), ConnectError> {
+        self.connect_with(CellConnection::new(a, angle_a, b, angle_b))
+    }
+
+    /// Adds a connection built with its own tunables, such as `CellConnection::with_stiffness`.
+    /// Fails like `connect` if its cells are missing, the same, or already connected.
+    pub fn connect_with(&mut self, connection: CellConnection) -> Result<(), ConnectError> {
+        let (a, b) = (connection.id_a, connection.id_b);
+        if a == b {
+            return Err(ConnectError::SelfConnection(a));
+        }
+        for id in [a, b] {
+            if !self.cells.is_initialized(id) {
+                return Err(ConnectError::MissingCell(id));
+            }
+        }
+        if self.connections.iter().any(|c| c.links(a, b)) {
+            return Err(ConnectError::AlreadyConnected);
+        }
+
+        self.connections.push(connection);
+        self.invalidate_adjacency();
+        self.emit(SimEvent::Connected { a, b });
+        Ok(())
+    }
+
+    /// Connects two live cells at the edge points facing each other,
+    /// deriving both angles in `[-PI, PI)` from their current positions and rotations.
+    pub fn connect_auto(&mut self, a: CellId, b: CellId) -> Result<(), ConnectError> {
+        for id in [a, b] {
+            if !self.cells.is_initialized(id) {
+                return Err(ConnectError::MissingCell(id));
+            }
+        }
+
+        let (cell_a, cell_b) = (self.cells.get(a), self.cells.get(b));
+        let direction = cell_b.position - cell_a.position;
+        let angle_a = wrap_angle(direction.angle() - cell_a.angle);
+        let angle_b = wrap_angle((-direction).angle() - cell_b.angle);
+
+        self.connect(a, angle_a, b, angle_b)
+    }
+
+    /// Joins the organisms of two cells by connecting them at the edge points facing each other.
+    /// Fails with `ConnectError::SameComponent` if the cells are already linked, directly or not.
+    pub fn merge(&mut self, a: CellId, b: CellId) -> Result<(), ConnectError> {
+        if a == b {
+            return Err(ConnectError::SelfConnection(a));
+        }
+        for id in [a, b] {
+            if !self.cells.is_initialized(id) {
+                return Err(ConnectError::MissingCell(id));
+            }
+        }
+        if self.component_of(a).binary_search(&b).is_ok() {
+            return Err(ConnectError::SameComponent);
+        }
+
+        self.connect_auto(a, b)
+    }
+
+    /// Returns the cells connected to `id`, directly or through others, including `id`, ascending.
+    pub fn component_of(&self, id: CellId) -> Vec<CellId> {
+        // Connections to freed cells, left until the next prune, no longer link anything.
+        let pairs: Vec<IdxPair> = self
+            .connections
+            .iter()
+            .filter(|c| self.cells.is_initialized(c.id_a) && self.cells.is_initialized(c.id_b))
+            .map(|c| IdxPair::new(c.id_a, c.id_b))
+            .collect();
+        let max_index = self.cells.capacity().max(id + 1) - 1;
+        let groups = CSR::groups_union_find(&pairs, max_index);
+
+        groups
+            .indptr
+            .iter()
+            .map(|range| &groups.indices[range.a..range.b])
+            .find(|members| members.binary_search(&id).is_ok())
+            .map_or_else(|| vec![id], <[CellId]>::to_vec)
+    }
+
+    /// Limits the joint between two connected cells to bend between `min` and `max` radians,
+    /// measured as cell `b`'s rotation relative to cell `a`. Returns `false` if they aren't connected.
+    pub fn limit_joint(&mut self, a: CellId, b: CellId, min: f64, max: f64) -> bool {
+        let Some(connection) = self.connections.iter_mut().find(|c| c.links(a, b)) else {
+            return false;
+        };
+
+        // Joint angles of a connection stored the other way around are mirrored.
+        let (min, max) = if connection.id_a == a { (min, max) } else { (-max, -min) };
+        connection.angle_min = Some(min);
+        connection.angle_max = Some(max);
+        true
+    }
+
+    /// Sets the constraint holding two connected cells together.
+    /// Returns `false` if they aren't connected.
+    pub fn set_connection_kind(&mut self, a: CellId, b: CellId, kind: ConnectionKind) -> bool {
+        let Some(connection) = self.connections.iter_mut().find(|c| c.links(a, b)) else {
+            return false;
+        };
+        connection.kind = kind;
+        true
+    }
+
+    /// Sets the attachment angle, relative to `cell`'s rotation, of the end of its connection to `other` at `cell`.
+    /// Returns `false` if the cells aren't connected.
+    pub fn set_connection_angle(&mut self, cell: CellId, other: CellId, angle: f64) -> bool {
+        let Some(connection) = self.connections.iter_mut().find(|c| c.links(cell, other)) else {
+            return false;
+        };
+        let angle = wrap_angle(angle);
+        if connection.id_a == cell {
+            connection.angle_a = angle;
+        } else {
+            connection.angle_b = angle;
+        }
+        true
+    }
+
+    /// Returns every connection end as `(cell, other, edge point)`: where the connection
+    /// between `cell` and `other` attaches to `cell`. Skips connections to freed cells.
+    pub fn connection_ends(&self) -> impl Iterator<Item = (CellId, CellId, Vec2d)> + '_ {
+        self.connections
+            .iter()
+            .filter(|c| self.cells.is_initialized(c.id_a) && self.cells.is_initialized(c.id_b))
+            .flat_map(|c| {
+                [
+                    (c.id_a, c.id_b, self.cells.get(c.id_a).edge_point(c.angle_a)),
+                    (c.id_b, c.id_a, self.cells.get(c.id_b).edge_point(c.angle_b)),
+                ]
+            })
+    }
+
+    /// Returns the connection end, as `(cell, other)`, whose edge point is nearest the world-space `point`
+    /// and no farther than `radius` from it.
+    pub fn pick_connection_end(&self, point: Vec2d, radius: f64) -> Option<(CellId, CellId)> {
+        self.connection_ends()
+            .map(|(cell, other, edge)| (cell, other, edge.distance(point)))
+            .filter(|&(_, _, distance)| distance <= radius)
+            .min_by(|a, b| a.2.total_cmp(&b.2))
+            .map(|(cell, other, _)| (cell, other))
+    }
+
+    /// Returns both cells of the connection at `index`,
+    /// or `None` if either endpoint has been freed without removing the connection.
+    pub fn connection_pair(&mut self, index: usize) -> Option<(&mut Cell, &mut Cell)> {
+        let CellConnection { id_a, id_b, .. } = self.connections[index];
+        if !self.cells.is_initialized(id_a) || !self.cells.is_initialized(id_b) {
+            return None;
+        }
+        Some(self.cells.get_mut_pair(id_a, id_b))
+    }
+
+    /// Removes connections to freed cells, which can be left behind when cells are
+    /// freed directly on the heap instead of through `remove`.
+    pub fn prune_stale_connections(&mut self) {
+        let mut i = self.connections.len();
+        while i > 0 {
+            i -= 1;
+            let CellConnection { id_a, id_b, .. } = self.connections[i];
+            if !self.cells.is_initialized(id_a) || !self.cells.is_initialized(id_b) {
                 self.connections.swap_remove(i);
+                self.invalidate_adjacency();
+                self.emit(SimEvent::Disconnected { a: id_a, b: id_b });
+            }
+        }
+    }
+
+    /// Removes the connection between two cells, in either order.
+    /// Returns `true` if a connection was removed.
+    pub fn disconnect(&mut self, a: CellId, b: CellId) -> bool {
+        match self.connections.iter().position(|c| c.links(a, b)) {
+            Some(i) => {
+                self.connections.swap_remove(i);
+                self.invalidate_adjacency();
+                self.emit(SimEvent::Disconnected { a, b });
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Iterates over the cells connected to `id`, with the connection linking each.
+    ///
+    /// Backed by an adjacency cache rebuilt on the first query after connections change.
+    pub fn neighbors(&self, id: CellId) -> impl Iterator<Item = (CellId, &CellConnection)> {
+        let adjacency = self.adjacency.get_or_init(|| {
+            let pairs: Vec<IdxPair> = self.connections.iter().map(|c| IdxPair::new(c.id_a, c.id_b)).collect();
+            let node_count = pairs.iter().map(|pair| pair.a.max(pair.b) + 1).max().unwrap_or(0);
+            CSR::incident_from_connections(&pairs, node_count)
+        });
+        let range = adjacency.indptr.get(id).map_or(0..0, |range| range.a..range.b);
+
+        adjacency.indices[range].iter().map(move |&i| {
+            let connection = &self.connections[i];
+            let other = if connection.id_a == id { connection.id_b } else { connection.id_a };
+            (other, connection)
+        })
+    }
+
+    /// Drops the adjacency cache used by `neighbors`.
+    /// Call after adding, removing, or re-pointing entries of `connections` directly.
+    pub fn invalidate_adjacency(&mut self) {
+        self.adjacency.take();
+    }
+
+    /// Returns the cell covering the world-space `point`, preferring the one whose center is closest.
+    pub fn pick_at(&self, point: Vec2d) -> Option<CellId> {
+        self.cells
+            .iter_indexed()
+            .map(|(id, cell)| (id, cell.position.distance(point), cell.radius()))
+            .filter(|&(_, distance, radius)| distance <= radius)
+            .min_by(|a, b| a.1.total_cmp(&b.1))
+            .map(|(id, _, _)| id)
+    }
+
+    /// Returns every pair of live cells whose centers lie within `reach` of each other, as `(a, b)` with `a < b`.
+    ///
+    /// Bucketed on a `SpatialHash` with cells of side `reach`, so each cell is only compared
+    /// with those in the neighboring buckets. Pairs come out in a deterministic order.
+    pub fn close_pairs(&self, reach: f64) -> Vec<(CellId, CellId)> {
+        if !(reach > 0.0 && reach.is_finite()) {
+            return Vec::new();
+        }
+
+        let mut grid = SpatialHash::new(reach);
+        for (id, cell) in self.cells.iter_indexed() {
+            grid.insert(id, cell.position);
+        }
+
+        self.cells
+            .iter_indexed()
+            .flat_map(|(a, cell)| {
+                grid.query_radius(cell.position, reach)
+                    .filter(move |&b| b > a)
+                    .map(move |b| (a, b))
+            })
+            .collect()
+    }
+
+    /// Returns the smallest world-space AABB containing every cell,
+    /// or `AABB::UNIT` if the simulation has no cells.
+    pub fn world_bounds(&self) -> AABB {
+        Self::union_bounds(self.cells.flatten_iter()).unwrap_or(AABB::UNIT)
+    }
+
+    /// Returns the smallest world-space AABB containing the given cells,
+    /// skipping IDs that don't refer to live cells, or `None` if none do.
+    pub fn bounds_of(&self, ids: &[CellId]) -> Option<AABB> {
+        Self::union_bounds(ids.iter().filter_map(|&id| self.cells.get_checked(id)))
+    }
+
+    /// Returns the union of the transformed bounds of `cells`, or `None` if there are none.
+    fn union_bounds<'a>(cells: impl Iterator<Item = &'a Cell>) -> Option<AABB> {
+        cells
+            .map(|cell| AABB::UNIT.transformed(cell.get_transform()))
+            .reduce(|a, b| a | b)
+    }
+
+    /// Exports the cell connection topology as a Graphviz DOT graph,
+    /// with each live cell labeled by its ID and `CellType`.
+    pub fn topology_dot(&self) -> String {
+        let Some(max_index) = self.cells.iter_indexed().map(|(i, _)| i).last() else {
+            return String::from("graph {\n}");
+        };
+
+        let pairs: Vec<IdxPair> = self
+            .connections
+            .iter()
+            .map(|c| IdxPair::new(c.id_a, c.id_b))
+            .collect();
+
+        CSR::adjacent_from_connections(&pairs, max_index).to_dot_labeled(|id| {
+            self.cells
+                .is_initialized(id)
+                .then(|| format!("{id}: {:?}", self.cells.get(id).typ))
+        })
+    }
+
+    /// Returns the IDs of all cells whose position, velocity, or angle
+    /// is NaN or infinite, or `Ok` if every cell is finite.
+    pub fn validate(&self) -> Result<(), Vec<CellId>> {
+        let invalid: Vec<CellId> = self
+            .cells
+            .iter_indexed()
+            .filter(|(_, cell)| {
+                !(cell.position.is_finite()
+                    && cell.velocity.is_finite()
+                    && cell.angle.is_finite()
+                    && cell.angular_velocity.is_finite())
+            })
+            .map(|(id, _)| id)
+            .collect();
+
+        if invalid.is_empty() { Ok(()) } else { Err(invalid) }
+    }
+
+    /// Stops the given cells and moves any without a finite position
+    /// to the middle of their finite neighbors, or the origin if they have none.
+    pub fn quarantine(&mut self, ids: &[CellId]) {
+        for &id in ids {
+            let neighbors: Vec<Vec2d> = self
+                .neighbors(id)
+                .map(|(other, _)| self.cells.get(other).position)
+                .filter(|position| position.is_finite())
+                .collect();
+
+            let cell = self.cells.get_mut(id);
+            if !cell.position.is_finite() {
+                cell.position = match neighbors.len() {
+                    0 => Vec2d::ZERO,
+                    n => neighbors.into_iter().fold(Vec2d::ZERO, |sum, position| sum + position) / n as f64,
+                };
             }
+            if !cell.angle.is_finite() {
+                cell.angle = 0.0;
+            }
+            cell.velocity = Vec2d::ZERO;
+            cell.angular_velocity = 0.0;
+            cell.force = Vec2d::ZERO;
+            cell.torque = 0.0;
+            cell.last_force = Vec2d::ZERO;
+
+            let uid = cell.uid;
+            self.emit(SimEvent::Quarantined { id, uid });
+        }
+    }
+
+    /// Checks for non-finite cells, quarantining them if enabled and otherwise
+    /// emitting `SimEvent::NonFinite` once for each cell that newly went bad.
+    /// Runs every tick in debug builds, but only with quarantine enabled in release builds.
+    fn check_finite(&mut self) {
+        if !(self.context.quarantine || cfg!(debug_assertions)) {
+            return;
+        }
+
+        let invalid = self.validate().err().unwrap_or_default();
+        if self.context.quarantine {
+            self.quarantine(&invalid);
+            return;
+        }
+
+        for &id in &invalid {
+            if !self.invalid.contains(&id) {
+                let uid = self.cells.get(id).uid;
+                self.emit(SimEvent::NonFinite { id, uid });
+            }
+        }
+        self.invalid = invalid;
+    }
+
+    /// Remembers where every cell is before the tick moves it, for interpolated rendering.
+    fn store_previous_transforms(&mut self) {
+        for cell in self.cells.flatten_iter_mut() {
+            cell.previous_position = cell.position;
+            cell.previous_angle = cell.angle;
+        }
+    }
+
+    /// Ages every cell by `dt` and updates its health from its stored energy.
+    fn age_pass(&mut self, dt: f64) {
+        for cell in self.cells.flatten_iter_mut() {
+            cell.age += dt;
+            cell.health = cell.resources.health();
         }
     }
 
     /// Advances the simulation state by a single time step `dt`.
     pub fn tick(&mut self, dt: f64) {
+        self.check_finite();
+        self.store_previous_transforms();
+        self.signal_pass(dt);
+        self.behavior_pass(dt);
+        self.growth_pass(dt);
         self.physics_pass(dt);
+        self.age_pass(dt);
         // Future passes like `share_resources_pass(dt)` can be added here.
+
+        self.tick_count += 1;
+        self.sim_time += dt;
     }
 }
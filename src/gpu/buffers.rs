@@ -1,5 +1,6 @@
 use crate::gpu::context::GpuContext;
-use wgpu::{BindGroup, BindGroupLayout, ShaderStages};
+use wgpu::util::StagingBelt;
+use wgpu::{BindGroup, BindGroupLayout, CommandEncoder, ShaderStages};
 use std::mem::size_of;
 
 /// A typed wrapper around a `wgpu::Buffer`, used for storage or uniform buffers.
@@ -45,22 +46,7 @@ impl GpuContext {
         label: &'static str,
         len: usize,
     ) -> GpuBuffer<T> {
-        let size = (size_of::<T>() * len) as wgpu::BufferAddress;
-
-        let buffer = self.device.create_buffer(&wgpu::BufferDescriptor {
-            label: Some(&format!("{label} - Buffer")),
-            size,
-            usage,
-            mapped_at_creation: false,
-        });
-
-        GpuBuffer {
-            label,
-            buffer,
-            usage,
-            len,
-            _marker: std::marker::PhantomData,
-        }
+        GpuBuffer::new(&self.device, usage, label, len)
     }
 
     /// Creates a `BindGroupLayout` and `BindGroup` from a list of buffers and their `BindInfo`.
@@ -68,10 +54,21 @@ impl GpuContext {
         &self,
         bindings: &[(&wgpu::Buffer, BindInfo)],
     ) -> (BindGroupLayout, BindGroup) {
-        let layout_entries: Vec<_> = bindings
+        let infos: Vec<_> = bindings.iter().map(|(_, info)| *info).collect();
+        let buffers: Vec<_> = bindings.iter().map(|(buffer, _)| *buffer).collect();
+
+        let layout = self.create_bind_layout(&infos);
+        let group = self.create_bind_group(&layout, &buffers);
+
+        (layout, group)
+    }
+
+    /// Creates a `BindGroupLayout` with one buffer binding per `BindInfo`, numbered in order.
+    pub fn create_bind_layout(&self, infos: &[BindInfo]) -> BindGroupLayout {
+        let layout_entries: Vec<_> = infos
             .iter()
             .enumerate()
-            .map(|(i, (_, info))| wgpu::BindGroupLayoutEntry {
+            .map(|(i, info)| wgpu::BindGroupLayoutEntry {
                 binding: i as u32,
                 visibility: info.visibility,
                 ty: match info.kind {
@@ -90,15 +87,18 @@ impl GpuContext {
             })
             .collect();
 
-        let layout = self.device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+        self.device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
             label: Some("auto-layout"),
             entries: &layout_entries,
-        });
+        })
+    }
 
-        let group_entries: Vec<_> = bindings
+    /// Creates a `BindGroup` against an existing layout, binding each buffer in order.
+    pub fn create_bind_group(&self, layout: &BindGroupLayout, buffers: &[&wgpu::Buffer]) -> BindGroup {
+        let group_entries: Vec<_> = buffers
             .iter()
             .enumerate()
-            .map(|(i, (buffer, _))| wgpu::BindGroupEntry {
+            .map(|(i, buffer)| wgpu::BindGroupEntry {
                 binding: i as u32,
                 resource: wgpu::BindingResource::Buffer(wgpu::BufferBinding {
                     buffer,
@@ -108,13 +108,33 @@ impl GpuContext {
             })
             .collect();
 
-        let group = self.device.create_bind_group(&wgpu::BindGroupDescriptor {
+        self.device.create_bind_group(&wgpu::BindGroupDescriptor {
             label: Some("auto-group"),
-            layout: &layout,
+            layout,
             entries: &group_entries,
+        })
+    }
+}
+
+impl<T> GpuBuffer<T> {
+    /// Creates a new GPU buffer of type `T` on `device` with the given usage, label, and length.
+    pub fn new(device: &wgpu::Device, usage: wgpu::BufferUsages, label: &'static str, len: usize) -> Self {
+        let size = (size_of::<T>() * len) as wgpu::BufferAddress;
+
+        let buffer = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some(&format!("{label} - Buffer")),
+            size,
+            usage,
+            mapped_at_creation: false,
         });
 
-        (layout, group)
+        GpuBuffer {
+            label,
+            buffer,
+            usage,
+            len,
+            _marker: std::marker::PhantomData,
+        }
     }
 }
 
@@ -147,16 +167,109 @@ impl<T: bytemuck::Pod> GpuBuffer<T> {
         queue.write_buffer(&self.buffer, 0, bytes);
     }
 
-    /// Writes a slice of `T` into the GPU buffer.
+    /// Copies the buffer's contents back to the CPU, blocking until the GPU finishes.
+    ///
+    /// Intended for debugging and tests. The buffer must have been created with
+    /// `BufferUsages::COPY_SRC`.
+    pub fn read_back(&self, device: &wgpu::Device, queue: &wgpu::Queue) -> Vec<T> {
+        assert!(
+            self.usage.contains(wgpu::BufferUsages::COPY_SRC),
+            "read_back: buffer '{}' was not created with COPY_SRC",
+            self.label
+        );
+
+        let size = (size_of::<T>() * self.len) as wgpu::BufferAddress;
+        let staging = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some(&format!("{} - Readback Staging", self.label)),
+            size,
+            usage: wgpu::BufferUsages::MAP_READ | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+
+        let mut encoder = device.create_command_encoder(&Default::default());
+        encoder.copy_buffer_to_buffer(&self.buffer, 0, &staging, 0, size);
+        queue.submit(std::iter::once(encoder.finish()));
+
+        let slice = staging.slice(..);
+        let (sender, receiver) = std::sync::mpsc::channel();
+        slice.map_async(wgpu::MapMode::Read, move |result| {
+            let _ = sender.send(result);
+        });
+        device.poll(wgpu::Maintain::Wait);
+        receiver
+            .recv()
+            .expect("read_back: map callback was dropped")
+            .expect("read_back: failed to map staging buffer");
+
+        let data = bytemuck::cast_slice(&slice.get_mapped_range()).to_vec();
+        staging.unmap();
+        data
+    }
+
+    /// Ensures the buffer can hold at least `len` elements, reallocating it if not.
+    ///
+    /// Capacity at least doubles on growth to amortize reallocations. The old contents
+    /// are discarded, and bind groups referencing the old buffer must be recreated.
+    /// Returns `true` if the buffer was reallocated.
+    pub fn reserve(&mut self, device: &wgpu::Device, len: usize) -> bool {
+        if len <= self.len {
+            return false;
+        }
+
+        let len = len.max(self.len * 2);
+        self.buffer = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some(&format!("{} - Buffer", self.label)),
+            size: (size_of::<T>() * len) as wgpu::BufferAddress,
+            usage: self.usage,
+            mapped_at_creation: false,
+        });
+        self.len = len;
+        true
+    }
+
+    /// Writes a slice of `T` into the start of the GPU buffer.
     /// Panics if the data length exceeds the allocated buffer size.
     pub fn write_array(&self, queue: &wgpu::Queue, data: &[T]) {
+        self.write_array_at(queue, 0, data);
+    }
+
+    /// Writes a slice of `T` into the GPU buffer starting at element `offset`,
+    /// leaving the rest of the buffer untouched, e.g. to update a single changed instance.
+    /// The byte offset and length must be multiples of `wgpu::COPY_BUFFER_ALIGNMENT`.
+    /// Panics if the data would run past the allocated buffer size.
+    pub fn write_array_at(&self, queue: &wgpu::Queue, offset: usize, data: &[T]) {
         assert!(
-            data.len() <= self.len,
-            "write_array: data length ({}) exceeds buffer capacity ({})",
+            offset + data.len() <= self.len,
+            "write_array_at: writing {} elements at offset {} exceeds buffer capacity ({})",
             data.len(),
+            offset,
             self.len
         );
         let bytes = bytemuck::cast_slice(data);
-        queue.write_buffer(&self.buffer, 0, bytes);
+        queue.write_buffer(&self.buffer, (offset * size_of::<T>()) as wgpu::BufferAddress, bytes);
+    }
+
+    /// Stages a slice of `T` through `belt`, recording the copy into `encoder`.
+    /// The belt must be finished before `encoder` is submitted and recalled afterwards.
+    /// Panics if the data length exceeds the allocated buffer size.
+    pub fn stage_array(
+        &self,
+        device: &wgpu::Device,
+        belt: &mut StagingBelt,
+        encoder: &mut CommandEncoder,
+        data: &[T],
+    ) {
+        assert!(
+            data.len() <= self.len,
+            "stage_array: data length ({}) exceeds buffer capacity ({})",
+            data.len(),
+            self.len
+        );
+        let bytes: &[u8] = bytemuck::cast_slice(data);
+        let Some(size) = wgpu::BufferSize::new(bytes.len() as wgpu::BufferAddress) else {
+            return;
+        };
+        belt.write_buffer(encoder, &self.buffer, 0, size, device)
+            .copy_from_slice(bytes);
     }
 }
@@ -0,0 +1,154 @@
+use super::context::GpuContext;
+use image::RgbaImage;
+use wgpu::RenderPass;
+
+impl GpuContext {
+    /// Returns `scale` reduced so a frame supersampled by it fits the device's texture size limit.
+    pub fn capture_scale(&self, scale: u32) -> u32 {
+        let max_dimension = self.device.limits().max_texture_dimension_2d;
+        let largest = self.size.width.max(self.size.height).max(1);
+        scale.clamp(1, (max_dimension / largest).max(1))
+    }
+
+    /// Renders a frame offscreen at `scale` times the window size and box-filters it back down,
+    /// giving a supersampled screenshot on top of any MSAA.
+    ///
+    /// `draw` records the scene into the pass and must lay it out for the size scaled by `capture_scale`,
+    /// which reduces `scale` if the scaled frame would exceed the device's texture size limit.
+    /// Returns `None` if the surface format isn't an 8-bit RGBA or BGRA one.
+    pub fn capture_supersampled<'a>(&self, scale: u32, draw: impl FnOnce(&mut RenderPass<'a>)) -> Option<RgbaImage> {
+        let (width, height) = (self.size.width.max(1), self.size.height.max(1));
+        let scale = self.capture_scale(scale);
+
+        let format = self.surface_format.add_srgb_suffix();
+        let extent = wgpu::Extent3d {
+            width: width * scale,
+            height: height * scale,
+            depth_or_array_layers: 1,
+        };
+        let create_target = |label, sample_count, usage| {
+            self.device.create_texture(&wgpu::TextureDescriptor {
+                label: Some(label),
+                size: extent,
+                mip_level_count: 1,
+                sample_count,
+                dimension: wgpu::TextureDimension::D2,
+                format,
+                usage,
+                view_formats: &[],
+            })
+        };
+
+        let target = create_target(
+            "Capture Target",
+            1,
+            wgpu::TextureUsages::RENDER_ATTACHMENT | wgpu::TextureUsages::COPY_SRC,
+        );
+        let target_view = target.create_view(&Default::default());
+        let msaa_view = (self.sample_count > 1).then(|| {
+            create_target("Capture MSAA Target", self.sample_count, wgpu::TextureUsages::RENDER_ATTACHMENT)
+                .create_view(&Default::default())
+        });
+
+        // Rows copied out of a texture must be padded to the copy alignment.
+        let unpadded_row = extent.width * 4;
+        let padded_row = unpadded_row.next_multiple_of(wgpu::COPY_BYTES_PER_ROW_ALIGNMENT);
+        let readback = self.device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("Capture Readback"),
+            size: padded_row as wgpu::BufferAddress * extent.height as wgpu::BufferAddress,
+            usage: wgpu::BufferUsages::MAP_READ | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+
+        let mut encoder = self.device.create_command_encoder(&Default::default());
+        {
+            let (view, resolve_target, store) = match &msaa_view {
+                Some(msaa_view) => (msaa_view, Some(&target_view), wgpu::StoreOp::Discard),
+                None => (&target_view, None, wgpu::StoreOp::Store),
+            };
+            // Detached from the encoder so `draw` can borrow renderers for the pass;
+            // the pass is ended before the encoder is used again.
+            let mut render_pass: RenderPass<'a> = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+                label: Some("Capture Pass"),
+                color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+                    view,
+                    resolve_target,
+                    ops: wgpu::Operations {
+                        load: wgpu::LoadOp::Clear(wgpu::Color::BLACK),
+                        store,
+                    },
+                })],
+                depth_stencil_attachment: None,
+                timestamp_writes: None,
+                occlusion_query_set: None,
+            }).forget_lifetime();
+            draw(&mut render_pass);
+        }
+        encoder.copy_texture_to_buffer(
+            target.as_image_copy(),
+            wgpu::TexelCopyBufferInfo {
+                buffer: &readback,
+                layout: wgpu::TexelCopyBufferLayout {
+                    offset: 0,
+                    bytes_per_row: Some(padded_row),
+                    rows_per_image: None,
+                },
+            },
+            extent,
+        );
+        self.queue.submit(std::iter::once(encoder.finish()));
+
+        let slice = readback.slice(..);
+        let (sender, receiver) = std::sync::mpsc::channel();
+        slice.map_async(wgpu::MapMode::Read, move |result| {
+            let _ = sender.send(result);
+        });
+        self.device.poll(wgpu::Maintain::Wait);
+        receiver
+            .recv()
+            .expect("capture: map callback was dropped")
+            .expect("capture: failed to map readback buffer");
+
+        let swap_red_blue = match format {
+            wgpu::TextureFormat::Rgba8UnormSrgb | wgpu::TextureFormat::Rgba8Unorm => false,
+            wgpu::TextureFormat::Bgra8UnormSrgb | wgpu::TextureFormat::Bgra8Unorm => true,
+            _ => {
+                eprintln!("Capture unsupported for surface format {:?}", format);
+                return None;
+            }
+        };
+
+        let mut pixels = Vec::with_capacity((unpadded_row * extent.height) as usize);
+        for row in slice.get_mapped_range().chunks_exact(padded_row as usize) {
+            pixels.extend_from_slice(&row[..unpadded_row as usize]);
+        }
+        readback.unmap();
+        if swap_red_blue {
+            pixels.chunks_exact_mut(4).for_each(|pixel| pixel.swap(0, 2));
+        }
+
+        let supersampled = RgbaImage::from_raw(extent.width, extent.height, pixels)?;
+        Some(downsample(&supersampled, scale))
+    }
+}
+
+/// Shrinks `image` by an integer `scale`, averaging each `scale`×`scale` block into one pixel.
+pub fn downsample(image: &RgbaImage, scale: u32) -> RgbaImage {
+    if scale <= 1 {
+        return image.clone();
+    }
+
+    let samples = scale * scale;
+    RgbaImage::from_fn(image.width() / scale, image.height() / scale, |x, y| {
+        let mut sum = [0u32; 4];
+        for dy in 0..scale {
+            for dx in 0..scale {
+                let pixel = image.get_pixel(x * scale + dx, y * scale + dy);
+                for (total, channel) in sum.iter_mut().zip(pixel.0) {
+                    *total += channel as u32;
+                }
+            }
+        }
+        image::Rgba(sum.map(|total| ((total + samples / 2) / samples) as u8))
+    })
+}
@@ -1,3 +1,5 @@
+use crate::graphics::layers::SimulationRenderResources;
+use std::cell::OnceCell;
 use std::sync::Arc;
 use winit::window::Window;
 
@@ -20,18 +22,59 @@ pub(crate) struct GpuContext {
 
     /// Format of the textures presented by the surface.
     pub surface_format: wgpu::TextureFormat,
+
+    /// Presentation mode used when configuring the surface.
+    pub present_mode: wgpu::PresentMode,
+
+    /// Presentation modes supported by the surface on this adapter.
+    supported_present_modes: Vec<wgpu::PresentMode>,
+
+    /// Number of MSAA samples per pixel used by every render pipeline.
+    pub sample_count: u32,
+
+    /// Multisampled color target resolved to the surface each frame, if MSAA is enabled.
+    pub msaa_view: Option<wgpu::TextureView>,
+
+    /// Render resources shared by all simulation tiles, created on first use.
+    pub simulation_resources: OnceCell<Arc<SimulationRenderResources>>,
 }
 
 impl GpuContext {
-    /// Asynchronously creates a new `GpuContext` bound to the given window.
-    pub(crate) async fn new(window: Arc<Window>) -> GpuContext {
-        let instance = wgpu::Instance::new(&wgpu::InstanceDescriptor::default());
+    /// Asynchronously creates a new `GpuContext` bound to the given window,
+    /// presenting with `present_mode` if the surface supports it.
+    ///
+    /// `sample_count` sets the MSAA level; only 1 and 4 are guaranteed by wgpu,
+    /// and unsupported counts fall back to 1.
+    ///
+    /// The backend can be forced with `WGPU_BACKEND` (e.g. `vulkan`, `dx12`, `metal`, `gl`),
+    /// and the high-performance adapter preference overridden with `WGPU_POWER_PREF`.
+    pub(crate) async fn new(window: Arc<Window>, present_mode: wgpu::PresentMode, sample_count: u32) -> GpuContext {
+        let instance = wgpu::Instance::new(&wgpu::InstanceDescriptor::from_env_or_default());
 
-        // Request an appropriate adapter (physical GPU).
-        let adapter = instance
-            .request_adapter(&wgpu::RequestAdapterOptions::default())
-            .await
-            .expect("Failed to find a GPU adapter");
+        let size = window.inner_size();
+
+        // Create the rendering surface linked to the window.
+        let surface = instance.create_surface(window.clone())
+            .expect("Failed to create surface");
+
+        // Request an adapter (physical GPU) that can present to the surface,
+        // falling back to a software adapter if no hardware one is available.
+        let mut options = wgpu::RequestAdapterOptions {
+            power_preference: wgpu::PowerPreference::from_env().unwrap_or(wgpu::PowerPreference::HighPerformance),
+            force_fallback_adapter: false,
+            compatible_surface: Some(&surface),
+        };
+        let adapter = match instance.request_adapter(&options).await {
+            Some(adapter) => adapter,
+            None => {
+                eprintln!("No hardware GPU adapter found, trying a fallback adapter");
+                options.force_fallback_adapter = true;
+                instance
+                    .request_adapter(&options)
+                    .await
+                    .expect("Failed to find a GPU adapter")
+            }
+        };
 
         // Request a logical device and command queue from the adapter.
         let (device, queue) = adapter
@@ -39,27 +82,46 @@ impl GpuContext {
             .await
             .expect("Failed to create device and queue");
 
-        let size = window.inner_size();
-
-        // Create the rendering surface linked to the window.
-        let surface = instance.create_surface(window.clone())
-            .expect("Failed to create surface");
-
-        // Query supported surface formats and pick the first.
+        // Query supported surface formats, preferring an sRGB one.
         let caps = surface.get_capabilities(&adapter);
-        let surface_format = caps.formats[0];
+        let surface_format = caps
+            .formats
+            .iter()
+            .copied()
+            .find(wgpu::TextureFormat::is_srgb)
+            .unwrap_or(caps.formats[0]);
+
+        let info = adapter.get_info();
+        println!(
+            "Using GPU adapter {} ({:?}, {:?}) with surface format {:?}",
+            info.name, info.backend, info.device_type, surface_format,
+        );
+
+        // Check the requested sample count against the adapter's format support.
+        let format_features = adapter.get_texture_format_features(surface_format.add_srgb_suffix());
+        let sample_count = if matches!(sample_count, 1 | 4) && format_features.flags.sample_count_supported(sample_count) {
+            sample_count
+        } else {
+            eprintln!("MSAA sample count {} unsupported, falling back to 1", sample_count);
+            1
+        };
 
-        let context = GpuContext {
+        let mut context = GpuContext {
             window,
             device,
             queue,
             size,
             surface,
             surface_format,
+            present_mode: wgpu::PresentMode::AutoVsync,
+            supported_present_modes: caps.present_modes,
+            sample_count,
+            msaa_view: None,
+            simulation_resources: OnceCell::new(),
         };
 
         // Initial surface configuration.
-        context.configure_surface();
+        context.set_present_mode(present_mode);
 
         context
     }
@@ -69,8 +131,18 @@ impl GpuContext {
         &self.window
     }
 
-    /// Configures the surface with the current size and format.
-    fn configure_surface(&self) {
+    /// Returns the multisample state pipelines must use to render into this context's frames.
+    pub fn multisample_state(&self) -> wgpu::MultisampleState {
+        wgpu::MultisampleState {
+            count: self.sample_count,
+            mask: !0,
+            alpha_to_coverage_enabled: false,
+        }
+    }
+
+    /// Configures the surface with the current size and format,
+    /// recreating the multisampled target to match.
+    fn configure_surface(&mut self) {
         let surface_config = wgpu::SurfaceConfiguration {
             usage: wgpu::TextureUsages::RENDER_ATTACHMENT,
             format: self.surface_format,
@@ -79,13 +151,51 @@ impl GpuContext {
             width: self.size.width,
             height: self.size.height,
             desired_maximum_frame_latency: 2,
-            present_mode: wgpu::PresentMode::AutoVsync,
+            present_mode: self.present_mode,
         };
         self.surface.configure(&self.device, &surface_config);
+
+        self.msaa_view = (self.sample_count > 1).then(|| {
+            self.device
+                .create_texture(&wgpu::TextureDescriptor {
+                    label: Some("MSAA Target"),
+                    size: wgpu::Extent3d {
+                        width: self.size.width.max(1),
+                        height: self.size.height.max(1),
+                        depth_or_array_layers: 1,
+                    },
+                    mip_level_count: 1,
+                    sample_count: self.sample_count,
+                    dimension: wgpu::TextureDimension::D2,
+                    format: self.surface_format.add_srgb_suffix(),
+                    usage: wgpu::TextureUsages::RENDER_ATTACHMENT,
+                    view_formats: &[],
+                })
+                .create_view(&wgpu::TextureViewDescriptor::default())
+        });
+    }
+
+    /// Sets the presentation mode and reconfigures the surface.
+    /// Falls back to `Fifo`, which every surface supports, if `mode` is unavailable.
+    pub(crate) fn set_present_mode(&mut self, mode: wgpu::PresentMode) {
+        let supported = matches!(mode, wgpu::PresentMode::AutoVsync | wgpu::PresentMode::AutoNoVsync)
+            || self.supported_present_modes.contains(&mode);
+
+        self.present_mode = if supported {
+            mode
+        } else {
+            eprintln!("Present mode {:?} unsupported, falling back to Fifo", mode);
+            wgpu::PresentMode::Fifo
+        };
+        self.configure_surface();
     }
 
     /// Handles window resizing by updating the stored size and reconfiguring the surface.
+    /// Zero-sized windows (e.g. minimized) are ignored, since a surface cannot be configured to them.
     pub(crate) fn resize(&mut self, new_size: winit::dpi::PhysicalSize<u32>) {
+        if new_size.width == 0 || new_size.height == 0 {
+            return;
+        }
         self.size = new_size;
         self.configure_surface();
     }
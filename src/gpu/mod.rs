@@ -1,3 +1,4 @@
 pub mod buffers;
+pub mod capture;
 pub mod context;
-mod shaders;
+pub mod shaders;
@@ -1,3 +1,11 @@
+use notify::{RecommendedWatcher, RecursiveMode, Watcher};
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+/// Directory holding the WGSL sources, watched for changes in debug builds.
+pub const SHADER_DIR: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/src/shaders");
+
 /// Macro to concatenate the contents of multiple source files into a single string.
 ///
 /// # Usage
@@ -7,13 +15,74 @@
 ///
 /// Accepts one or more string literals representing file paths.
 /// Trailing comma is optional.
+///
+/// The files are baked into the binary. In debug builds they are re-read from disk
+/// on every call instead, falling back to the baked copy if any file can't be read,
+/// so shaders can be edited without recompiling.
 #[macro_export]
 macro_rules! combine_code {
     ($($path:literal),+ $(,)?) => {{
-        concat!(
+        let baked: &'static str = concat!(
             $(
                 include_str!($path),
             )+
-        )
+        );
+
+        let from_disk = if cfg!(debug_assertions) {
+            $crate::gpu::shaders::read_from_disk(file!(), &[$($path),+])
+        } else {
+            None
+        };
+
+        match from_disk {
+            Some(code) => std::borrow::Cow::<'static, str>::Owned(code),
+            None => std::borrow::Cow::Borrowed(baked),
+        }
     }};
-}
\ No newline at end of file
+}
+
+/// Reads and concatenates files given relative to the source file `caller`,
+/// as `include_str!` resolves them. Returns `None` if any file can't be read.
+pub fn read_from_disk(caller: &str, paths: &[&str]) -> Option<String> {
+    let dir = Path::new(env!("CARGO_MANIFEST_DIR")).join(caller);
+    let dir = dir.parent()?;
+
+    paths
+        .iter()
+        .map(|path| std::fs::read_to_string(dir.join(path)).ok())
+        .collect()
+}
+
+/// Watches a directory of shader sources and records whether any file changed.
+pub struct ShaderWatcher {
+    /// Kept alive so the underlying watch isn't dropped.
+    _watcher: RecommendedWatcher,
+    changed: Arc<AtomicBool>,
+}
+
+impl ShaderWatcher {
+    /// Starts watching `dir` for created or modified files.
+    pub fn new(dir: impl AsRef<Path>) -> notify::Result<Self> {
+        let changed = Arc::new(AtomicBool::new(false));
+        let flag = changed.clone();
+
+        let mut watcher = notify::recommended_watcher(move |event: notify::Result<notify::Event>| {
+            if let Ok(event) = event
+                && (event.kind.is_modify() || event.kind.is_create())
+            {
+                flag.store(true, Ordering::Relaxed);
+            }
+        })?;
+        watcher.watch(dir.as_ref(), RecursiveMode::NonRecursive)?;
+
+        Ok(Self {
+            _watcher: watcher,
+            changed,
+        })
+    }
+
+    /// Returns `true` if a shader changed since the last call.
+    pub fn take_changed(&self) -> bool {
+        self.changed.swap(false, Ordering::Relaxed)
+    }
+}
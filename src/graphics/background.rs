@@ -0,0 +1,122 @@
+use crate::combine_code;
+use crate::gpu::buffers::{BindInfo, BufferKind, GpuBuffer};
+use crate::gpu::context::GpuContext;
+use super::models::{cpu::Color, gpu::*};
+use super::renderer::{TileRenderer, RenderPhase};
+
+use glam::Vec2;
+use wgpu::{BindGroup, Queue, ShaderStages};
+use crate::core::sim::SimulationState;
+
+/// A GPU-backed renderer filling its tile with a solid color.
+///
+/// All tiles share one render pass and its single clear, so adding this as
+/// the first layer of a tile gives it its own background without another pass.
+/// The fill is a single triangle covering the viewport, generated in the shader.
+pub struct BackgroundTile {
+    pipeline: wgpu::RenderPipeline,
+    color: Color,
+    info_buff: GpuBuffer<BackgroundInfoUniform>,
+    info_bind: BindGroup,
+}
+
+impl BackgroundTile {
+    /// Creates a new `BackgroundTile` filling its tile with `color`.
+    pub fn new(context: &GpuContext, color: Color) -> Self {
+        // Compile the WGSL shader module for background rendering
+        let shader = context.device.create_shader_module(wgpu::ShaderModuleDescriptor {
+            label: Some("Background Shader"),
+            source: wgpu::ShaderSource::Wgsl(combine_code!(
+                "../shaders/background.wgsl"
+            )),
+        });
+
+        // Create a uniform buffer holding the fill color
+        let info_buff = context.create_buffer::<BackgroundInfoUniform>(
+            wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+            "Background Info",
+            1,
+        );
+
+        // Create a bind group for the uniform buffer with fragment shader visibility
+        let (info_layout, info_bind) = context.create_bind_data(&[(
+            &info_buff.buffer,
+            BindInfo {
+                visibility: ShaderStages::FRAGMENT,
+                kind: BufferKind::Uniform,
+            },
+        )]);
+
+        let pipeline_layout = context.device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+            label: Some("Background Pipeline Layout"),
+            bind_group_layouts: &[&info_layout],
+            push_constant_ranges: &[],
+        });
+
+        // Create the render pipeline; the fill replaces whatever was drawn before it
+        let pipeline = context.device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+            label: Some("Background Pipeline"),
+            layout: Some(&pipeline_layout),
+            vertex: wgpu::VertexState {
+                module: &shader,
+                entry_point: Some("vs_main"),
+                buffers: &[],
+                compilation_options: wgpu::PipelineCompilationOptions::default(),
+            },
+            fragment: Some(wgpu::FragmentState {
+                module: &shader,
+                entry_point: Some("fs_main"),
+                targets: &[Some(wgpu::ColorTargetState {
+                    format: context.surface_format,
+                    blend: Some(wgpu::BlendState::REPLACE),
+                    write_mask: wgpu::ColorWrites::ALL,
+                })],
+                compilation_options: wgpu::PipelineCompilationOptions::default(),
+            }),
+            primitive: wgpu::PrimitiveState {
+                topology: wgpu::PrimitiveTopology::TriangleList,
+                strip_index_format: None,
+                front_face: wgpu::FrontFace::Ccw,
+                cull_mode: None,
+                polygon_mode: wgpu::PolygonMode::Fill,
+                unclipped_depth: false,
+                conservative: false,
+            },
+            depth_stencil: None,
+            multisample: context.multisample_state(),
+            multiview: None,
+            cache: None,
+        });
+
+        Self { pipeline, color, info_buff, info_bind }
+    }
+}
+
+impl TileRenderer for BackgroundTile {
+    /// Drawn first, under every other layer.
+    fn phase(&self) -> RenderPhase {
+        RenderPhase::Background
+    }
+
+    /// Called once to initialize the renderer.
+    fn init(&self, queue: &Queue) {
+        self.info_buff.write(queue, &BackgroundInfoUniform::new(self.color.to_f32_array()));
+    }
+
+    /// Called when the viewport or target size changes.
+    fn resize(&mut self, _size: Vec2, _queue: &wgpu::Queue) {
+        // The fill covers the viewport whatever its size
+    }
+
+    /// Updates render data based on simulation state.
+    fn update_render_data(&mut self, _state: &SimulationState, _context: &GpuContext) {
+        // Background doesn't need state updates
+    }
+
+    /// Encodes commands to render on the render pass.
+    fn render_pipeline(&self, render_pass: &mut wgpu::RenderPass) {
+        render_pass.set_pipeline(&self.pipeline);
+        render_pass.set_bind_group(0, &self.info_bind, &[]);
+        render_pass.draw(0..3, 0..1);
+    }
+}
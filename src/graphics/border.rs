@@ -1,9 +1,8 @@
-use std::sync::{Arc, Mutex};
 use crate::combine_code;
 use crate::gpu::buffers::{BindInfo, BufferKind, GpuBuffer};
 use crate::gpu::context::GpuContext;
 use super::models::{gpu::*, space::*};
-use super::renderer::TileRenderer;
+use super::renderer::{TileRenderer, RenderPhase};
 
 use glam::Vec2;
 use wgpu::{BindGroup, Queue, ShaderStages};
@@ -32,7 +31,7 @@ impl BorderTile {
             label: Some("Border Shader"),
             source: wgpu::ShaderSource::Wgsl(combine_code!(
                 "../shaders/border.wgsl"
-            ).into()),
+            )),
         });
 
         // Create the vertex buffer for border geometry (24 vertices for 4 quads)
@@ -95,7 +94,7 @@ impl BorderTile {
                 conservative: false,
             },
             depth_stencil: None,
-            multisample: wgpu::MultisampleState::default(),
+            multisample: context.multisample_state(),
             multiview: None,
             cache: None,
         });
@@ -103,49 +102,36 @@ impl BorderTile {
         Self { pipeline, vert_buff, info_buff, info_bind }
     }
 
-    /// Generates the mesh vertices for a border around the given AABB.
-    fn generate_border_mesh(aabb: AABB, width: f32) -> [GpuVertex; 24] {
-        // Inner rectangle shrunk by border width
-        let inner = aabb.add_padding(-width).corners();
-        // Outer rectangle is the original aabb corners
-        let outer = aabb.add_padding(0.0).corners();
+    /// Generates the mesh vertices for a border of the given width inside a possibly rotated box,
+    /// e.g. a selection outline around a rotated cell.
+    pub fn generate_border_mesh(obb: OBB, width: f32) -> [GpuVertex; 24] {
+        // Outer quad is the box itself; inner quad is shrunk by the border width
+        let outer = QuadVerts::from_obb(obb);
+        let inner = QuadVerts::from_obb(obb.add_padding(-width));
 
-        let v = |pos: Vec2| GpuVertex::new(pos);
-
-        [
-            // Top quad (2 triangles)
-            v(outer.tl), v(outer.tr), v(inner.tr),
-            v(inner.tr), v(inner.tl), v(outer.tl),
-
-            // Right quad
-            v(outer.tr), v(outer.br), v(inner.br),
-            v(inner.br), v(inner.tr), v(outer.tr),
-
-            // Bottom quad
-            v(outer.br), v(outer.bl), v(inner.bl),
-            v(inner.bl), v(inner.br), v(outer.br),
-
-            // Left quad
-            v(outer.bl), v(outer.tl), v(inner.tl),
-            v(inner.tl), v(inner.bl), v(outer.bl),
-        ]
+        outer.frame_mesh(&inner)
     }
 }
 
 impl TileRenderer for BorderTile {
+    /// Drawn last, framing every other layer.
+    fn phase(&self) -> RenderPhase {
+        RenderPhase::Border
+    }
+
     /// Called once to initialize the renderer.
     fn init(&self, _queue: &Queue) {}
 
     /// Called when the viewport or target size changes.
     fn resize(&mut self, size: Vec2, queue: &wgpu::Queue) {
         let aabb = AABB::new(Vec2::ZERO, size * 0.5);
-        let vertices = Self::generate_border_mesh(aabb, 20.0);
+        let vertices = Self::generate_border_mesh(aabb.into(), 20.0);
         self.vert_buff.write_array(queue, &vertices);
         self.info_buff.write(queue, &BorderInfoUniform::new(size, 20.0));
     }
 
     /// Updates render data based on simulation state.
-    fn update_render_data(&mut self, _state: Arc<Mutex<SimulationState>>, _queue: &wgpu::Queue) {
+    fn update_render_data(&mut self, _state: &SimulationState, _context: &GpuContext) {
         // Border doesn't need state updates
     }
 
@@ -0,0 +1,197 @@
+use super::models::space::{SrtTransform, AABB};
+use glam::{vec2, Mat4, Vec2};
+use std::time::{Duration, Instant};
+
+/// Determines how the simulation worldspace is mapped onto a tile whose aspect ratio differs.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum FitMode {
+    /// Maps the worldspace exactly onto the tile, distorting it if the aspect ratios differ.
+    Stretch,
+    /// Shows the whole worldspace, adding margin on one axis to preserve the aspect ratio.
+    Contain,
+    /// Fills the tile with the worldspace, cropping one axis to preserve the aspect ratio.
+    Cover,
+}
+
+impl FitMode {
+    /// Returns the mode following this one, wrapping around.
+    pub fn next(self) -> Self {
+        match self {
+            FitMode::Contain => FitMode::Cover,
+            FitMode::Cover => FitMode::Stretch,
+            FitMode::Stretch => FitMode::Contain,
+        }
+    }
+}
+
+/// A 2D camera viewing a rotated region of worldspace.
+///
+/// The requested `view` is fitted to each tile's aspect ratio according to `fit_mode`.
+#[derive(Clone, Copy, Debug)]
+pub struct Camera {
+    /// World-space region to show, before fitting and rotation.
+    pub view: AABB,
+    /// Rotation of the view in radians.
+    pub rotate: f32,
+    /// How `view` is fitted to a tile whose aspect ratio differs.
+    pub fit_mode: FitMode,
+    /// Half-extents of the view at zoom 1, i.e. those the camera was created with.
+    home_half: Vec2,
+    /// Smallest zoom `zoom_at` can reach, showing the most of the world.
+    pub min_zoom: f32,
+    /// Largest zoom `zoom_at` can reach, showing the least of the world.
+    pub max_zoom: f32,
+}
+
+impl Camera {
+    /// Default zoom limits, relative to the initial view.
+    pub const DEFAULT_ZOOM_LIMITS: (f32, f32) = (0.05, 50.0);
+
+    /// Creates an unrotated camera containing `view`, which is treated as zoom 1.
+    pub fn new(view: AABB) -> Self {
+        let (min_zoom, max_zoom) = Self::DEFAULT_ZOOM_LIMITS;
+        Self {
+            view,
+            rotate: 0.0,
+            fit_mode: FitMode::Contain,
+            home_half: view.half,
+            min_zoom,
+            max_zoom,
+        }
+    }
+
+    /// World-space margin kept around bounds framed by `frame`.
+    pub const FRAME_PADDING: f32 = 1.0;
+
+    /// Sets the view to `bounds` plus padding.
+    pub fn frame(&mut self, bounds: AABB) {
+        self.view = bounds.add_padding(Self::FRAME_PADDING);
+    }
+
+    /// Returns the current zoom relative to the initial view; larger values show less of the world.
+    pub fn zoom(&self) -> f32 {
+        self.home_half.x / self.view.half.x
+    }
+
+    /// Returns the transform mapping clip space onto the visible world-space region
+    /// of a tile with aspect ratio `tile_aspect`.
+    /// A degenerate aspect, e.g. from a tile with zero height mid-resize, falls back to stretching.
+    pub fn view_transform(&self, tile_aspect: f32) -> SrtTransform {
+        let fitted = match self.fit_mode {
+            _ if !(tile_aspect.is_finite() && tile_aspect > 0.0) => self.view,
+            FitMode::Stretch => self.view,
+            FitMode::Contain => self.view.min_proportional(tile_aspect),
+            FitMode::Cover => self.view.max_proportional(tile_aspect),
+        };
+
+        SrtTransform {
+            rotate: self.rotate,
+            ..fitted.to_forward_projection()
+        }
+        .normalized()
+    }
+
+    /// Returns the matrix mapping world space to clip space for a tile with aspect ratio `tile_aspect`.
+    pub fn projection_matrix(&self, tile_aspect: f32) -> Mat4 {
+        self.view_transform(tile_aspect).to_mat4().inverse()
+    }
+
+    /// Pans by `delta`, measured in visible half-extents along the screen axes,
+    /// so the same input moves the view by the same screen fraction at any zoom.
+    pub fn pan(&mut self, delta: Vec2, tile_aspect: f32) {
+        let half = self.view_transform(tile_aspect).scale;
+        self.view.center += Vec2::from_angle(self.rotate).rotate(delta * half);
+    }
+
+    /// Zooms in by `factor`, or out if it is below one, keeping the world-space point `anchor`
+    /// at the same place on screen. The result is clamped to `min_zoom..=max_zoom`,
+    /// and non-positive or non-finite factors are ignored.
+    pub fn zoom_at(&mut self, anchor: Vec2, factor: f32) {
+        if !(factor.is_finite() && factor > 0.0) {
+            return;
+        }
+
+        let zoom = self.zoom();
+        let factor = (zoom * factor).clamp(self.min_zoom, self.max_zoom) / zoom;
+
+        self.view.center = anchor + (self.view.center - anchor) / factor;
+        self.view.half /= factor;
+    }
+
+    /// Maps a world-space point to pixel coordinates within a tile of `tile_size`,
+    /// with the origin at the top-left corner and y pointing down.
+    pub fn world_to_screen(&self, world: Vec2, tile_size: Vec2) -> Vec2 {
+        let clip = self
+            .projection_matrix(tile_size.x / tile_size.y)
+            .project_point3(world.extend(0.0));
+        vec2((clip.x + 1.0) * 0.5 * tile_size.x, (1.0 - clip.y) * 0.5 * tile_size.y)
+    }
+
+    /// Maps pixel coordinates within a tile of `tile_size` back to world space,
+    /// inverting `world_to_screen`.
+    pub fn screen_to_world(&self, screen: Vec2, tile_size: Vec2) -> Vec2 {
+        let clip = vec2(screen.x / tile_size.x * 2.0 - 1.0, 1.0 - screen.y / tile_size.y * 2.0);
+        self.view_transform(tile_size.x / tile_size.y)
+            .to_mat4()
+            .project_point3(clip.extend(0.0))
+            .truncate()
+    }
+}
+
+
+/// Easing curve mapping linear progress in `0.0..=1.0` to eased progress.
+pub type Easing = fn(f32) -> f32;
+
+/// Cubic ease-out: fast start, gentle settle.
+pub fn ease_out_cubic(t: f32) -> f32 {
+    1.0 - (1.0 - t).powi(3)
+}
+
+/// Animates a camera transform from a start to a target over a fixed duration.
+pub struct CameraAnimator {
+    start: SrtTransform,
+    /// Transform the animation ends on.
+    pub target: SrtTransform,
+    duration: Duration,
+    started: Instant,
+    easing: Easing,
+}
+
+impl CameraAnimator {
+    /// Default length of a camera transition.
+    pub const DEFAULT_DURATION: Duration = Duration::from_millis(300);
+
+    /// Creates an animator starting now.
+    pub fn new(start: SrtTransform, target: SrtTransform, duration: Duration, easing: Easing) -> Self {
+        Self {
+            start,
+            target,
+            duration,
+            started: Instant::now(),
+            easing,
+        }
+    }
+
+    /// Creates an animator using the default cubic ease-out transition.
+    pub fn ease_out(start: SrtTransform, target: SrtTransform) -> Self {
+        Self::new(start, target, Self::DEFAULT_DURATION, ease_out_cubic)
+    }
+
+    /// Returns linear progress through the animation at `now`, clamped to `0.0..=1.0`.
+    fn progress(&self, now: Instant) -> f32 {
+        if self.duration.is_zero() {
+            return 1.0;
+        }
+        (now.duration_since(self.started).as_secs_f32() / self.duration.as_secs_f32()).min(1.0)
+    }
+
+    /// Returns the eased camera transform at `now`.
+    pub fn sample(&self, now: Instant) -> SrtTransform {
+        self.start.lerp(self.target, (self.easing)(self.progress(now)))
+    }
+
+    /// Returns `true` once the animation has reached its target.
+    pub fn is_finished(&self, now: Instant) -> bool {
+        self.progress(now) >= 1.0
+    }
+}
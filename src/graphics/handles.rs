@@ -0,0 +1,189 @@
+use super::layers::{DebugPrimitiveTile, SharedTileView, TileView};
+use super::models::cpu::{Color, Primitive, ShapeDesc};
+use super::models::space::SrtTransform;
+use super::renderer::{TileEvent, TileRenderer, RenderPhase};
+use crate::app::components::UndoStack;
+use crate::core::elements::{CellId, Uid};
+use crate::core::sim::SimulationState;
+use crate::gpu::context::GpuContext;
+use crate::utils::vector::Vec2d;
+
+use glam::Vec2;
+use std::sync::{Arc, Mutex};
+use wgpu::RenderPass;
+
+/// A debug overlay drawing a handle where each connection attaches to its cells,
+/// which can be dragged around the cell to change the connection's angle there.
+///
+/// Tiles only see the simulation read-only while rendering, so the tile keeps the shared
+/// state to apply drags from mouse events. Draws through a `DebugPrimitiveTile`,
+/// following the simulation tile's view and staying hidden, and inert, until toggled with `TileEvent::ToggleDebug`.
+pub struct ConnectionHandleTile {
+    /// Tile drawing the handles rebuilt every frame.
+    debug: DebugPrimitiveTile,
+
+    /// Simulation whose connections are edited by dragging.
+    state: Arc<Mutex<SimulationState>>,
+
+    /// History the simulation is recorded into as each drag begins, so it can be undone.
+    undo_stack: Arc<Mutex<UndoStack>>,
+
+    /// Cursor position in tile-local pixels, or `None` if it is off the tile.
+    cursor: Option<Vec2>,
+
+    /// Connection end being dragged, as the stable IDs of `(cell, other)`,
+    /// so the drag follows the cells when removals or compaction move their slots.
+    dragging: Option<(Uid, Uid)>,
+}
+
+impl ConnectionHandleTile {
+    /// Diameter of a handle, in world units.
+    const HANDLE_SIZE: f32 = 0.15;
+    /// Distance from a handle's center within which a click grabs it, in world units.
+    const GRAB_RADIUS: f64 = 0.15;
+
+    const HANDLE_COLOR: Color = Color::rgb(255, 255, 255);
+    const DRAGGED_COLOR: Color = Color::rgb(255, 170, 0);
+
+    /// Constructs a hidden `ConnectionHandleTile` editing `state`, drawn through the shared `view`,
+    /// that records the state into `undo_stack` before each drag.
+    pub fn new(view: SharedTileView, state: Arc<Mutex<SimulationState>>, undo_stack: Arc<Mutex<UndoStack>>, context: &GpuContext) -> Self {
+        Self {
+            debug: DebugPrimitiveTile::new(view, None, context),
+            state,
+            undo_stack,
+            cursor: None,
+            dragging: None,
+        }
+    }
+
+    /// Shows or hides the handles, which can only be grabbed while shown.
+    pub fn set_visible(&mut self, visible: bool) {
+        self.debug.set_visible(visible);
+    }
+
+    /// Returns a handle at every connection end, highlighting the one being dragged,
+    /// placed on the cell where `view` draws it.
+    pub fn handles(state: &SimulationState, dragging: Option<(CellId, CellId)>, view: &TileView) -> Vec<Primitive> {
+        state
+            .connection_ends()
+            .map(|(cell, other, edge)| {
+                let color = if dragging == Some((cell, other)) { Self::DRAGGED_COLOR } else { Self::HANDLE_COLOR };
+                // Carry the edge offset along with the cell's interpolated turn.
+                let body = state.cells.get(cell);
+                let shown = body.get_transform_interpolated(view.origin, view.alpha);
+                let offset = (edge - body.position).to_glam();
+                Primitive {
+                    transform: SrtTransform {
+                        translate: shown.translate + Vec2::from_angle(shown.rotate - body.rotation()).rotate(offset),
+                        rotate: 0.0,
+                        scale: Vec2::splat(Self::HANDLE_SIZE),
+                    },
+                    ..Primitive::new(ShapeDesc::Circle, color)
+                }
+            })
+            .collect()
+    }
+
+    /// Returns the world-space point under the cursor, if it is on the tile.
+    fn cursor_world(&self) -> Option<Vec2> {
+        self.cursor.map(|cursor| self.debug.view().screen_to_world(cursor))
+    }
+
+    /// Returns the current slots of the dragged connection end's cells, or `None` if either is gone.
+    fn resolve(state: &SimulationState, dragging: Option<(Uid, Uid)>) -> Option<(CellId, CellId)> {
+        let (cell, other) = dragging?;
+        Some((state.id_of(cell)?, state.id_of(other)?))
+    }
+
+    /// Grabs the handle under the cursor, if any, recording the state once for the whole drag.
+    fn grab(&mut self) {
+        let Some(world) = self.cursor_world() else {
+            return;
+        };
+        let state = self.state.lock().unwrap();
+        self.dragging = state
+            .pick_connection_end(world.into(), Self::GRAB_RADIUS)
+            .map(|(cell, other)| (state.cells.get(cell).uid, state.cells.get(other).uid));
+        if self.dragging.is_some() {
+            self.undo_stack.lock().unwrap().push(&state);
+        }
+    }
+
+    /// Turns the dragged connection end to face the cursor from its cell's center,
+    /// cancelling the drag if either cell or the connection is gone.
+    fn drag(&mut self) {
+        let (Some(_), Some(world)) = (self.dragging, self.cursor_world()) else {
+            return;
+        };
+
+        let mut state = self.state.lock().unwrap();
+        let Some((cell, other)) = Self::resolve(&state, self.dragging) else {
+            self.dragging = None;
+            return;
+        };
+        let body = state.cells.get(cell);
+        let angle = (Vec2d::from(world) - body.position).angle() - body.angle;
+        if !state.set_connection_angle(cell, other, angle) {
+            self.dragging = None;
+        }
+    }
+}
+
+impl TileRenderer for ConnectionHandleTile {
+    /// Drawn over the cells.
+    fn phase(&self) -> RenderPhase {
+        RenderPhase::Overlay
+    }
+
+    /// Called once to initialize the renderer.
+    fn init(&self, queue: &wgpu::Queue) {
+        self.debug.init(queue);
+    }
+
+    /// Called when the viewport or target size changes
+    fn resize(&mut self, size: Vec2, queue: &wgpu::Queue) {
+        self.debug.resize(size, queue);
+    }
+
+    /// Follows the view of the simulation tile.
+    fn update_view(&mut self, queue: &wgpu::Queue) {
+        self.debug.update_view(queue);
+    }
+
+    /// Rebuilds the handles from the current connections, dropping a drag whose cells are gone.
+    fn update_render_data(&mut self, state: &SimulationState, context: &GpuContext) {
+        let dragged = Self::resolve(state, self.dragging);
+        if dragged.is_none() {
+            self.dragging = None;
+        }
+        if self.debug.is_visible() {
+            self.debug.set_primitives(Self::handles(state, dragged, &self.debug.view()));
+        }
+        self.debug.update_render_data(state, context);
+    }
+
+    /// Encodes commands to render on the render pass.
+    fn render_pipeline<'a>(&'a self, render_pass: &mut RenderPass<'a>) {
+        self.debug.render_pipeline(render_pass);
+    }
+
+    /// Toggles, and grabs, drags, and releases handles while shown.
+    fn handle_event(&mut self, event: &TileEvent) {
+        self.debug.handle_event(event);
+        if let TileEvent::CursorMoved(cursor) = *event {
+            self.cursor = cursor;
+        }
+        if !self.debug.is_visible() {
+            self.dragging = None;
+            return;
+        }
+
+        match *event {
+            TileEvent::CursorMoved(_) => self.drag(),
+            TileEvent::MouseButton { pressed: true } => self.grab(),
+            TileEvent::MouseButton { pressed: false } => self.dragging = None,
+            _ => {}
+        }
+    }
+}
@@ -1,41 +1,333 @@
+use super::camera::{Camera, CameraAnimator, FitMode};
 use super::loaders::EnvironmentRenderLoader;
-use super::models::{gpu::*, space::*};
-use super::renderer::TileRenderer;
+use super::models::{cpu::{Primitive, ShapeDesc}, gpu::*, space::*};
+use super::renderer::{RenderPhase, TileEvent, TileRenderer};
+use super::text::TextTile;
+use crate::core::features::ThemeConfig;
 use crate::core::sim::SimulationState;
 use crate::gpu::buffers::{BindInfo, BufferKind, GpuBuffer};
 use crate::gpu::context::GpuContext;
-use glam::{Vec2, vec2};
+use crate::utils::vector::Vec2d;
+use glam::{vec2, Mat4, Vec2};
+use wgpu::util::StagingBelt;
 use std::sync::{Arc, Mutex};
+use std::time::Instant;
 use crate::combine_code;
 
+/// How a `SimulationTile` draws its cells.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum RenderBackend {
+    /// Connected cells are blended through the per-fragment shape SDFs, outlining every `ShapeDesc`.
+    #[default]
+    Sdf,
+    /// Every cell is a filled circle mesh, ignoring its shape and connections.
+    /// Much cheaper per fragment when there are many cells (see `bench_render_backends`).
+    Mesh,
+}
+
+/// The view a `SimulationTile` is drawn with, shared with the overlays drawn over it
+/// so they line up with its cells while the camera animates and cells are interpolated.
+#[derive(Clone, Copy, Debug)]
+pub struct TileView {
+    /// View transform shown, following any camera transition in progress.
+    pub view: SrtTransform,
+    /// World point the cells are drawn relative to.
+    pub origin: Vec2d,
+    /// How far cells are drawn between the previous tick at 0 and the current one at 1.
+    pub alpha: f64,
+    /// Size of the tile in pixels, as of the last resize.
+    pub size: Vec2,
+}
+
+impl Default for TileView {
+    /// Returns the identity view of a one pixel tile, drawing the current tick from the world origin.
+    fn default() -> Self {
+        Self {
+            view: SrtTransform::default(),
+            origin: Vec2d::ZERO,
+            alpha: 1.0,
+            size: Vec2::ONE,
+        }
+    }
+}
+
+impl TileView {
+    /// Returns `view` measured from `origin`, where the uploaded cells are placed.
+    pub fn relative_view(&self) -> SrtTransform {
+        SrtTransform {
+            translate: (Vec2d::from(self.view.translate) - self.origin).to_glam(),
+            ..self.view
+        }
+    }
+
+    /// Returns the matrix mapping positions relative to `origin` to clip space.
+    pub fn relative_projection(&self) -> Mat4 {
+        self.relative_view().to_mat4().inverse()
+    }
+
+    /// Maps pixel coordinates within the tile, from its top-left corner with y pointing down,
+    /// to world space through the view shown.
+    pub fn screen_to_world(&self, screen: Vec2) -> Vec2 {
+        let clip = vec2(screen.x / self.size.x * 2.0 - 1.0, 1.0 - screen.y / self.size.y * 2.0);
+        self.view.to_mat4().project_point3(clip.extend(0.0)).truncate()
+    }
+}
+
+/// A `TileView` written by a `SimulationTile` and read by the overlays drawn over it.
+pub type SharedTileView = Arc<Mutex<TileView>>;
+
+/// GPU state shared by every `SimulationTile`.
+///
+/// Holds the render pipelines, their bind group layouts, and the unit quad and circle
+/// vertex buffers, none of which depend on the tile. Created once per
+/// `GpuContext` via `GpuContext::simulation_resources`.
+pub struct SimulationRenderResources {
+    /// The GPU render pipeline configured with shaders and fixed-function state.
+    pipeline: wgpu::RenderPipeline,
+
+    /// Pipeline drawing instanced circle meshes for `RenderBackend::Mesh`.
+    mesh_pipeline: wgpu::RenderPipeline,
+
+    /// Unit quad mesh stretched over each render instance's AABB.
+    vert_buff: GpuBuffer<GpuVertex>,
+
+    /// Unit circle mesh transformed by each circle instance.
+    circle_vert_buff: GpuBuffer<GpuVertex>,
+
+    // Layouts used to build each tile's bind groups:
+    projection_layout: wgpu::BindGroupLayout,
+    cell_data_layout: wgpu::BindGroupLayout,
+}
+
+impl SimulationRenderResources {
+    /// Bindings of the projection and edge uniforms (group 0).
+    const PROJECTION_BINDINGS: [BindInfo; 2] = [
+        BindInfo {
+            visibility: wgpu::ShaderStages::VERTEX,
+            kind: BufferKind::Uniform,
+        },
+        BindInfo {
+            visibility: wgpu::ShaderStages::FRAGMENT,
+            kind: BufferKind::Uniform,
+        },
+    ];
+
+    /// Default width in pixels over which SDF shape edges are smoothed.
+    pub const DEFAULT_EDGE_FEATHER: f32 = 1.0;
+
+    /// Bindings of the primitive index and primitive storage buffers (group 1).
+    const CELL_DATA_BINDINGS: [BindInfo; 2] = [
+        BindInfo {
+            visibility: wgpu::ShaderStages::FRAGMENT,
+            kind: BufferKind::Storage { read_only: true },
+        },
+        BindInfo {
+            visibility: wgpu::ShaderStages::FRAGMENT,
+            kind: BufferKind::Storage { read_only: true },
+        },
+    ];
+
+    /// Returns a unit circle of `ShapeDesc::CIRCLE_SEGMENTS` sides as a triangle fan
+    /// around the origin, unrolled into a CCW triangle list since wgpu has no fan topology.
+    pub fn circle_fan() -> Vec<GpuVertex> {
+        let outline = ShapeDesc::Circle.polygon_vertices(&SrtTransform::default());
+        (0..outline.len())
+            .flat_map(|i| [Vec2::ZERO, outline[i], outline[(i + 1) % outline.len()]])
+            .map(GpuVertex::from)
+            .collect()
+    }
+
+    /// Compiles shaders, creates the pipelines and bind group layouts,
+    /// and uploads the unit quad and circle meshes.
+    fn new(context: &GpuContext) -> Self {
+        let shader = context.device.create_shader_module(wgpu::ShaderModuleDescriptor {
+            label: Some("Environment Shader"),
+            source: wgpu::ShaderSource::Wgsl(combine_code!(
+                "../shaders/primitive_ren.wgsl",
+                "../shaders/primitive_utils.wgsl"
+            )),
+        });
+
+        let vert_buff = context.create_buffer(
+            wgpu::BufferUsages::VERTEX | wgpu::BufferUsages::COPY_DST,
+            "Unit Verts",
+            6,
+        );
+        vert_buff.write_array(&context.queue, &AABB::UNIT.corners().ccw_mesh());
+
+        let mesh_shader = context.device.create_shader_module(wgpu::ShaderModuleDescriptor {
+            label: Some("Circle Mesh Shader"),
+            source: wgpu::ShaderSource::Wgsl(combine_code!("../shaders/circle_mesh.wgsl")),
+        });
+
+        let circle_fan = Self::circle_fan();
+        let circle_vert_buff = context.create_buffer(
+            wgpu::BufferUsages::VERTEX | wgpu::BufferUsages::COPY_DST,
+            "Unit Circle Verts",
+            circle_fan.len(),
+        );
+        circle_vert_buff.write_array(&context.queue, &circle_fan);
+
+        let projection_layout = context.create_bind_layout(&Self::PROJECTION_BINDINGS);
+        let cell_data_layout = context.create_bind_layout(&Self::CELL_DATA_BINDINGS);
+
+        let pipeline = Self::create_pipeline(
+            context,
+            "Render Pipeline",
+            &shader,
+            &[&projection_layout, &cell_data_layout],
+            &[GpuVertex::desc(), GpuQuadRenderInstance::desc()],
+        );
+        // The mesh shader only needs the projection.
+        let mesh_pipeline = Self::create_pipeline(
+            context,
+            "Circle Mesh Pipeline",
+            &mesh_shader,
+            &[&projection_layout],
+            &[GpuVertex::desc(), GpuCircleInstance::desc()],
+        );
+
+        Self {
+            pipeline,
+            mesh_pipeline,
+            vert_buff,
+            circle_vert_buff,
+            projection_layout,
+            cell_data_layout,
+        }
+    }
+
+    /// Creates an alpha-blended triangle list pipeline drawing `shader`'s `vs_main` and `fs_main`
+    /// to the context's frames.
+    fn create_pipeline(
+        context: &GpuContext,
+        label: &'static str,
+        shader: &wgpu::ShaderModule,
+        bind_group_layouts: &[&wgpu::BindGroupLayout],
+        buffers: &[wgpu::VertexBufferLayout],
+    ) -> wgpu::RenderPipeline {
+        // Create the pipeline layout referencing the bind group layouts.
+        let render_pipeline_layout =
+            context.device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+                label: Some(label),
+                bind_group_layouts,
+                push_constant_ranges: &[],
+            });
+
+        // Create the render pipeline specifying shaders, vertex layouts, and rasterization state.
+        context.device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+            label: Some(label),
+            layout: Some(&render_pipeline_layout),
+            vertex: wgpu::VertexState {
+                module: shader,
+                entry_point: Some("vs_main"), // Vertex shader entry
+                buffers,
+                compilation_options: wgpu::PipelineCompilationOptions::default(),
+            },
+            fragment: Some(wgpu::FragmentState {
+                module: shader,
+                entry_point: Some("fs_main"), // Fragment shader entry
+                targets: &[Some(wgpu::ColorTargetState {
+                    format: context.surface_format,
+                    blend: Some(wgpu::BlendState::ALPHA_BLENDING),
+                    write_mask: wgpu::ColorWrites::ALL,
+                })],
+                compilation_options: wgpu::PipelineCompilationOptions::default(),
+            }),
+
+            primitive: wgpu::PrimitiveState {
+                topology: wgpu::PrimitiveTopology::TriangleList,
+                strip_index_format: None,
+                front_face: wgpu::FrontFace::Ccw,
+                cull_mode: Some(wgpu::Face::Back),
+                polygon_mode: wgpu::PolygonMode::Fill,
+                unclipped_depth: false,
+                conservative: false,
+            },
+
+            depth_stencil: None,
+            multisample: context.multisample_state(),
+            multiview: None,
+            cache: None,
+        })
+    }
+}
+
+impl GpuContext {
+    /// Returns the shared simulation render resources, creating them on first use.
+    pub fn simulation_resources(&self) -> Arc<SimulationRenderResources> {
+        self.simulation_resources
+            .get_or_init(|| Arc::new(SimulationRenderResources::new(self)))
+            .clone()
+    }
+}
+
 /// A tile responsible for rendering the simulation environment.
 ///
-/// This struct manages GPU buffers and a pipeline for rendering primitives
-/// that represent simulation entities. It tracks a world-space AABB,
-/// a camera transform, and maintains buffers for vertex data, instance
-/// data, primitive data, and uniform data.
+/// This struct manages the per-tile GPU buffers for rendering primitives
+/// that represent simulation entities. It tracks a `Camera` and the
+/// transform currently shown, and maintains buffers for instance data,
+/// primitive data, and uniform data.
 ///
-/// The rendering pipeline uses WGSL shaders combined from multiple shader files,
-/// and uses instanced rendering of quads to represent simulation objects.
+/// The pipeline and unit quad mesh are shared between tiles through
+/// `SimulationRenderResources`, and instanced rendering of quads is used
+/// to represent simulation objects.
 pub struct SimulationTile {
-    /// Axis-aligned bounding box defining the simulation world space for this tile.
-    worldspace: AABB,
+    /// Camera choosing the region of the simulation world shown in this tile.
+    camera: Camera,
 
-    /// Camera transform representing translation, rotation, and scale.
-    camera: SrtTransform,
+    /// View transform currently shown, which follows the camera's view of the tile.
+    view: SrtTransform,
 
-    /// The GPU render pipeline configured with shaders and fixed-function state.
-    pipeline: wgpu::RenderPipeline,
+    /// Size of the tile in pixels, as of the last resize.
+    size: Vec2,
+
+    /// Width over height of the tile, as of the last resize.
+    aspect: f32,
+
+    /// Cursor position in pixels from the tile's top-left corner, while over the tile.
+    cursor: Option<Vec2>,
+
+    /// Overlay describing the cell under the cursor.
+    tooltip: TextTile,
+
+    /// In-progress transition of `view` towards the camera's view, if any.
+    animator: Option<CameraAnimator>,
+
+    /// Whether the next change of the camera's view should be animated rather than snapped.
+    animate_next: bool,
+
+    /// Pipeline and mesh shared with other simulation tiles.
+    resources: Arc<SimulationRenderResources>,
 
     /// Loader responsible for preparing simulation data into GPU-friendly buffers.
     loader: EnvironmentRenderLoader,
 
-    // GPU Buffers for vertex data, instances, primitives, and uniforms:
-    vert_buff: GpuBuffer<GpuVertex>,
+    /// How cells are drawn.
+    backend: RenderBackend,
+
+    /// Whether cells are drawn relative to the view center rather than the world origin.
+    camera_relative: bool,
+
+    /// View shown, origin, and interpolation, published every frame for overlays.
+    shared_view: SharedTileView,
+
+    // GPU Buffers for instances, primitives, and uniforms:
     render_instance_buff: GpuBuffer<GpuQuadRenderInstance>,
     primitive_index_buff: GpuBuffer<GpuPrimitiveIndex>,
     primitive_buff: GpuBuffer<GpuPrimitive>,
+    circle_instance_buff: GpuBuffer<GpuCircleInstance>,
     projection_buff: GpuBuffer<[[f32; 4]; 4]>,
+    edge_buff: GpuBuffer<EdgeInfoUniform>,
+
+    /// Width in pixels over which SDF shape edges are smoothed.
+    edge_feather: f32,
+
+    /// Reused staging memory batching the per-frame instance and primitive uploads.
+    staging_belt: StagingBelt,
+
+    /// Whether to read the next uploaded primitives back and compare them with the loader's.
+    check_upload: bool,
 
     /// Number of instances to render in the current frame.
     instance_count: u32,
@@ -46,20 +338,14 @@ pub struct SimulationTile {
 }
 
 impl SimulationTile {
-    /// Constructs a new `SimulationTile` with specified size and GPU context.
+    /// Constructs a new `SimulationTile` initially viewing a world-space region
+    /// of `size / zoom` centered on `center`.
     ///
-    /// This initializes all GPU buffers, compiles shaders, sets up pipeline layout,
-    /// and prepares bind groups for uniform and storage buffers.
-    pub(crate) fn new(size: Vec2, context: &GpuContext) -> Self {
-        let worldspace = AABB::from_wh(size);
-
-        let shader = context.device.create_shader_module(wgpu::ShaderModuleDescriptor {
-            label: Some("Environment Shader"),
-            source: wgpu::ShaderSource::Wgsl(combine_code!(
-                "../shaders/primitive_ren.wgsl",
-                "../shaders/primitive_utils.wgsl"
-            ).into()),
-        });
+    /// This initializes the per-tile GPU buffers and bind groups,
+    /// reusing the context's shared `SimulationRenderResources`.
+    pub(crate) fn new(size: Vec2, zoom: f32, center: Vec2, context: &GpuContext) -> Self {
+        let camera = Camera::new(AABB::new(center, size * 0.5 / zoom));
+        let resources = context.simulation_resources();
 
         // Create GPU buffers with usage flags appropriate for vertex, uniform, or storage data.
         let projection_buff = context.create_buffer(
@@ -67,10 +353,10 @@ impl SimulationTile {
             "Projection Uniform",
             1,
         );
-        let vert_buff = context.create_buffer(
-            wgpu::BufferUsages::VERTEX | wgpu::BufferUsages::COPY_DST,
-            "Unit Verts",
-            6,
+        let edge_buff = context.create_buffer(
+            wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+            "Edge Uniform",
+            1,
         );
         let render_instance_buff = context.create_buffer(
             wgpu::BufferUsages::VERTEX | wgpu::BufferUsages::COPY_DST,
@@ -78,106 +364,60 @@ impl SimulationTile {
             100,
         );
 
+        // Storage buffers are also COPY_SRC so they can be read back for debugging.
         let primitive_index_buff = context.create_buffer(
-            wgpu::BufferUsages::STORAGE | wgpu::BufferUsages::COPY_DST,
+            wgpu::BufferUsages::STORAGE | wgpu::BufferUsages::COPY_DST | wgpu::BufferUsages::COPY_SRC,
             "Primitive Index Storage",
             100,
         );
         let primitive_buff = context.create_buffer(
-            wgpu::BufferUsages::STORAGE | wgpu::BufferUsages::COPY_DST,
+            wgpu::BufferUsages::STORAGE | wgpu::BufferUsages::COPY_DST | wgpu::BufferUsages::COPY_SRC,
             "Primitive Storage",
             100,
         );
+        let circle_instance_buff = context.create_buffer(
+            wgpu::BufferUsages::VERTEX | wgpu::BufferUsages::COPY_DST,
+            "Circle Instances",
+            100,
+        );
 
-        // Create bind groups and layouts for uniform and storage buffers.
-        let (projection_layout, projection_bind) = context.create_bind_data(&[(
-            &projection_buff.buffer,
-            BindInfo {
-                visibility: wgpu::ShaderStages::VERTEX,
-                kind: BufferKind::Uniform,
-            },
-        )]);
-
-        let (cell_data_layout, cell_data_bind) = context.create_bind_data(&[
-            (
-                &primitive_index_buff.buffer,
-                BindInfo {
-                    visibility: wgpu::ShaderStages::FRAGMENT,
-                    kind: BufferKind::Storage { read_only: true },
-                },
-            ),
-            (
-                &primitive_buff.buffer,
-                BindInfo {
-                    visibility: wgpu::ShaderStages::FRAGMENT,
-                    kind: BufferKind::Storage { read_only: true },
-                },
-            ),
-        ]);
-
-        // Create the pipeline layout referencing the bind group layouts.
-        let render_pipeline_layout =
-            context.device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
-                label: Some("Render Pipeline Layout"),
-                bind_group_layouts: &[&projection_layout, &cell_data_layout],
-                push_constant_ranges: &[],
-            });
-
-        // Create the render pipeline specifying shaders, vertex layouts, and rasterization state.
-        let render_pipeline =
-            context.device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
-                label: Some("Render Pipeline"),
-                layout: Some(&render_pipeline_layout),
-                vertex: wgpu::VertexState {
-                    module: &shader,
-                    entry_point: Some("vs_main"), // Vertex shader entry
-                    buffers: &[GpuVertex::desc(), GpuQuadRenderInstance::desc()],
-                    compilation_options: wgpu::PipelineCompilationOptions::default(),
-                },
-                fragment: Some(wgpu::FragmentState {
-                    module: &shader,
-                    entry_point: Some("fs_main"), // Fragment shader entry
-                    targets: &[Some(wgpu::ColorTargetState {
-                        format: context.surface_format,
-                        blend: Some(wgpu::BlendState::ALPHA_BLENDING),
-                        write_mask: wgpu::ColorWrites::ALL,
-                    })],
-                    compilation_options: wgpu::PipelineCompilationOptions::default(),
-                }),
-
-                primitive: wgpu::PrimitiveState {
-                    topology: wgpu::PrimitiveTopology::TriangleList,
-                    strip_index_format: None,
-                    front_face: wgpu::FrontFace::Ccw,
-                    cull_mode: Some(wgpu::Face::Back),
-                    polygon_mode: wgpu::PolygonMode::Fill,
-                    unclipped_depth: false,
-                    conservative: false,
-                },
-
-                depth_stencil: None,
-                multisample: wgpu::MultisampleState {
-                    count: 1,
-                    mask: !0,
-                    alpha_to_coverage_enabled: false,
-                },
-                multiview: None,
-                cache: None,
-            });
+        // Create bind groups against the shared layouts.
+        let projection_bind = context.create_bind_group(
+            &resources.projection_layout,
+            &[&projection_buff.buffer, &edge_buff.buffer],
+        );
+        let cell_data_bind = context.create_bind_group(
+            &resources.cell_data_layout,
+            &[&primitive_index_buff.buffer, &primitive_buff.buffer],
+        );
 
         Self {
-            worldspace,
-            camera: SrtTransform::default(),
+            camera,
+            view: SrtTransform::default(),
+            size: Vec2::ONE,
+            aspect: 1.0,
+            cursor: None,
+            tooltip: TextTile::new(context, Box::new(|_| String::new())),
+            animator: None,
+            animate_next: false,
 
-            pipeline: render_pipeline,
+            resources,
 
             loader: EnvironmentRenderLoader::new(),
+            backend: RenderBackend::default(),
+            camera_relative: false,
+            shared_view: SharedTileView::default(),
 
-            vert_buff,
             render_instance_buff,
             primitive_index_buff,
             primitive_buff,
+            circle_instance_buff,
             projection_buff,
+            edge_buff,
+            edge_feather: SimulationRenderResources::DEFAULT_EDGE_FEATHER,
+
+            staging_belt: StagingBelt::new(Self::STAGING_CHUNK_SIZE),
+            check_upload: false,
 
             instance_count: 0,
 
@@ -185,57 +425,524 @@ impl SimulationTile {
             projection_bind,
         }
     }
+
+    /// Size of each staging buffer chunk; larger uploads get a dedicated chunk.
+    const STAGING_CHUNK_SIZE: wgpu::BufferAddress = 1 << 20;
+
+    /// Offset of the tooltip from the hovered cell's center, in pixels.
+    const TOOLTIP_OFFSET: Vec2 = Vec2::new(16.0, 16.0);
+
+    /// Returns the camera of this tile.
+    pub fn camera(&self) -> &Camera {
+        &self.camera
+    }
+
+    /// Sets the visible worldspace to `bounds` plus padding, animating the transition.
+    /// Takes any bounds, such as all cells from `world_bounds` or a selection from `bounds_of`.
+    pub fn frame_all(&mut self, bounds: AABB) {
+        self.camera.frame(bounds);
+        self.animate_next = true;
+    }
+
+    /// Pans the view by `pan`, measured in view half-extents along the screen axes,
+    /// and rotates it by `rotate` radians.
+    pub fn move_camera(&mut self, pan: Vec2, rotate: f32) {
+        self.camera.pan(pan, self.aspect);
+        self.camera.rotate += rotate;
+    }
+
+    /// Returns the view shown, the loader's origin and interpolation, and the tile size.
+    fn tile_view(&self) -> TileView {
+        TileView {
+            view: self.view,
+            origin: self.loader.origin,
+            alpha: self.loader.alpha,
+            size: self.size,
+        }
+    }
+
+    /// Returns the view this tile draws with, kept up to date every frame,
+    /// for overlays that should line up with its cells.
+    pub fn shared_view(&self) -> SharedTileView {
+        self.shared_view.clone()
+    }
+
+    /// Uploads the projection matrix relative to the loader's origin and the edge feathering,
+    /// and publishes the view to the overlays.
+    fn write_projection(&self, queue: &wgpu::Queue) {
+        let view = self.tile_view();
+        *self.shared_view.lock().unwrap() = view;
+        self.projection_buff.write(queue, &mat4_to_gpu_mat(view.relative_projection()));
+        self.edge_buff.write(queue, &EdgeInfoUniform::new(self.edge_feather));
+    }
+
+    /// Moves `view` towards the camera's view, starting a transition if one was requested.
+    fn advance_camera(&mut self) {
+        let target = self.camera.view_transform(self.aspect);
+        if self.animate_next {
+            self.animate_next = false;
+            self.animator = Some(CameraAnimator::ease_out(self.view, target));
+        }
+
+        let now = Instant::now();
+        self.view = match &mut self.animator {
+            Some(animator) => {
+                // Follow target changes (e.g. window resizes) mid-transition.
+                animator.target = target;
+                animator.sample(now)
+            }
+            None => target,
+        };
+
+        if self.animator.as_ref().is_some_and(|animator| animator.is_finished(now)) {
+            self.animator = None;
+        }
+    }
+
+    /// Shows the stats of the cell under the cursor next to it, or hides the tooltip
+    /// if the cursor is off the tile or over empty space.
+    fn update_tooltip(&mut self, state: &SimulationState, queue: &wgpu::Queue) {
+        let hovered = self.cursor.and_then(|cursor| {
+            let world = self.camera.screen_to_world(cursor, self.size);
+            state.pick_at(world.into()).map(|id| state.cells.get(id))
+        });
+
+        let Some(cell) = hovered else {
+            self.tooltip.set_text("", queue);
+            return;
+        };
+
+        let text = format!(
+            "{}\nAGE: {:.1}\nENERGY: {:.2}\nVX: {:.2}\nVY: {:.2}",
+            cell.typ,
+            cell.age,
+            cell.resources.energy(),
+            cell.velocity.x,
+            cell.velocity.y,
+        );
+        let anchor = self.camera.world_to_screen(cell.position.to_glam(), self.size);
+        self.tooltip.set_origin(anchor + Self::TOOLTIP_OFFSET);
+        self.tooltip.set_text(&text, queue);
+    }
+
+    /// Returns how many render instances were culled as off-screen in the last update.
+    pub fn culled_count(&self) -> usize {
+        self.loader.culled_count
+    }
+
+    /// Reads the primitives uploaded for the current frame back from the GPU, for debugging.
+    pub fn read_back_primitives(&self, context: &GpuContext) -> Vec<GpuPrimitive> {
+        let mut primitives = self.primitive_buff.read_back(&context.device, &context.queue);
+        primitives.truncate(self.loader.gpu_primitives.len());
+        primitives
+    }
+
+    /// Reports whether the primitives read back from the GPU match the ones just uploaded.
+    fn check_uploaded_primitives(&self, context: &GpuContext) {
+        let expected = &self.loader.gpu_primitives;
+        let uploaded = self.read_back_primitives(context);
+        let mismatch = uploaded
+            .iter()
+            .zip(expected)
+            .position(|(a, b)| bytemuck::bytes_of(a) != bytemuck::bytes_of(b));
+        match mismatch {
+            None => println!("Upload check passed: {} primitives match.", expected.len()),
+            Some(i) => eprintln!("Upload check failed: primitive {i} of {} differs.", expected.len()),
+        }
+    }
+
+    /// Uploads the loader's circle instances for `RenderBackend::Mesh`.
+    fn upload_circles(&mut self, context: &GpuContext) {
+        let device = &context.device;
+        let circles = &self.loader.gpu_circle_instances;
+        self.circle_instance_buff.reserve(device, circles.len());
+        self.instance_count = circles.len() as u32;
+        self.circle_instance_buff.write_array(&context.queue, circles);
+    }
+
+    /// Recreates the cell data bind group from the current primitive buffers.
+    ///
+    /// Must be called whenever either buffer is reallocated, or the shader keeps
+    /// reading the old, stale buffer.
+    pub fn rebuild_cell_bind(&mut self, context: &GpuContext) {
+        self.cell_data_bind = context.create_bind_group(
+            &self.resources.cell_data_layout,
+            &[&self.primitive_index_buff.buffer, &self.primitive_buff.buffer],
+        );
+    }
+
+    /// Overrides how cell types are drawn.
+    pub fn set_theme(&mut self, theme: ThemeConfig) {
+        self.loader.theme = theme;
+    }
+
+    /// Sets how cells are drawn.
+    pub fn set_backend(&mut self, backend: RenderBackend) {
+        self.backend = backend;
+    }
+
+    /// Returns how cells are drawn.
+    pub fn backend(&self) -> RenderBackend {
+        self.backend
+    }
+
+    /// Sets the width in pixels over which SDF shape edges are smoothed; 0 leaves them unsmoothed.
+    pub fn set_edge_feather(&mut self, feather: f32) {
+        self.edge_feather = feather.max(0.0);
+    }
+
+    /// Sets whether cells are drawn relative to the view center, so they keep `f32` precision
+    /// and don't jitter when the simulation drifts far from the world origin.
+    pub fn set_camera_relative(&mut self, enabled: bool) {
+        self.camera_relative = enabled;
+    }
+
+    /// Sets how the worldspace is fitted to the tile.
+    pub fn set_fit_mode(&mut self, fit_mode: FitMode) {
+        self.camera.fit_mode = fit_mode;
+    }
 }
 
 impl TileRenderer for SimulationTile {
     /// Called once to initialize the renderer.
     fn init(&self, queue: &wgpu::Queue) {
-        self.vert_buff
-            .write_array(&queue, &AABB::UNIT.corners().ccw_mesh());
         self.projection_buff
-            .write(&queue, &mat4_to_gpu_mat(self.camera.to_mat4().inverse()))
+            .write(queue, &mat4_to_gpu_mat(self.camera.projection_matrix(self.aspect)))
     }
 
     /// Called when the viewport or target size changes
     fn resize(&mut self, size: Vec2, queue: &wgpu::Queue) {
-        let aspect = size.x / size.y;
-        let zoom = 10.0;
-        let center = vec2(0., 0.);
-
-        // Update camera transform to keep aspect ratio and zoom
-        self.camera = SrtTransform {
-            translate: center,
-            rotate: 0.0,
-            scale: vec2(zoom, zoom / aspect),
-        };
+        // Keep the previous aspect while a dimension is momentarily zero, avoiding a degenerate projection.
+        if size.x <= 0.0 || size.y <= 0.0 {
+            return;
+        }
+        self.size = size;
+        self.aspect = size.x / size.y;
+        self.tooltip.resize(size, queue);
+    }
 
-        // Upload updated projection matrix to uniform buffer
-        self.projection_buff
-            .write(&queue, &mat4_to_gpu_mat(self.camera.to_mat4().inverse()))
+    /// Advances any camera transition and uploads the projection matrix.
+    fn update_view(&mut self, queue: &wgpu::Queue) {
+        self.advance_camera();
+        self.write_projection(queue);
     }
 
     /// Updates render data based on simulation state.
-    fn update_render_data(&mut self, state: Arc<Mutex<SimulationState>>, queue: &wgpu::Queue) {
-        self.loader.run(state);
+    fn update_render_data(&mut self, state: &SimulationState, context: &GpuContext) {
+        let queue = &context.queue;
+
+        // Move the view to the origin when drawing relative to its center
+        self.loader.origin = if self.camera_relative { self.view.translate.into() } else { Vec2d::ZERO };
+        self.write_projection(queue);
+
+        let view = OBB::from_transform(self.tile_view().relative_view()).fit_aabb();
+        if self.backend == RenderBackend::Mesh {
+            self.loader.run_circles(state, Some(view));
+            self.update_tooltip(state, queue);
+            self.upload_circles(context);
+            return;
+        }
+        self.loader.run(state, Some(view));
+        self.update_tooltip(state, queue);
+
+        // Grow buffers to fit this frame's data, rebinding any storage buffer that moved
+        let device = &context.device;
+        self.render_instance_buff.reserve(device, self.loader.gpu_render_instances.len());
+        let indices_grew = self.primitive_index_buff.reserve(device, self.loader.gpu_primitive_indices.len());
+        let primitives_grew = self.primitive_buff.reserve(device, self.loader.gpu_primitives.len());
+        if indices_grew || primitives_grew {
+            self.rebuild_cell_bind(context);
+        }
 
         self.instance_count = self.loader.gpu_render_instances.len() as u32;
+
+        // Upload all three arrays with a single encoder and submission
+        let mut encoder = device.create_command_encoder(&wgpu::CommandEncoderDescriptor {
+            label: Some("Simulation Upload Encoder"),
+        });
+        let belt = &mut self.staging_belt;
         self.primitive_buff
-            .write_array(&queue, &self.loader.gpu_primitives);
+            .stage_array(device, belt, &mut encoder, &self.loader.gpu_primitives);
         self.primitive_index_buff
-            .write_array(&queue, &self.loader.gpu_primitive_indices);
+            .stage_array(device, belt, &mut encoder, &self.loader.gpu_primitive_indices);
         self.render_instance_buff
-            .write_array(&queue, &self.loader.gpu_render_instances);
+            .stage_array(device, belt, &mut encoder, &self.loader.gpu_render_instances);
+        belt.finish();
+        queue.submit(std::iter::once(encoder.finish()));
+        belt.recall();
+
+        if std::mem::take(&mut self.check_upload) {
+            self.check_uploaded_primitives(context);
+        }
     }
 
     /// Encodes commands to render on the render pass.
-    fn render_pipeline(&self, render_pass: &mut wgpu::RenderPass) {
-        render_pass.set_pipeline(&self.pipeline);
+    fn render_pipeline<'a>(&'a self, render_pass: &mut wgpu::RenderPass<'a>) {
+        match self.backend {
+            RenderBackend::Sdf => {
+                render_pass.set_pipeline(&self.resources.pipeline);
+                render_pass.set_bind_group(0, &self.projection_bind, &[]);
+                render_pass.set_bind_group(1, &self.cell_data_bind, &[]);
+
+                render_pass.set_vertex_buffer(0, self.resources.vert_buff.buffer.slice(..));
+                render_pass.set_vertex_buffer(1, self.render_instance_buff.buffer.slice(..));
+
+                render_pass.draw(0..6, 0..self.instance_count);
+            }
+            RenderBackend::Mesh => {
+                render_pass.set_pipeline(&self.resources.mesh_pipeline);
+                render_pass.set_bind_group(0, &self.projection_bind, &[]);
+
+                let circle_verts = &self.resources.circle_vert_buff;
+                render_pass.set_vertex_buffer(0, circle_verts.buffer.slice(..));
+                render_pass.set_vertex_buffer(1, self.circle_instance_buff.buffer.slice(..));
+
+                render_pass.draw(0..circle_verts.len as u32, 0..self.instance_count);
+            }
+        }
+
+        self.tooltip.render_pipeline(render_pass);
+    }
+
+    /// Handles camera commands and requests to check the next upload.
+    fn handle_event(&mut self, event: &TileEvent) {
+        match *event {
+            TileEvent::FrameBounds(bounds) => self.frame_all(bounds),
+            TileEvent::MoveCamera { pan, rotate } => self.move_camera(pan, rotate),
+            TileEvent::CycleColorMode => self.loader.color_mode = self.loader.color_mode.next(),
+            TileEvent::CycleFitMode => self.set_fit_mode(self.camera.fit_mode.next()),
+            TileEvent::Zoom { anchor, factor } => {
+                self.camera.zoom_at(self.camera.screen_to_world(anchor, self.size), factor);
+            }
+            TileEvent::CursorMoved(cursor) => self.cursor = cursor,
+            TileEvent::InterpolationAlpha(alpha) => self.loader.alpha = alpha,
+            TileEvent::CheckUpload => self.check_upload = true,
+            TileEvent::ToggleDebug | TileEvent::MouseButton { .. } => {}
+        }
+    }
+}
+
+/// Callback producing debug primitives from the current simulation state,
+/// placed relative to the view's origin and interpolated at its alpha.
+pub type PrimitiveSource = Box<dyn Fn(&SimulationState, &TileView) -> Vec<Primitive>>;
+
+/// A tile drawing arbitrary world-space primitives, such as force vectors or trajectory dots,
+/// through the simulation pipeline without needing cells.
+///
+/// Each primitive becomes its own render instance, bypassing connection grouping.
+/// It is drawn through the view of the `SimulationTile` it overlays, so primitives are given
+/// relative to that view's origin. Hidden until toggled with `TileEvent::ToggleDebug`.
+pub struct DebugPrimitiveTile {
+    /// View of the simulation tile the primitives are drawn over.
+    view: SharedTileView,
+
+    /// Whether the primitives are drawn.
+    visible: bool,
+
+    /// Primitives to draw relative to the view's origin, replaced each frame by `source` if one is set.
+    primitives: Vec<Primitive>,
+
+    /// Callback refreshing `primitives` from the simulation every frame, if any.
+    source: Option<PrimitiveSource>,
+
+    /// Pipeline and mesh shared with the simulation tiles.
+    resources: Arc<SimulationRenderResources>,
+
+    // GPU Buffers for instances, primitives, and uniforms:
+    render_instance_buff: GpuBuffer<GpuQuadRenderInstance>,
+    primitive_index_buff: GpuBuffer<GpuPrimitiveIndex>,
+    primitive_buff: GpuBuffer<GpuPrimitive>,
+    projection_buff: GpuBuffer<[[f32; 4]; 4]>,
+    edge_buff: GpuBuffer<EdgeInfoUniform>,
+
+    /// Number of instances to render in the current frame.
+    instance_count: u32,
+
+    // Bind groups for uniform and storage buffers passed to shaders:
+    cell_data_bind: wgpu::BindGroup,
+    projection_bind: wgpu::BindGroup,
+}
+
+impl DebugPrimitiveTile {
+    /// Constructs a hidden `DebugPrimitiveTile` drawn through the shared `view`,
+    /// optionally refreshing its primitives from the simulation with `source`.
+    pub(crate) fn new(view: SharedTileView, source: Option<PrimitiveSource>, context: &GpuContext) -> Self {
+        let resources = context.simulation_resources();
+
+        let projection_buff = context.create_buffer(
+            wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+            "Debug Projection Uniform",
+            1,
+        );
+        let edge_buff = context.create_buffer(
+            wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+            "Debug Edge Uniform",
+            1,
+        );
+        let render_instance_buff = context.create_buffer(
+            wgpu::BufferUsages::VERTEX | wgpu::BufferUsages::COPY_DST,
+            "Debug Render Instances",
+            16,
+        );
+        let primitive_index_buff = context.create_buffer(
+            wgpu::BufferUsages::STORAGE | wgpu::BufferUsages::COPY_DST,
+            "Debug Primitive Index Storage",
+            16,
+        );
+        let primitive_buff = context.create_buffer(
+            wgpu::BufferUsages::STORAGE | wgpu::BufferUsages::COPY_DST,
+            "Debug Primitive Storage",
+            16,
+        );
+
+        let projection_bind = context.create_bind_group(
+            &resources.projection_layout,
+            &[&projection_buff.buffer, &edge_buff.buffer],
+        );
+        let cell_data_bind = context.create_bind_group(
+            &resources.cell_data_layout,
+            &[&primitive_index_buff.buffer, &primitive_buff.buffer],
+        );
+
+        Self {
+            view,
+            visible: false,
+            primitives: Vec::new(),
+            source,
+            resources,
+            render_instance_buff,
+            primitive_index_buff,
+            primitive_buff,
+            projection_buff,
+            edge_buff,
+            instance_count: 0,
+            cell_data_bind,
+            projection_bind,
+        }
+    }
+
+    /// Replaces the primitives drawn from the next frame on, given relative to the view's origin.
+    /// Overwritten every frame if the tile has a source.
+    pub fn set_primitives(&mut self, primitives: Vec<Primitive>) {
+        self.primitives = primitives;
+    }
+
+    /// Shows or hides the primitives.
+    pub fn set_visible(&mut self, visible: bool) {
+        self.visible = visible;
+    }
+
+    /// Returns whether the primitives are drawn.
+    pub fn is_visible(&self) -> bool {
+        self.visible
+    }
+
+    /// Returns the view the primitives are drawn through, as of the last update.
+    pub fn view(&self) -> TileView {
+        *self.view.lock().unwrap()
+    }
+
+    /// Uploads the projection of the shared view.
+    fn write_projection(&self, queue: &wgpu::Queue) {
+        self.projection_buff
+            .write(queue, &mat4_to_gpu_mat(self.view().relative_projection()));
+    }
+
+    /// Returns one render instance per primitive, each bounding just that primitive.
+    pub fn instances(primitives: &[Primitive]) -> Vec<GpuQuadRenderInstance> {
+        primitives
+            .iter()
+            .enumerate()
+            .map(|(i, primitive)| {
+                // Same margin as the simulation loader, so outlines aren't clipped.
+                let aabb = OBB::from_transform(primitive.transform).fit_aabb() * 1.2;
+                GpuQuadRenderInstance {
+                    aabb_center: aabb.center.to_array(),
+                    aabb_half: aabb.half.to_array(),
+                    start_i: i as u32,
+                    end_i: i as u32 + 1,
+                }
+            })
+            .collect()
+    }
+}
+
+impl TileRenderer for DebugPrimitiveTile {
+    /// Drawn over the cells.
+    fn phase(&self) -> RenderPhase {
+        RenderPhase::Overlay
+    }
+
+    /// Called once to initialize the renderer.
+    fn init(&self, queue: &wgpu::Queue) {
+        self.write_projection(queue);
+        self.edge_buff
+            .write(queue, &EdgeInfoUniform::new(SimulationRenderResources::DEFAULT_EDGE_FEATHER));
+    }
+
+    /// Sized along with the simulation tile, which publishes its size in the shared view.
+    fn resize(&mut self, _size: Vec2, _queue: &wgpu::Queue) {}
+
+    /// Follows the view of the simulation tile, including camera transitions.
+    fn update_view(&mut self, queue: &wgpu::Queue) {
+        self.write_projection(queue);
+    }
+
+    /// Refreshes the primitives from the source, if any, and uploads them
+    /// with the projection for the simulation tile's current origin.
+    fn update_render_data(&mut self, state: &SimulationState, context: &GpuContext) {
+        if !self.visible {
+            self.instance_count = 0;
+            return;
+        }
+        if let Some(source) = &self.source {
+            self.primitives = source(state, &self.view());
+        }
+
+        let queue = &context.queue;
+        self.write_projection(queue);
+
+        let instances = Self::instances(&self.primitives);
+        let indices: Vec<GpuPrimitiveIndex> = (0..self.primitives.len()).map(GpuPrimitiveIndex::from).collect();
+        let primitives: Vec<GpuPrimitive> = self.primitives.iter().copied().map(GpuPrimitive::from).collect();
+
+        // Grow buffers to fit this frame's data, rebinding any storage buffer that moved
+        let device = &context.device;
+        self.render_instance_buff.reserve(device, instances.len());
+        let indices_grew = self.primitive_index_buff.reserve(device, indices.len());
+        let primitives_grew = self.primitive_buff.reserve(device, primitives.len());
+        if indices_grew || primitives_grew {
+            self.cell_data_bind = context.create_bind_group(
+                &self.resources.cell_data_layout,
+                &[&self.primitive_index_buff.buffer, &self.primitive_buff.buffer],
+            );
+        }
+
+        self.render_instance_buff.write_array(queue, &instances);
+        self.primitive_index_buff.write_array(queue, &indices);
+        self.primitive_buff.write_array(queue, &primitives);
+        self.instance_count = instances.len() as u32;
+    }
+
+    /// Encodes commands to render on the render pass.
+    fn render_pipeline<'a>(&'a self, render_pass: &mut wgpu::RenderPass<'a>) {
+        if self.instance_count == 0 {
+            return;
+        }
+        render_pass.set_pipeline(&self.resources.pipeline);
         render_pass.set_bind_group(0, &self.projection_bind, &[]);
         render_pass.set_bind_group(1, &self.cell_data_bind, &[]);
 
-        render_pass.set_vertex_buffer(0, self.vert_buff.buffer.slice(..));
+        render_pass.set_vertex_buffer(0, self.resources.vert_buff.buffer.slice(..));
         render_pass.set_vertex_buffer(1, self.render_instance_buff.buffer.slice(..));
 
         render_pass.draw(0..6, 0..self.instance_count);
     }
+
+    /// Toggles visibility; camera commands reach the simulation tile, whose view this follows.
+    fn handle_event(&mut self, event: &TileEvent) {
+        if let TileEvent::ToggleDebug = event {
+            self.visible = !self.visible;
+        }
+    }
 }
@@ -1,5 +1,10 @@
+pub mod background;
 pub mod border;
+pub mod camera;
+pub mod handles;
 pub mod layers;
-mod loaders;
+pub(crate) mod loaders;
 pub mod models;
-pub mod renderer;
\ No newline at end of file
+pub mod renderer;
+pub mod text;
+pub mod vectors;
\ No newline at end of file
@@ -1,10 +1,16 @@
 use super::space::SrtTransform;
+use glam::Vec2;
+use serde::{Deserialize, Serialize};
+use std::f32::consts::{PI, TAU};
 
 /// Offset used for distinguishing star-shaped polygons (e.g. pentagram vs pentagon).
 const STAR_OFFSET: u32 = 10;
 
+/// Radius of the inner points of star shapes, relative to the outer points.
+pub const STAR_INNER_RADIUS: f32 = 0.5;
+
 /// Enum representing various polygonal shapes and their star-shaped variants.
-#[derive(Clone, Copy, Debug)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
 #[repr(u32)]
 pub enum ShapeDesc {
     Circle = 0,
@@ -24,8 +30,56 @@ pub enum ShapeDesc {
     Decagram = 10 + STAR_OFFSET,
 }
 
+impl ShapeDesc {
+    /// Returns the number of outer points of a polygon or star, or `None` for a circle.
+    pub fn sides(&self) -> Option<u32> {
+        match *self as u32 {
+            0 => None,
+            n if n > STAR_OFFSET => Some(n - STAR_OFFSET),
+            n => Some(n),
+        }
+    }
+
+    /// Returns `true` for the star variants.
+    pub fn is_star(&self) -> bool {
+        *self as u32 > STAR_OFFSET
+    }
+
+    /// Number of segments approximating a circle in `polygon_vertices`.
+    pub const CIRCLE_SEGMENTS: u32 = 32;
+
+    /// Returns the outline of the shape mapped through `transform`, counter-clockwise.
+    ///
+    /// See `polygon_vertices_with_segments`; circles use `CIRCLE_SEGMENTS` segments.
+    pub fn polygon_vertices(&self, transform: &SrtTransform) -> Vec<Vec2> {
+        self.polygon_vertices_with_segments(transform, Self::CIRCLE_SEGMENTS)
+    }
+
+    /// Returns the outline of the shape mapped through `transform`, counter-clockwise.
+    ///
+    /// In unit space, polygons have their corners on the unit circle, starting on the +X axis,
+    /// as drawn by the shader. Stars alternate between those corners and inner points at
+    /// `STAR_INNER_RADIUS`, halfway between them. Circles are approximated by a regular
+    /// polygon of `circle_segments` sides.
+    pub fn polygon_vertices_with_segments(&self, transform: &SrtTransform, circle_segments: u32) -> Vec<Vec2> {
+        let corners = self.sides().unwrap_or(circle_segments.max(3));
+        let (points, step) = if self.is_star() {
+            (corners * 2, PI / corners as f32)
+        } else {
+            (corners, TAU / corners as f32)
+        };
+
+        (0..points)
+            .map(|i| {
+                let radius = if self.is_star() && i % 2 == 1 { STAR_INNER_RADIUS } else { 1.0 };
+                transform.transform_point(Vec2::from_angle(i as f32 * step) * radius)
+            })
+            .collect()
+    }
+}
+
 /// RGBA color representation.
-#[derive(Clone, Copy, Debug)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
 pub struct Color {
     pub r: u8,
     pub g: u8,
@@ -42,14 +96,77 @@ impl Color {
     pub const PURPLE: Color = Color { r: 128, g: 0, b: 128, a: 255 };
     pub const BLACK: Color = Color { r: 0, g: 0, b: 0, a: 255 };
     pub const GRAY: Color = Color { r: 128, g: 128, b: 128, a: 255 };
+
+    /// Creates an opaque color from 8-bit channels.
+    pub const fn rgb(r: u8, g: u8, b: u8) -> Color {
+        Color { r, g, b, a: 255 }
+    }
+
+    /// Creates a color from a packed `0xRRGGBBAA` value.
+    pub const fn from_hex(hex: u32) -> Color {
+        let [r, g, b, a] = hex.to_be_bytes();
+        Color { r, g, b, a }
+    }
+
+    /// Creates a color from normalized channels, clamping each to `0.0..=1.0`.
+    pub fn rgba_f32(r: f32, g: f32, b: f32, a: f32) -> Color {
+        let to_u8 = |c: f32| (c.clamp(0.0, 1.0) * 255.0).round() as u8;
+        Color { r: to_u8(r), g: to_u8(g), b: to_u8(b), a: to_u8(a) }
+    }
+
+    /// Returns the channels normalized to `0.0..=1.0`, as expected by shaders.
+    pub fn to_f32_array(self) -> [f32; 4] {
+        [self.r, self.g, self.b, self.a].map(|c| c as f32 / 255.0)
+    }
+
+    /// Returns the gray of the same luma (Rec. 601), keeping the alpha.
+    pub fn grayscale(self) -> Color {
+        let luma = (0.299 * self.r as f32 + 0.587 * self.g as f32 + 0.114 * self.b as f32).round() as u8;
+        Color { r: luma, g: luma, b: luma, a: self.a }
+    }
+
+    /// Linearly interpolates each channel towards `other`, with `t` clamped to `0.0..=1.0`.
+    pub fn lerp(self, other: Color, t: f32) -> Color {
+        let t = t.clamp(0.0, 1.0);
+        let mix = |a: u8, b: u8| (a as f32 + (b as f32 - a as f32) * t).round() as u8;
+        Color {
+            r: mix(self.r, other.r),
+            g: mix(self.g, other.g),
+            b: mix(self.b, other.b),
+            a: mix(self.a, other.a),
+        }
+    }
 }
 
 /// A drawable primitive shape with color and transformation.
 #[derive(Clone, Copy, Debug)]
 pub struct Primitive {
-    pub(crate) shape: ShapeDesc,
-    pub(crate) color: Color,
-    pub(crate) transform: SrtTransform,
+    pub shape: ShapeDesc,
+    pub color: Color,
+    pub transform: SrtTransform,
+}
+
+impl Primitive {
+    /// Creates a primitive with the given shape and color and an identity transform.
+    pub fn new(shape: ShapeDesc, color: Color) -> Self {
+        Self {
+            shape,
+            color,
+            transform: SrtTransform::default(),
+        }
+    }
+
+    /// Returns this primitive with its transform replaced.
+    pub fn with_transform(mut self, transform: SrtTransform) -> Self {
+        self.transform = transform;
+        self
+    }
+
+    /// Returns this primitive with its color replaced.
+    pub fn with_color(mut self, color: Color) -> Self {
+        self.color = color;
+        self
+    }
 }
 
 impl Default for Primitive {
@@ -69,6 +69,50 @@ impl GpuQuadRenderInstance {
     }
 }
 
+/// Instance data for drawing a primitive as a filled unit circle mesh.
+///
+/// Holds the primitive's transform as the world-space images of the unit axes plus a translation.
+#[repr(C)]
+#[derive(Copy, Clone, Debug, bytemuck::Pod, bytemuck::Zeroable)]
+pub struct GpuCircleInstance {
+    pub x_axis: [f32; 2],
+    pub y_axis: [f32; 2],
+    pub translation: [f32; 2],
+    pub color: [f32; 4],
+}
+
+impl GpuCircleInstance {
+    /// Vertex attributes for the instance buffer starting at location 5.
+    const ATTRIBUTES: [wgpu::VertexAttribute; 4] = wgpu::vertex_attr_array![
+        5 => Float32x2,
+        6 => Float32x2,
+        7 => Float32x2,
+        8 => Float32x4
+    ];
+
+    /// Returns the vertex buffer layout descriptor for instances.
+    pub fn desc() -> wgpu::VertexBufferLayout<'static> {
+        wgpu::VertexBufferLayout {
+            array_stride: size_of::<GpuCircleInstance>() as wgpu::BufferAddress,
+            step_mode: wgpu::VertexStepMode::Instance,
+            attributes: &Self::ATTRIBUTES,
+        }
+    }
+}
+
+impl From<Primitive> for GpuCircleInstance {
+    fn from(p: Primitive) -> Self {
+        let mat = p.transform.to_mat4();
+
+        GpuCircleInstance {
+            x_axis: [mat.x_axis.x, mat.x_axis.y],
+            y_axis: [mat.y_axis.x, mat.y_axis.y],
+            translation: [mat.w_axis.x, mat.w_axis.y],
+            color: p.color.to_f32_array(),
+        }
+    }
+}
+
 /// GPU representation of a primitive shape with transform and color.
 #[repr(C, align(16))]
 #[derive(Copy, Clone, Debug)]
@@ -85,12 +129,7 @@ unsafe impl bytemuck::Zeroable for GpuPrimitive {}
 impl From<Primitive> for GpuPrimitive {
     fn from(p: Primitive) -> Self {
         let transform = p.transform;
-        let color = [
-            p.color.r as f32 / 255.0,
-            p.color.g as f32 / 255.0,
-            p.color.b as f32 / 255.0,
-            p.color.a as f32 / 255.0,
-        ];
+        let color = p.color.to_f32_array();
         let shape = p.shape as u32;
 
         GpuPrimitive {
@@ -145,3 +184,55 @@ impl BorderInfoUniform {
         }
     }
 }
+
+/// Uniform buffer for the anti-aliasing of SDF shape edges.
+#[repr(C)]
+#[derive(Copy, Clone, bytemuck::Pod, bytemuck::Zeroable, Debug)]
+pub struct EdgeInfoUniform {
+    pub feather: f32,
+    _pad: [f32; 3], // Padding to the 16-byte minimum uniform size
+}
+
+impl EdgeInfoUniform {
+    /// Creates a new `EdgeInfoUniform` smoothing edges over `feather` pixels.
+    pub fn new(feather: f32) -> Self {
+        Self {
+            feather,
+            _pad: [0.0; 3],
+        }
+    }
+}
+
+/// Uniform buffer for text overlay rendering information.
+#[repr(C)]
+#[derive(Copy, Clone, bytemuck::Pod, bytemuck::Zeroable, Debug)]
+pub struct TextInfoUniform {
+    pub size: [f32; 2],
+    _pad: [f32; 2], // Padding so `color` starts on a 16-byte boundary
+    pub color: [f32; 4],
+}
+
+impl TextInfoUniform {
+    /// Creates a new `TextInfoUniform`.
+    pub fn new(size: Vec2, color: [f32; 4]) -> Self {
+        Self {
+            size: [size.x, size.y],
+            _pad: [0.0; 2],
+            color,
+        }
+    }
+}
+
+/// Uniform buffer for tile background rendering information.
+#[repr(C)]
+#[derive(Copy, Clone, bytemuck::Pod, bytemuck::Zeroable, Debug)]
+pub struct BackgroundInfoUniform {
+    pub color: [f32; 4],
+}
+
+impl BackgroundInfoUniform {
+    /// Creates a new `BackgroundInfoUniform`.
+    pub fn new(color: [f32; 4]) -> Self {
+        Self { color }
+    }
+}
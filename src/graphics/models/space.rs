@@ -1,5 +1,6 @@
 use crate::graphics::models::gpu::GpuVertex;
 use glam::{Mat4, Vec2};
+use std::f32::consts::{PI, TAU};
 use std::ops::{BitAnd, BitOr, Div, Mul};
 
 /// Represents a 2D Scale-Rotate-Translate transform.
@@ -55,13 +56,42 @@ impl SrtTransform {
         let scale = Mat4::from_scale(self.scale.extend(1.0));
         translation * rotation * scale
     }
+
+    /// Maps a point through the transform: scaled, then rotated, then translated.
+    pub fn transform_point(&self, point: Vec2) -> Vec2 {
+        self.translate + Vec2::from_angle(self.rotate).rotate(point * self.scale)
+    }
+
+    /// Linearly interpolates translation, rotation, and scale towards `other`.
+    /// Rotation turns the shorter way around, so normalized angles don't spin across the wrap.
+    pub fn lerp(self, other: Self, t: f32) -> Self {
+        let turn = Self::wrap_angle(other.rotate - self.rotate);
+        Self {
+            translate: self.translate.lerp(other.translate, t),
+            rotate: self.rotate + turn * t,
+            scale: self.scale.lerp(other.scale, t),
+        }
+    }
+
+    /// Returns this transform with its rotation wrapped into `[-PI, PI)`.
+    pub fn normalized(self) -> Self {
+        Self {
+            rotate: Self::wrap_angle(self.rotate),
+            ..self
+        }
+    }
+
+    /// Wraps an angle in radians into `[-PI, PI)`.
+    fn wrap_angle(angle: f32) -> f32 {
+        (angle + PI).rem_euclid(TAU) - PI
+    }
 }
 
 /// Axis-Aligned Bounding Box (AABB) in 2D.
 ///
 /// Defined by center and half-extents along X and Y axes.
 /// Used for spatial queries, culling, and bounding volume calculations.
-#[derive(Clone, Copy, Debug)]
+#[derive(Clone, Copy, Debug, PartialEq)]
 pub struct AABB {
     /// Center point of the bounding box
     pub center: Vec2,
@@ -188,8 +218,15 @@ impl AABB {
         AABB::from_edges(min, max)
     }
 
-    /// Returns a new AABB resized proportionally to fit the given aspect ratio,
-    /// expanding either width or height to match the ratio without shrinking.
+    /// Returns `true` if this AABB and another overlap or touch.
+    pub fn intersects(&self, other: &AABB) -> bool {
+        let delta = (self.center - other.center).abs();
+        let reach = self.half + other.half;
+        delta.x <= reach.x && delta.y <= reach.y
+    }
+
+    /// Returns the largest AABB with the given aspect ratio that fits inside this one,
+    /// shrinking either width or height to match the ratio.
     pub fn max_proportional(&self, aspect: f32) -> AABB {
         let dim = self.wh();
         let max_width_for_height = dim.y * aspect;
@@ -205,6 +242,23 @@ impl AABB {
         }
     }
 
+    /// Returns the smallest AABB with the given aspect ratio that contains this one,
+    /// expanding either width or height to match the ratio.
+    pub fn min_proportional(&self, aspect: f32) -> AABB {
+        let dim = self.wh();
+        let min_width_for_height = dim.y * aspect;
+        let min_height_for_width = dim.x / aspect;
+
+        AABB {
+            center: self.center,
+            half: if min_width_for_height >= dim.x {
+                Vec2::new(min_width_for_height / 2.0, self.half.y)
+            } else {
+                Vec2::new(self.half.x, min_height_for_width / 2.0)
+            },
+        }
+    }
+
     /// Returns a new AABB padded by `padding` units on all sides.
     pub fn add_padding(&self, padding: f32) -> AABB {
         AABB {
@@ -265,20 +319,19 @@ impl BitOr for AABB {
 }
 
 impl BitAnd for AABB {
-    type Output = AABB;
+    type Output = Option<AABB>;
 
-    /// Returns the intersection of two AABBs (overlapping region).
-    /// If no overlap exists, returns an empty box centered between min and max.
-    fn bitand(self, rhs: AABB) -> AABB {
+    /// Returns the intersection of two AABBs (overlapping region),
+    /// or `None` if they don't overlap with a positive area, including boxes that only touch.
+    fn bitand(self, rhs: AABB) -> Option<AABB> {
         let min = self.min().max(rhs.min());
         let max = self.max().min(rhs.max());
 
-        if min.x > max.x || min.y > max.y {
-            // No overlap; return empty AABB
-            return AABB::new((min + max) * 0.5, Vec2::ZERO);
+        if min.x >= max.x || min.y >= max.y {
+            return None;
         }
 
-        AABB::from_edges(min, max)
+        Some(AABB::from_edges(min, max))
     }
 }
 
@@ -296,6 +349,28 @@ pub struct QuadVerts {
 }
 
 impl QuadVerts {
+    /// Returns the corners of an oriented box, following its rotation.
+    pub fn from_obb(obb: OBB) -> Self {
+        obb.corners()
+    }
+
+    /// Returns the average of the four corners.
+    pub fn center(&self) -> Vec2 {
+        (self.tl + self.tr + self.bl + self.br) * 0.25
+    }
+
+    /// Returns the quad scaled by `factor` about its center.
+    pub fn scaled(&self, factor: f32) -> Self {
+        let center = self.center();
+        let scale = |corner: Vec2| center + (corner - center) * factor;
+        Self {
+            tl: scale(self.tl),
+            tr: scale(self.tr),
+            bl: scale(self.bl),
+            br: scale(self.br),
+        }
+    }
+
     /// Returns the max coordinates among all corners
     pub fn max(&self) -> Vec2 {
         let xs = [self.tl.x, self.tr.x, self.bl.x, self.br.x];
@@ -367,6 +442,31 @@ impl QuadVerts {
             self.tr.into(), self.br.into(), self.bl.into(),
         ]
     }
+
+    /// Returns the ring between this quad and a nested `inner` quad as eight triangles,
+    /// one quad per edge. Works for rotated quads as long as the corners correspond.
+    pub fn frame_mesh(&self, inner: &QuadVerts) -> [GpuVertex; 24] {
+        let (outer, inner) = (self, inner);
+        let v = |pos: Vec2| GpuVertex::new(pos);
+
+        [
+            // Top quad (2 triangles)
+            v(outer.tl), v(outer.tr), v(inner.tr),
+            v(inner.tr), v(inner.tl), v(outer.tl),
+
+            // Right quad
+            v(outer.tr), v(outer.br), v(inner.br),
+            v(inner.br), v(inner.tr), v(outer.tr),
+
+            // Bottom quad
+            v(outer.br), v(outer.bl), v(inner.bl),
+            v(inner.bl), v(inner.br), v(outer.br),
+
+            // Left quad
+            v(outer.bl), v(outer.tl), v(inner.tl),
+            v(inner.tl), v(inner.bl), v(outer.bl),
+        ]
+    }
 }
 
 /// Oriented Bounding Box in 2D.
@@ -383,31 +483,76 @@ pub struct OBB {
 }
 
 impl OBB {
+    /// Creates the OBB covering the unit square mapped through `transform`,
+    /// keeping its rotation.
+    pub fn from_transform(transform: SrtTransform) -> Self {
+        Self {
+            center: transform.translate,
+            half: transform.scale,
+            angle: transform.rotate,
+        }
+    }
+
+    /// Returns a new OBB expanded (or shrunk, if negative) by `padding` on every side,
+    /// keeping its rotation.
+    pub fn add_padding(&self, padding: f32) -> OBB {
+        OBB {
+            half: self.half + Vec2::splat(padding),
+            ..*self
+        }
+    }
+
     /// Computes the four corners of the OBB as a `QuadVerts` struct.
     pub fn corners(&self) -> QuadVerts {
-        let cos_a = self.angle.cos();
-        let sin_a = self.angle.sin();
+        self.frame().corners
+    }
+
+    /// Computes the corners and the unit edge axes in one pass,
+    /// evaluating the rotation's sine and cosine only once.
+    pub fn frame(&self) -> ObbFrame {
+        let (sin_a, cos_a) = self.angle.sin_cos();
 
-        let right = Vec2::new(cos_a, sin_a) * self.half.x;
-        let up = Vec2::new(-sin_a, cos_a) * self.half.y;
+        let axis_x = Vec2::new(cos_a, sin_a);
+        let axis_y = Vec2::new(-sin_a, cos_a);
+
+        let right = axis_x * self.half.x;
+        let up = axis_y * self.half.y;
 
         let tl = self.center - right + up;
         let tr = self.center + right + up;
         let bl = self.center - right - up;
         let br = self.center + right - up;
 
-        QuadVerts { tl, tr, bl, br }
+        ObbFrame {
+            corners: QuadVerts { tl, tr, bl, br },
+            axes: [axis_x, axis_y],
+        }
     }
 
-    /// Fits and returns an axis-aligned bounding box that fully contains this OBB.
+    /// Fits and returns an axis-aligned bounding box that fully contains this OBB,
+    /// projecting its half-extents onto the world axes.
     pub fn fit_aabb(&self) -> AABB {
-        let corners = self.corners();
-        AABB::from_edges(corners.min(), corners.max())
+        let [axis_x, axis_y] = self.frame().axes;
+        AABB::new(self.center, axis_x.abs() * self.half.x + axis_y.abs() * self.half.y)
     }
 }
 
-/// Represents a 2D camera with a rectangular viewport.
-struct Camera {
-    /// Viewport bounds as an AABB in world coordinates
-    viewport: AABB,
-}
\ No newline at end of file
+impl From<AABB> for OBB {
+    /// Creates an unrotated OBB covering the same region.
+    fn from(aabb: AABB) -> Self {
+        Self {
+            center: aabb.center,
+            half: aabb.half,
+            angle: 0.0,
+        }
+    }
+}
+
+/// Corners and edge axes of an `OBB`, as needed by separating-axis tests.
+#[derive(Clone, Copy, Debug)]
+pub struct ObbFrame {
+    /// The four corners of the box
+    pub corners: QuadVerts,
+    /// Unit vectors along the box's local X and Y edges
+    pub axes: [Vec2; 2],
+}
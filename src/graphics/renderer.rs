@@ -1,8 +1,8 @@
 use crate::gpu::context::GpuContext;
 use glam::Vec2;
-use std::sync::{Arc, Mutex};
 use wgpu::RenderPass;
 use crate::core::sim::SimulationState;
+use super::models::space::AABB;
 
 /// Holds the data needed to render a single frame,
 /// including the texture to draw to, command encoder, and view.
@@ -10,19 +10,28 @@ pub struct FrameContext {
     pub surface_texture: wgpu::SurfaceTexture,
     pub encoder: wgpu::CommandEncoder,
     pub view: wgpu::TextureView,
+
+    /// Multisampled target drawn to and resolved into `view`, if MSAA is enabled.
+    pub msaa_view: Option<wgpu::TextureView>,
 }
 
 impl FrameContext {
     /// Starts a render pass that clears the frame to black.
+    /// With MSAA, drawing goes to the multisampled target, which is resolved into the frame.
     pub fn begin_render_pass(&mut self) -> RenderPass {
+        let (view, resolve_target, store) = match &self.msaa_view {
+            Some(msaa_view) => (msaa_view, Some(&self.view), wgpu::StoreOp::Discard),
+            None => (&self.view, None, wgpu::StoreOp::Store),
+        };
+
         self.encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
             label: Some("Render Pass"),
             color_attachments: &[Some(wgpu::RenderPassColorAttachment {
-                view: &self.view,
-                resolve_target: None,
+                view,
+                resolve_target,
                 ops: wgpu::Operations {
                     load: wgpu::LoadOp::Clear(wgpu::Color::BLACK),
-                    store: wgpu::StoreOp::Store,
+                    store,
                 },
             })],
             depth_stencil_attachment: None,
@@ -53,6 +62,7 @@ impl GpuContext {
             surface_texture,
             encoder,
             view: texture_view,
+            msaa_view: self.msaa_view.clone(),
         }
     }
 
@@ -64,6 +74,51 @@ impl GpuContext {
     }
 }
 
+/// Commands that can be sent to the render layers of a tile.
+#[derive(Clone, Copy, Debug)]
+pub enum TileEvent {
+    /// Fit the view to the given world-space bounds.
+    FrameBounds(AABB),
+    /// Pan the view by `pan`, in view half-extents along the screen axes, and rotate it by `rotate` radians.
+    MoveCamera { pan: Vec2, rotate: f32 },
+    /// Switch to the next `ColorMode`.
+    CycleColorMode,
+    /// Switch the camera to the next `FitMode`.
+    CycleFitMode,
+    /// Zoom in by `factor`, or out if it is below one, keeping the world point under `anchor`,
+    /// in pixels from the tile's top-left corner, in place.
+    Zoom { anchor: Vec2, factor: f32 },
+    /// Show or hide debug overlays.
+    ToggleDebug,
+    /// The left mouse button was pressed or released.
+    MouseButton { pressed: bool },
+    /// Draw the simulation the given fraction of a tick past its previous state, towards its current one.
+    InterpolationAlpha(f64),
+    /// The cursor moved to the given pixel position from the tile's top-left corner,
+    /// or `None` if it left the tile.
+    CursorMoved(Option<Vec2>),
+    /// Read the next uploaded primitives back from the GPU and check them against the CPU copy.
+    CheckUpload,
+}
+
+/// Stage of a tile's drawing a render layer belongs to.
+///
+/// Layers of a tile are drawn in phase order, later phases over earlier ones;
+/// layers sharing a phase keep the order they were added in.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum RenderPhase {
+    /// Fills behind everything else, e.g. the background color.
+    Background,
+    /// The simulation's cells.
+    Cells,
+    /// Debug and editing aids drawn over the cells, e.g. force arrows and handles.
+    Overlay,
+    /// Text such as live stats.
+    Text,
+    /// The tile's frame, drawn last.
+    Border,
+}
+
 /// Interface for rendering tiles.
 ///
 /// Responsible for initialization, resizing, updating GPU data,
@@ -75,9 +130,22 @@ pub trait TileRenderer {
     /// Called when the viewport or target size changes
     fn resize(&mut self, size: Vec2, queue: &wgpu::Queue);
     
-    /// Updates render data based on simulation state.
-    fn update_render_data(&mut self, state: Arc<Mutex<SimulationState>>, queue: &wgpu::Queue);
+    /// Updates what doesn't depend on the simulation, such as camera transitions, every frame,
+    /// even when the simulation is busy and `update_render_data` is skipped. Nothing by default.
+    fn update_view(&mut self, _queue: &wgpu::Queue) {}
+
+    /// Updates render data based on simulation state, already locked by the caller.
+    /// Receives the whole context so renderers can reallocate GPU resources as data grows.
+    fn update_render_data(&mut self, state: &SimulationState, context: &GpuContext);
 
     /// Encodes commands to render on the render pass.
     fn render_pipeline<'a>(&'a self, render_pass: &mut RenderPass<'a>);
+
+    /// Handles a command sent to the tile. Ignored by default.
+    fn handle_event(&mut self, _event: &TileEvent) {}
+
+    /// Returns the phase the layer is drawn in. Cells by default.
+    fn phase(&self) -> RenderPhase {
+        RenderPhase::Cells
+    }
 }
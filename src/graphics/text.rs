@@ -0,0 +1,269 @@
+use crate::combine_code;
+use crate::gpu::buffers::{BindInfo, BufferKind, GpuBuffer};
+use crate::gpu::context::GpuContext;
+use super::models::{gpu::*, space::*};
+use super::renderer::{TileRenderer, RenderPhase};
+
+use glam::{vec2, Vec2};
+use wgpu::{BindGroup, Queue, ShaderStages};
+use crate::core::sim::SimulationState;
+
+/// Callback producing the overlay text from the current simulation state.
+pub type TextSource = Box<dyn Fn(&SimulationState) -> String>;
+
+/// A GPU-backed renderer for drawing a text overlay, by default in the top-left of a tile.
+///
+/// Text is rasterized on the CPU with a fixed-width 3x5 bitmap font,
+/// emitting one quad per lit font pixel. The text itself is pulled each
+/// frame from a user-supplied `TextSource` closure.
+pub struct TextTile {
+    pipeline: wgpu::RenderPipeline,
+    vert_buff: GpuBuffer<GpuVertex>,
+    info_buff: GpuBuffer<TextInfoUniform>,
+    info_bind: BindGroup,
+
+    /// Closure queried for the text to display.
+    source: TextSource,
+
+    /// Current tile size in pixels.
+    size: Vec2,
+
+    /// Position of the text's top-left corner, in pixels from the tile's top-left corner.
+    origin: Vec2,
+
+    /// Number of vertices written for the current frame.
+    vertex_count: u32,
+}
+
+impl TextTile {
+    /// Maximum number of visible glyphs the vertex buffer can hold.
+    const MAX_GLYPHS: usize = 256;
+
+    /// Screen pixels per font pixel.
+    const PIXEL_SCALE: f32 = 3.0;
+
+    /// Offset of the first glyph from the top-left tile corner, in pixels.
+    const MARGIN: f32 = 30.0;
+
+    /// Creates a new `TextTile` that displays the text returned by `source`.
+    pub fn new(context: &GpuContext, source: TextSource) -> Self {
+        // Compile the WGSL shader module for text rendering
+        let shader = context.device.create_shader_module(wgpu::ShaderModuleDescriptor {
+            label: Some("Text Shader"),
+            source: wgpu::ShaderSource::Wgsl(combine_code!(
+                "../shaders/text.wgsl"
+            )),
+        });
+
+        // Create the vertex buffer, sized for a fully lit glyph in every slot
+        let vert_buff = context.create_buffer(
+            wgpu::BufferUsages::VERTEX | wgpu::BufferUsages::COPY_DST,
+            "Text Vertices",
+            Self::MAX_GLYPHS * GLYPH_WIDTH * GLYPH_HEIGHT * 6,
+        );
+
+        // Create a uniform buffer holding tile size and text color
+        let info_buff = context.create_buffer::<TextInfoUniform>(
+            wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+            "Text Info",
+            1,
+        );
+
+        let (info_layout, info_bind) = context.create_bind_data(&[(
+            &info_buff.buffer,
+            BindInfo {
+                visibility: ShaderStages::VERTEX_FRAGMENT,
+                kind: BufferKind::Uniform,
+            },
+        )]);
+
+        let pipeline_layout = context.device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+            label: Some("Text Pipeline Layout"),
+            bind_group_layouts: &[&info_layout],
+            push_constant_ranges: &[],
+        });
+
+        let pipeline = context.device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+            label: Some("Text Pipeline"),
+            layout: Some(&pipeline_layout),
+            vertex: wgpu::VertexState {
+                module: &shader,
+                entry_point: Some("vs_main"),
+                buffers: &[GpuVertex::desc()],
+                compilation_options: wgpu::PipelineCompilationOptions::default(),
+            },
+            fragment: Some(wgpu::FragmentState {
+                module: &shader,
+                entry_point: Some("fs_main"),
+                targets: &[Some(wgpu::ColorTargetState {
+                    format: context.surface_format,
+                    blend: Some(wgpu::BlendState::ALPHA_BLENDING),
+                    write_mask: wgpu::ColorWrites::ALL,
+                })],
+                compilation_options: wgpu::PipelineCompilationOptions::default(),
+            }),
+            primitive: wgpu::PrimitiveState {
+                topology: wgpu::PrimitiveTopology::TriangleList,
+                strip_index_format: None,
+                front_face: wgpu::FrontFace::Ccw,
+                cull_mode: None,
+                polygon_mode: wgpu::PolygonMode::Fill,
+                unclipped_depth: false,
+                conservative: false,
+            },
+            depth_stencil: None,
+            multisample: context.multisample_state(),
+            multiview: None,
+            cache: None,
+        });
+
+        Self {
+            pipeline,
+            vert_buff,
+            info_buff,
+            info_bind,
+            source,
+            size: Vec2::ZERO,
+            origin: Vec2::splat(Self::MARGIN),
+            vertex_count: 0,
+        }
+    }
+
+    /// Moves the text's top-left corner to `origin`, in pixels from the tile's top-left corner.
+    pub fn set_origin(&mut self, origin: Vec2) {
+        self.origin = origin;
+    }
+
+    /// Rebuilds the glyph mesh to display `text`.
+    pub fn set_text(&mut self, text: &str, queue: &Queue) {
+        let vertices = Self::generate_text_mesh(text, self.size, self.origin);
+        self.vertex_count = vertices.len() as u32;
+        self.vert_buff.write_array(queue, &vertices);
+    }
+
+    /// Generates quad vertices for `text`, laid out from `origin` pixels off the top-left
+    /// of a tile of the given size.
+    ///
+    /// Coordinates are in pixels relative to the tile center, with +Y pointing up.
+    fn generate_text_mesh(text: &str, size: Vec2, origin: Vec2) -> Vec<GpuVertex> {
+        let origin = vec2(-size.x, size.y) * 0.5 + vec2(origin.x, -origin.y);
+        let advance = vec2(GLYPH_WIDTH as f32 + 1.0, GLYPH_HEIGHT as f32 + 1.0) * Self::PIXEL_SCALE;
+
+        let mut vertices = Vec::new();
+        let mut glyph_count = 0;
+
+        for (line_i, line) in text.lines().enumerate() {
+            for (col_i, c) in line.chars().enumerate() {
+                if glyph_count >= Self::MAX_GLYPHS {
+                    return vertices;
+                }
+                glyph_count += 1;
+
+                let glyph_origin = origin + vec2(col_i as f32 * advance.x, -(line_i as f32) * advance.y);
+
+                for (row, bits) in glyph(c).iter().enumerate() {
+                    for col in 0..GLYPH_WIDTH {
+                        if bits & (1 << (GLYPH_WIDTH - 1 - col)) == 0 {
+                            continue;
+                        }
+
+                        let min = glyph_origin + vec2(col as f32, -(row as f32 + 1.0)) * Self::PIXEL_SCALE;
+                        let pixel = AABB::from_edges(min, min + Vec2::splat(Self::PIXEL_SCALE));
+                        vertices.extend(pixel.corners().ccw_mesh());
+                    }
+                }
+            }
+        }
+
+        vertices
+    }
+}
+
+impl TileRenderer for TextTile {
+    /// Drawn over the cells and overlays.
+    fn phase(&self) -> RenderPhase {
+        RenderPhase::Text
+    }
+
+    /// Called once to initialize the renderer.
+    fn init(&self, _queue: &Queue) {}
+
+    /// Called when the viewport or target size changes.
+    fn resize(&mut self, size: Vec2, queue: &wgpu::Queue) {
+        self.size = size;
+        self.info_buff.write(queue, &TextInfoUniform::new(size, [1.0, 1.0, 1.0, 1.0]));
+    }
+
+    /// Pulls the overlay text from the simulation state and rebuilds the glyph mesh.
+    fn update_render_data(&mut self, state: &SimulationState, context: &GpuContext) {
+        let text = (self.source)(state);
+
+        self.set_text(&text, &context.queue);
+    }
+
+    /// Encodes commands to render on the render pass.
+    fn render_pipeline(&self, render_pass: &mut wgpu::RenderPass) {
+        if self.vertex_count == 0 {
+            return;
+        }
+        render_pass.set_pipeline(&self.pipeline);
+        render_pass.set_bind_group(0, &self.info_bind, &[]);
+        render_pass.set_vertex_buffer(0, self.vert_buff.buffer.slice(..));
+        render_pass.draw(0..self.vertex_count, 0..1);
+    }
+}
+
+/// Width of a font glyph in font pixels.
+const GLYPH_WIDTH: usize = 3;
+
+/// Height of a font glyph in font pixels.
+const GLYPH_HEIGHT: usize = 5;
+
+/// Returns the 3x5 bitmap for a character, one row per entry with the leftmost pixel in the high bit.
+/// Lowercase letters are drawn as uppercase; unsupported characters are drawn as `?`.
+fn glyph(c: char) -> [u8; GLYPH_HEIGHT] {
+    match c.to_ascii_uppercase() {
+        ' ' => [0b000, 0b000, 0b000, 0b000, 0b000],
+        '0' => [0b111, 0b101, 0b101, 0b101, 0b111],
+        '1' => [0b010, 0b110, 0b010, 0b010, 0b111],
+        '2' => [0b111, 0b001, 0b111, 0b100, 0b111],
+        '3' => [0b111, 0b001, 0b111, 0b001, 0b111],
+        '4' => [0b101, 0b101, 0b111, 0b001, 0b001],
+        '5' => [0b111, 0b100, 0b111, 0b001, 0b111],
+        '6' => [0b111, 0b100, 0b111, 0b101, 0b111],
+        '7' => [0b111, 0b001, 0b001, 0b001, 0b001],
+        '8' => [0b111, 0b101, 0b111, 0b101, 0b111],
+        '9' => [0b111, 0b101, 0b111, 0b001, 0b111],
+        'A' => [0b010, 0b101, 0b111, 0b101, 0b101],
+        'B' => [0b110, 0b101, 0b110, 0b101, 0b110],
+        'C' => [0b011, 0b100, 0b100, 0b100, 0b011],
+        'D' => [0b110, 0b101, 0b101, 0b101, 0b110],
+        'E' => [0b111, 0b100, 0b110, 0b100, 0b111],
+        'F' => [0b111, 0b100, 0b110, 0b100, 0b100],
+        'G' => [0b011, 0b100, 0b101, 0b101, 0b011],
+        'H' => [0b101, 0b101, 0b111, 0b101, 0b101],
+        'I' => [0b111, 0b010, 0b010, 0b010, 0b111],
+        'J' => [0b001, 0b001, 0b001, 0b101, 0b010],
+        'K' => [0b101, 0b101, 0b110, 0b101, 0b101],
+        'L' => [0b100, 0b100, 0b100, 0b100, 0b111],
+        'M' => [0b101, 0b111, 0b111, 0b101, 0b101],
+        'N' => [0b110, 0b101, 0b101, 0b101, 0b101],
+        'O' => [0b010, 0b101, 0b101, 0b101, 0b010],
+        'P' => [0b110, 0b101, 0b110, 0b100, 0b100],
+        'Q' => [0b010, 0b101, 0b101, 0b110, 0b011],
+        'R' => [0b110, 0b101, 0b110, 0b101, 0b101],
+        'S' => [0b011, 0b100, 0b010, 0b001, 0b110],
+        'T' => [0b111, 0b010, 0b010, 0b010, 0b010],
+        'U' => [0b101, 0b101, 0b101, 0b101, 0b111],
+        'V' => [0b101, 0b101, 0b101, 0b101, 0b010],
+        'W' => [0b101, 0b101, 0b111, 0b111, 0b101],
+        'X' => [0b101, 0b101, 0b010, 0b101, 0b101],
+        'Y' => [0b101, 0b101, 0b010, 0b010, 0b010],
+        'Z' => [0b111, 0b001, 0b010, 0b100, 0b111],
+        ':' => [0b000, 0b010, 0b000, 0b010, 0b000],
+        '.' => [0b000, 0b000, 0b000, 0b000, 0b010],
+        '-' => [0b000, 0b000, 0b111, 0b000, 0b000],
+        '/' => [0b001, 0b001, 0b010, 0b100, 0b100],
+        _ => [0b111, 0b001, 0b010, 0b000, 0b010],
+    }
+}
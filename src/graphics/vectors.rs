@@ -0,0 +1,134 @@
+use super::layers::{DebugPrimitiveTile, SharedTileView, TileView};
+use super::models::cpu::{Color, Primitive, ShapeDesc};
+use super::models::space::SrtTransform;
+use super::renderer::{TileEvent, TileRenderer, RenderPhase};
+use crate::core::sim::SimulationState;
+use crate::gpu::context::GpuContext;
+
+use glam::Vec2;
+use wgpu::RenderPass;
+
+/// A debug overlay drawing each cell's velocity and last net force as arrows from its center.
+///
+/// Draws through a `DebugPrimitiveTile`, so it follows the simulation tile's view
+/// and stays hidden until toggled with `TileEvent::ToggleDebug`.
+pub struct VectorFieldTile {
+    /// Tile drawing the arrows rebuilt every frame.
+    debug: DebugPrimitiveTile,
+
+    /// World units of arrow length per unit of speed.
+    pub velocity_scale: f32,
+
+    /// World units of arrow length per unit of force.
+    pub force_scale: f32,
+}
+
+impl VectorFieldTile {
+    pub const VELOCITY_COLOR: Color = Color::YELLOW;
+    pub const FORCE_COLOR: Color = Color::RED;
+
+    /// Width of an arrow's shaft, in world units.
+    const SHAFT_WIDTH: f32 = 0.05;
+    /// Size of an arrow's head, in world units.
+    const HEAD_SIZE: f32 = 0.2;
+    /// Arrows shorter than this, in world units, are skipped.
+    const MIN_LENGTH: f32 = 1e-3;
+
+    /// Constructs a hidden `VectorFieldTile` drawn through the shared `view`.
+    pub fn new(view: SharedTileView, velocity_scale: f32, force_scale: f32, context: &GpuContext) -> Self {
+        Self {
+            debug: DebugPrimitiveTile::new(view, None, context),
+            velocity_scale,
+            force_scale,
+        }
+    }
+
+    /// Shows or hides the arrows.
+    pub fn set_visible(&mut self, visible: bool) {
+        self.debug.set_visible(visible);
+    }
+
+    /// Returns the velocity and force arrows of every cell, starting where `view` draws the cell.
+    pub fn vectors(&self, state: &SimulationState, view: &TileView) -> Vec<Primitive> {
+        state
+            .cells
+            .flatten_iter()
+            .flat_map(|cell| {
+                let start = cell.get_transform_interpolated(view.origin, view.alpha).translate;
+                let velocity = cell.velocity.to_glam() * self.velocity_scale;
+                let force = cell.last_force.to_glam() * self.force_scale;
+
+                Self::arrow(start, velocity, Self::VELOCITY_COLOR)
+                    .into_iter()
+                    .chain(Self::arrow(start, force, Self::FORCE_COLOR))
+                    .flatten()
+            })
+            .collect()
+    }
+
+    /// Returns the shaft and head of an arrow spanning `vector` from `start`,
+    /// or `None` if it is too short to see.
+    pub fn arrow(start: Vec2, vector: Vec2, color: Color) -> Option<[Primitive; 2]> {
+        let length = vector.length();
+        if !length.is_finite() || length < Self::MIN_LENGTH {
+            return None;
+        }
+        let rotate = vector.to_angle();
+
+        let shaft = Primitive {
+            transform: SrtTransform {
+                translate: start + vector * 0.5,
+                rotate,
+                scale: Vec2::new(length, Self::SHAFT_WIDTH),
+            },
+            ..Primitive::new(ShapeDesc::Square, color)
+        };
+        let head = Primitive {
+            transform: SrtTransform {
+                translate: start + vector,
+                rotate,
+                scale: Vec2::splat(Self::HEAD_SIZE),
+            },
+            ..Primitive::new(ShapeDesc::Triangle, color)
+        };
+        Some([shaft, head])
+    }
+}
+
+impl TileRenderer for VectorFieldTile {
+    /// Drawn over the cells.
+    fn phase(&self) -> RenderPhase {
+        RenderPhase::Overlay
+    }
+
+    /// Called once to initialize the renderer.
+    fn init(&self, queue: &wgpu::Queue) {
+        self.debug.init(queue);
+    }
+
+    /// Called when the viewport or target size changes
+    fn resize(&mut self, size: Vec2, queue: &wgpu::Queue) {
+        self.debug.resize(size, queue);
+    }
+
+    /// Follows the view of the simulation tile.
+    fn update_view(&mut self, queue: &wgpu::Queue) {
+        self.debug.update_view(queue);
+    }
+
+    /// Rebuilds the arrows from the current cell velocities and forces.
+    fn update_render_data(&mut self, state: &SimulationState, context: &GpuContext) {
+        self.debug.set_primitives(self.vectors(state, &self.debug.view()));
+        self.debug.update_render_data(state, context);
+    }
+
+    /// Encodes commands to render on the render pass.
+    fn render_pipeline<'a>(&'a self, render_pass: &mut RenderPass<'a>) {
+        self.debug.render_pipeline(render_pass);
+    }
+
+    /// Toggles visibility.
+    fn handle_event(&mut self, event: &TileEvent) {
+        self.debug.handle_event(event);
+    }
+}
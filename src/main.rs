@@ -8,6 +8,11 @@ mod app;
 
 use winit::event_loop::{ControlFlow, EventLoop};
 use crate::app::app::App;
+use crate::app::components::CsvLog;
+use crate::core::features::{CellType, ThemeConfig};
+use crate::core::genes::Gene;
+use crate::graphics::layers::RenderBackend;
+use crate::testing::benches;
 
 
 // entry code for application.
@@ -16,5 +21,46 @@ fn main() {
     let event_loop = EventLoop::new().unwrap();
     event_loop.set_control_flow(ControlFlow::Poll);
     let mut app = App::new();
+
+    // Log a snapshot every second of simulated time when a CSV path is given.
+    if let Ok(path) = std::env::var("CELLULAR_CSV") {
+        app.set_csv_log(CsvLog::create(path, 60).expect("Failed to create CSV log"));
+    }
+    // Grow the initial organism from its gene tree instead of placing its cells by hand,
+    // start from a lone cell of the named type, e.g. `CELLULAR_GENE=muscle`,
+    // or grow a gene read from a RON file, which is created with the default gene if missing.
+    if let Ok(gene) = std::env::var("CELLULAR_GENE") {
+        let gene = if gene == "lookn" {
+            benches::organism_lookn_gene()
+        } else if let Some(typ) = CellType::from_name(&gene) {
+            Gene::leaf_node(typ)
+        } else {
+            if !std::path::Path::new(&gene).exists() {
+                std::fs::write(&gene, benches::organism_lookn_gene().to_ron()).expect("Failed to write gene file");
+            }
+            let source = std::fs::read_to_string(&gene).expect("Failed to read gene file");
+            Gene::from_ron(&source).expect("Failed to parse gene file")
+        };
+        app.set_gene(&gene).expect("Gene grows too many cells");
+    }
+    // Draw cells with the colorblind palette, or a theme read from a RON file.
+    if let Ok(theme) = std::env::var("CELLULAR_THEME") {
+        let theme = match theme.as_str() {
+            "colorblind" => ThemeConfig::colorblind(),
+            path => {
+                let source = std::fs::read_to_string(path).expect("Failed to read theme file");
+                ThemeConfig::from_ron(&source).expect("Failed to parse theme file")
+            }
+        };
+        app.set_theme(theme);
+    }
+    // Draw every cell as a plain filled circle mesh, for large populations.
+    if std::env::var("CELLULAR_BACKEND").is_ok_and(|backend| backend == "mesh") {
+        app.set_render_backend(RenderBackend::Mesh);
+    }
+    // Limit the redraw rate to save power, e.g. `CELLULAR_FPS_CAP=30`.
+    if let Ok(cap) = std::env::var("CELLULAR_FPS_CAP") {
+        app.set_fps_cap(Some(cap.parse().expect("CELLULAR_FPS_CAP must be a number")));
+    }
     event_loop.run_app(&mut app).unwrap();
 }
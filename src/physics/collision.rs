@@ -0,0 +1,200 @@
+use super::objects::{Disk, Rectangle, RegularPolygon};
+use crate::utils::vector::Vec2d;
+use std::f64::consts::PI;
+
+/// Axis-aligned bounds given by their minimum and maximum corners.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Bounds {
+    pub min: Vec2d,
+    pub max: Vec2d,
+}
+
+impl Bounds {
+    /// Returns `true` if the two bounds overlap or touch.
+    pub fn overlaps(&self, other: &Bounds) -> bool {
+        self.min.x <= other.max.x
+            && other.min.x <= self.max.x
+            && self.min.y <= other.max.y
+            && other.min.y <= self.max.y
+    }
+}
+
+/// A convex shape that can be tested for collisions with any other.
+///
+/// Object safe, so mixed shapes can be stored and compared as `dyn CollisionShape`.
+/// Local shapes are centered on the origin; wrap them in `Placed` to move them into the world.
+pub trait CollisionShape {
+    /// Returns the point of the shape farthest along `dir`, the basis of GJK.
+    fn support(&self, dir: Vec2d) -> Vec2d;
+
+    /// Returns `true` if `point` lies inside the shape or on its boundary.
+    fn contains(&self, point: Vec2d) -> bool;
+
+    /// Returns the tightest axis-aligned bounds of the shape, used by the broad phase.
+    fn aabb(&self) -> Bounds {
+        Bounds {
+            min: Vec2d::new(self.support(Vec2d::new(-1.0, 0.0)).x, self.support(Vec2d::new(0.0, -1.0)).y),
+            max: Vec2d::new(self.support(Vec2d::new(1.0, 0.0)).x, self.support(Vec2d::new(0.0, 1.0)).y),
+        }
+    }
+}
+
+impl<S: CollisionShape + ?Sized> CollisionShape for Box<S> {
+    fn support(&self, dir: Vec2d) -> Vec2d {
+        (**self).support(dir)
+    }
+
+    fn contains(&self, point: Vec2d) -> bool {
+        (**self).contains(point)
+    }
+
+    fn aabb(&self) -> Bounds {
+        (**self).aabb()
+    }
+}
+
+impl CollisionShape for Disk {
+    fn support(&self, dir: Vec2d) -> Vec2d {
+        dir.normalize() * self.radius
+    }
+
+    fn contains(&self, point: Vec2d) -> bool {
+        point.length() <= self.radius
+    }
+
+    fn aabb(&self) -> Bounds {
+        Bounds {
+            min: Vec2d::new(-self.radius, -self.radius),
+            max: Vec2d::new(self.radius, self.radius),
+        }
+    }
+}
+
+impl CollisionShape for Rectangle {
+    fn support(&self, dir: Vec2d) -> Vec2d {
+        Vec2d::new(
+            0.5 * self.width.copysign(dir.x),
+            0.5 * self.height.copysign(dir.y),
+        )
+    }
+
+    fn contains(&self, point: Vec2d) -> bool {
+        point.x.abs() <= 0.5 * self.width && point.y.abs() <= 0.5 * self.height
+    }
+}
+
+impl RegularPolygon {
+    /// Returns the angle between neighboring vertices.
+    fn angle_per_side(&self) -> f64 {
+        2.0 * PI / self.sides as f64
+    }
+}
+
+/// Vertices lie on the circumradius, the first on the positive x axis, matching the shape rendered for it.
+impl CollisionShape for RegularPolygon {
+    fn support(&self, dir: Vec2d) -> Vec2d {
+        // The farthest vertex is the one nearest in angle to `dir`.
+        let step = self.angle_per_side();
+        let vertex = (dir.angle() / step).round();
+        Vec2d::from_angle(vertex * step) * self.radius
+    }
+
+    fn contains(&self, point: Vec2d) -> bool {
+        // Fold the point into the sector of its nearest edge, whose normal points to the edge's middle.
+        let step = self.angle_per_side();
+        let edge = (point.angle() / step).floor() + 0.5;
+        let apothem = self.radius * (0.5 * step).cos();
+        point.dot(Vec2d::from_angle(edge * step)) <= apothem
+    }
+}
+
+/// A shape moved to `position` and turned by `angle` radians.
+pub struct Placed<S> {
+    pub shape: S,
+    pub position: Vec2d,
+    pub angle: f64,
+}
+
+impl<S: CollisionShape> CollisionShape for Placed<S> {
+    fn support(&self, dir: Vec2d) -> Vec2d {
+        self.position + self.shape.support(dir.rotate(-self.angle)).rotate(self.angle)
+    }
+
+    fn contains(&self, point: Vec2d) -> bool {
+        self.shape.contains((point - self.position).rotate(-self.angle))
+    }
+}
+
+/// Returns `true` if the two shapes overlap or touch, checking their bounds before running GJK.
+pub fn collide(a: &dyn CollisionShape, b: &dyn CollisionShape) -> bool {
+    a.aabb().overlaps(&b.aabb()) && gjk_intersects(a, b)
+}
+
+/// Iterations after which GJK gives up and reports contact, reached only in degenerate touching cases.
+const GJK_MAX_ITERATIONS: usize = 32;
+
+/// Runs GJK, searching the Minkowski difference `a - b` for a simplex enclosing the origin.
+pub fn gjk_intersects(a: &dyn CollisionShape, b: &dyn CollisionShape) -> bool {
+    let support = |dir: Vec2d| a.support(dir) - b.support(-dir);
+
+    let first = support(Vec2d::new(1.0, 0.0));
+    let mut simplex = vec![first];
+    let mut dir = -first;
+
+    for _ in 0..GJK_MAX_ITERATIONS {
+        if dir == Vec2d::ZERO {
+            // The origin lies on the simplex itself.
+            return true;
+        }
+        let point = support(dir);
+        if point.dot(dir) < 0.0 {
+            // The difference doesn't reach past the origin in this direction, so it can't contain it.
+            return false;
+        }
+        simplex.push(point);
+        if evolve_simplex(&mut simplex, &mut dir) {
+            return true;
+        }
+    }
+    true
+}
+
+/// Reduces the simplex to the feature nearest the origin and points `dir` from it toward the origin.
+/// Returns `true` once a triangle encloses the origin. The newest point is last.
+fn evolve_simplex(simplex: &mut Vec<Vec2d>, dir: &mut Vec2d) -> bool {
+    // Returns the normal of `edge` on the side of `toward`.
+    let normal_toward = |edge: Vec2d, toward: Vec2d| {
+        let normal = edge.perp();
+        if normal.dot(toward) < 0.0 { -normal } else { normal }
+    };
+
+    match *simplex.as_slice() {
+        [b, a] => {
+            let (ab, ao) = (b - a, -a);
+            if ab.dot(ao) > 0.0 {
+                *dir = normal_toward(ab, ao);
+            } else {
+                *simplex = vec![a];
+                *dir = ao;
+            }
+            false
+        }
+        [c, b, a] => {
+            let (ab, ac, ao) = (b - a, c - a, -a);
+            let ab_out = -normal_toward(ab, ac);
+            let ac_out = -normal_toward(ac, ab);
+            if ab_out.dot(ao) > 0.0 {
+                *simplex = vec![b, a];
+                *dir = ab_out;
+                false
+            } else if ac_out.dot(ao) > 0.0 {
+                *simplex = vec![c, a];
+                *dir = ac_out;
+                false
+            } else {
+                true
+            }
+        }
+        _ => unreachable!("GJK simplex holds two or three points"),
+    }
+}
@@ -1,12 +1,15 @@
 use crate::core::elements::Cell;
+use crate::utils::data::Heap;
 use crate::utils::vector::Vec2d;
 
 /// Trait for objects that can have forces and torques applied to them,
-/// and can provide their position.
+/// and can provide their position and velocity.
 pub trait ForceAppl {
     fn apply_force(&mut self, force: Vec2d);
     fn apply_torque(&mut self, torque: f64);
     fn pos(&self) -> Vec2d;
+    fn vel(&self) -> Vec2d;
+    fn ang_vel(&self) -> f64;
 }
 
 /// Trait for objects that apply forces between two ForceAppl instances.
@@ -45,22 +48,60 @@ impl<'a, T: ForceAppl> ForceAppl for Lever<'a, T> {
     fn pos(&self) -> Vec2d {
         self.body.pos() + self.application
     }
+
+    /// Returns the velocity of the application point,
+    /// including the contribution of the body's rotation.
+    fn vel(&self) -> Vec2d {
+        self.body.vel() + self.application.perp() * self.body.ang_vel()
+    }
+
+    /// Returns the angular velocity of the body carrying the lever.
+    fn ang_vel(&self) -> f64 {
+        self.body.ang_vel()
+    }
 }
 
-/// A linear spring applying forces between two ForceAppl objects,
+/// A damped linear spring applying forces between two ForceAppl objects,
 /// based on Hooke's law.
 pub struct LinearSpring {
     pub length: f64,
     pub k: f64,
+    pub damping: f64,
+    /// Upper bound on the magnitude of the applied force, if any.
+    pub max_force: Option<f64>,
+}
+
+impl LinearSpring {
+    /// Creates a spring damped at `ratio` times the critical value for the given reduced mass;
+    /// below 1.0 it rings, above 1.0 it creeps back, and 0.0 leaves it undamped.
+    pub fn damped(length: f64, k: f64, reduced_mass: f64, ratio: f64) -> Self {
+        Self {
+            length,
+            k,
+            damping: ratio * 2.0 * (k * reduced_mass).sqrt(),
+            max_force: None,
+        }
+    }
+
+    /// Limits the magnitude of the force this spring can apply.
+    pub fn with_max_force(mut self, max_force: f64) -> Self {
+        self.max_force = Some(max_force);
+        self
+    }
 }
 
 impl<T: ForceAppl> ForceApplier<T> for LinearSpring {
-    /// Updates forces on two objects based on their distance and spring parameters.
+    /// Updates forces on two objects based on their distance, relative velocity, and spring parameters.
     fn tick(&mut self, a: &mut T, b: &mut T) {
         let delta = b.pos() - a.pos();
         let stretch = delta.length() - self.length;
-        let force_mag = -self.k * stretch;
+        // Coincident objects have no defined direction; `normalize` yields zero, so no force is applied.
         let force_dir = delta.normalize();
+        let stretch_rate = (b.vel() - a.vel()).dot(force_dir);
+        let mut force_mag = -self.k * stretch - self.damping * stretch_rate;
+        if let Some(max_force) = self.max_force {
+            force_mag = force_mag.clamp(-max_force, max_force);
+        }
         let force = force_dir * force_mag;
 
         a.apply_force(force * -1.0);
@@ -68,6 +109,68 @@ impl<T: ForceAppl> ForceApplier<T> for LinearSpring {
     }
 }
 
+/// A one-way constraint that pulls two objects together only while they are
+/// farther apart than `length`, and lets them move freely closer, like a rope.
+pub struct Rope {
+    pub length: f64,
+    pub k: f64,
+}
+
+impl<T: ForceAppl> ForceApplier<T> for Rope {
+    /// Pulls the objects together in proportion to how far the rope is stretched; slack ropes apply nothing.
+    fn tick(&mut self, a: &mut T, b: &mut T) {
+        let delta = b.pos() - a.pos();
+        let stretch = delta.length() - self.length;
+        if stretch <= 0.0 {
+            return;
+        }
+        let force = delta.normalize() * (self.k * stretch);
+
+        a.apply_force(force);
+        b.apply_force(force * -1.0);
+    }
+}
+
+/// Mutual inverse-square attraction between cells, proportional to their masses.
+#[derive(Clone, Copy, Debug)]
+pub struct GravityField {
+    /// Gravitational constant scaling every attraction.
+    pub g: f64,
+    /// Distance added in quadrature to each separation, bounding the force as cells overlap.
+    pub softening: f64,
+}
+
+impl GravityField {
+    /// Applies gravity between every pair of live cells. Cost grows quadratically with cell count.
+    pub fn apply_all(&mut self, cells: &mut Heap<Cell>) {
+        let ids: Vec<usize> = cells.iter_indexed().map(|(id, _)| id).collect();
+
+        for (i, &a) in ids.iter().enumerate() {
+            for &b in &ids[i + 1..] {
+                let (cell_a, cell_b) = cells.get_mut_pair(a, b);
+                self.tick(cell_a, cell_b);
+            }
+        }
+    }
+}
+
+impl ForceApplier<Cell> for GravityField {
+    /// Pulls two cells towards each other with a softened inverse-square force.
+    fn tick(&mut self, a: &mut Cell, b: &mut Cell) {
+        let delta = b.position - a.position;
+        let softened_sq = delta.dot(delta) + self.softening * self.softening;
+        if softened_sq <= 0.0 {
+            return;
+        }
+
+        // Dividing by the softened distance cubed both normalizes `delta` and applies 1/r^2.
+        let force = delta * (self.g * a.mass * b.mass / (softened_sq * softened_sq.sqrt()));
+
+        a.apply_force(force);
+        b.apply_force(-force);
+    }
+}
+
 impl ForceAppl for Cell {
     /// Adds force to the cell's force accumulator.
     fn apply_force(&mut self, force: Vec2d) {
@@ -81,4 +184,12 @@ impl ForceAppl for Cell {
     fn pos(&self) -> Vec2d {
         self.position
     }
+    /// Returns the cell's current velocity.
+    fn vel(&self) -> Vec2d {
+        self.velocity
+    }
+    /// Returns the cell's current angular velocity.
+    fn ang_vel(&self) -> f64 {
+        self.angular_velocity
+    }
 }
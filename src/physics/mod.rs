@@ -1,2 +1,3 @@
+pub mod collision;
 pub mod forces;
 pub mod objects;
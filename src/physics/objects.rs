@@ -48,3 +48,97 @@ impl ObjectData2D for Disk {
         0.5 * self.radius * self.radius * self.mass()
     }
 }
+
+/// Represents a solid annulus (disk with a concentric hole).
+pub struct Ring {
+    pub inner_radius: f64,
+    pub outer_radius: f64,
+    pub density: f64,
+}
+
+impl Ring {
+    /// Creates a ring from inner and outer radius and density.
+    pub fn new(inner_radius: f64, outer_radius: f64, density: f64) -> Self {
+        Self {
+            inner_radius,
+            outer_radius,
+            density,
+        }
+    }
+}
+
+impl ObjectData2D for Ring {
+    /// Calculates the ring's mass using its area and density.
+    fn mass(&self) -> f64 {
+        let area = PI * (self.outer_radius.powi(2) - self.inner_radius.powi(2));
+        area * self.density
+    }
+
+    /// Calculates rotational inertia of the ring (thick-walled cylinder formula).
+    fn rotational_inertia(&self) -> f64 {
+        0.5 * self.mass() * (self.inner_radius.powi(2) + self.outer_radius.powi(2))
+    }
+}
+
+/// Represents a solid rectangle rotating about its center.
+pub struct Rectangle {
+    pub width: f64,
+    pub height: f64,
+    pub density: f64,
+}
+
+impl Rectangle {
+    /// Creates a rectangle from width, height and density.
+    pub fn new(width: f64, height: f64, density: f64) -> Self {
+        Self {
+            width,
+            height,
+            density,
+        }
+    }
+}
+
+impl ObjectData2D for Rectangle {
+    /// Calculates the rectangle's mass using its area and density.
+    fn mass(&self) -> f64 {
+        self.width * self.height * self.density
+    }
+
+    /// Calculates rotational inertia of the rectangle about its center.
+    fn rotational_inertia(&self) -> f64 {
+        self.mass() * (self.width.powi(2) + self.height.powi(2)) / 12.0
+    }
+}
+
+/// Represents a solid regular polygon described by its circumradius.
+pub struct RegularPolygon {
+    pub sides: u32,
+    pub radius: f64,
+    pub density: f64,
+}
+
+impl RegularPolygon {
+    /// Creates a regular polygon from side count, circumradius and density.
+    pub fn new(sides: u32, radius: f64, density: f64) -> Self {
+        Self {
+            sides,
+            radius,
+            density,
+        }
+    }
+}
+
+impl ObjectData2D for RegularPolygon {
+    /// Calculates the polygon's mass using its area and density.
+    fn mass(&self) -> f64 {
+        let n = self.sides as f64;
+        let area = 0.5 * n * self.radius * self.radius * (2.0 * PI / n).sin();
+        area * self.density
+    }
+
+    /// Calculates rotational inertia of the polygon about its center.
+    fn rotational_inertia(&self) -> f64 {
+        let n = self.sides as f64;
+        self.mass() * self.radius * self.radius / 6.0 * (1.0 + 2.0 * (PI / n).cos().powi(2))
+    }
+}
@@ -0,0 +1,18 @@
+struct BackgroundInfo {
+    color: vec4<f32>,
+};
+
+@group(0) @binding(0)
+var<uniform> background: BackgroundInfo;
+
+// A single triangle covering the whole viewport, generated from the vertex index.
+@vertex
+fn vs_main(@builtin(vertex_index) index: u32) -> @builtin(position) vec4<f32> {
+    let uv = vec2(f32((index << 1u) & 2u), f32(index & 2u));
+    return vec4(uv * 2.0 - 1.0, 0.0, 1.0);
+}
+
+@fragment
+fn fs_main() -> @location(0) vec4<f32> {
+    return background.color;
+}
@@ -0,0 +1,36 @@
+struct VertexInput {
+    @location(0) unit_pos: vec2<f32>,
+};
+
+struct CircleInstance {
+    @location(5) x_axis: vec2<f32>,
+    @location(6) y_axis: vec2<f32>,
+    @location(7) translation: vec2<f32>,
+    @location(8) color: vec4<f32>,
+};
+
+@group(0) @binding(0)
+var<uniform> map_world_clip: mat4x4<f32>;
+
+struct FragmentInput {
+    @builtin(position) clip_pos: vec4<f32>,
+    @location(0) color: vec4<f32>,
+};
+
+@vertex
+fn vs_main(
+    vert: VertexInput,
+    instance: CircleInstance,
+) -> FragmentInput {
+    let world_pos = instance.x_axis * vert.unit_pos.x + instance.y_axis * vert.unit_pos.y + instance.translation;
+
+    var out: FragmentInput;
+    out.clip_pos = map_world_clip * vec4<f32>(world_pos, 0.0, 1.0);
+    out.color = instance.color;
+    return out;
+}
+
+@fragment
+fn fs_main(in: FragmentInput) -> @location(0) vec4<f32> {
+    return in.color;
+}
@@ -12,6 +12,13 @@ struct PrimitiveGroup {
 @group(0) @binding(0)
 var<uniform> map_world_clip: mat4x4<f32>;
 
+struct EdgeInfo {
+    feather: f32,
+};
+
+@group(0) @binding(1)
+var<uniform> edge_info: EdgeInfo;
+
 struct PrimitiveIndex {index: u32,
     _pad0: u32,
     _pad1: u32,
@@ -79,12 +86,7 @@ fn fs_main(in: FragmentInput) -> @location(0) vec4<f32> {
 
 // sdf
         let unit_pos = transform_2d_point(primitive.transform, in.world_pos);
-        var sdf: f32;
-        if (primitive.shape == 0u) {
-            sdf = circle_sdf(unit_pos);
-        } else {
-            sdf = regular_polygon_sdf(primitive.shape, unit_pos);
-        }
+        let sdf = shape_sdf(primitive.shape, unit_pos);
 
         let clamped_sdf = max(sdf, clamp_inside);
 
@@ -103,10 +105,13 @@ fn fs_main(in: FragmentInput) -> @location(0) vec4<f32> {
 
     let color = color_total / max(weight_total, 1e-6);
 
-    let edge0 = 0.0;
-    let edge1 = 0.1;
+    // Fade out over the outline width, or over `feather` pixels where that is thinner than them,
+    // so outlines don't alias when zoomed out.
+    let pixel = fwidth(blended_sdf);
+    let width = max(0.1, pixel * edge_info.feather);
 
-    let alpha = smoothstep(edge1, edge0, abs(blended_sdf));
+    // Edge coverage, faded by the blended primitive opacity.
+    let alpha = (1.0 - smoothstep(0.0, width, abs(blended_sdf))) * color.a;
 
     if (alpha < 1e-3) {
         discard;
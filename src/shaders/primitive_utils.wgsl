@@ -1,3 +1,19 @@
+// Offset of star shape ids from their polygons', matching `STAR_OFFSET` in `ShapeDesc`.
+const STAR_OFFSET: u32 = 10u;
+// Radius of the inner points of star shapes, matching `STAR_INNER_RADIUS` in `ShapeDesc`.
+const STAR_INNER_RADIUS: f32 = 0.5;
+
+// Signed distance to the unit shape with the given `ShapeDesc` id; negative inside.
+fn shape_sdf(shape: u32, p: vec2<f32>) -> f32 {
+    if (shape == 0u) {
+        return circle_sdf(p);
+    }
+    if (shape > STAR_OFFSET) {
+        return star_sdf(shape - STAR_OFFSET, STAR_INNER_RADIUS, p);
+    }
+    return regular_polygon_sdf(shape, p);
+}
+
 fn circle_sdf (pos: vec2<f32>) -> f32 {
     return length(pos) - 1.0;
 }
@@ -19,26 +35,22 @@ fn regular_polygon_sdf(n: u32, p: vec2<f32>) -> f32 {
     return edge_dist;
 }
 
-
+// Signed distance to a star with `n` outer points on the unit circle, starting on the +X axis,
+// and `n` inner points at `inner_radius` halfway between them, as in `ShapeDesc::polygon_vertices`.
 fn star_sdf(n: u32, inner_radius: f32, p: vec2<f32>) -> f32 {
     let pi = 3.141592653589793;
-    let angle = atan2(p.y, p.x);
-    let radius = length(p);
+    let step = pi / f32(n);
 
-    let total_points = 2u * n;
-    let angle_step = 2.0 * pi / f32(total_points);
+    // Fold p by symmetry into the wedge between an inner point on the +X axis and the next outer point.
+    let angle = abs(fmod(atan2(p.y, p.x), 2.0 * step) - step);
+    let q = length(p) * vec2(cos(angle), sin(angle));
 
-    let normalized_angle = fmod(angle + 2.0 * pi, 2.0 * pi);
+    let inner = vec2(inner_radius, 0.0);
+    let edge = vec2(cos(step), sin(step)) - inner;
+    let offset = q - inner;
+    let distance = length(offset - edge * clamp(dot(offset, edge) / dot(edge, edge), 0.0, 1.0));
 
-    // Figure out which point we're near (outer or inner)
-    let point_index = u32(floor(normalized_angle / angle_step));
-    let is_inner = (point_index % 2u) == 1u;
-    // Pick radius: outer = 1.0, inner = inner_radius
-    let r = select(1.0, inner_radius, is_inner);
-    // Get angle offset into current "wedge"
-    let local_angle = normalized_angle - f32(point_index) * angle_step;
-    let half_step = angle_step * 0.5;
-    // Signed distance approximation
-    let edge_dist = radius * cos(local_angle - half_step) - r * cos(half_step);
-    return edge_dist;
-}
\ No newline at end of file
+    // The origin lies to the left of the edge, so points there are inside.
+    let inside = edge.x * offset.y - edge.y * offset.x > 0.0;
+    return select(distance, -distance, inside);
+}
@@ -0,0 +1,24 @@
+struct TextInfo {
+    size: vec2<f32>,
+    color: vec4<f32>,
+};
+
+@group(0) @binding(0)
+var<uniform> text: TextInfo;
+
+struct FragmentInput {
+    @builtin(position) position: vec4<f32>,
+};
+
+@vertex
+fn vs_main(@location(0) position: vec2<f32>) -> FragmentInput {
+    var out: FragmentInput;
+    let ndc = (position / (text.size / 2));
+    out.position = vec4(ndc, 0.0, 1.0);
+    return out;
+}
+
+@fragment
+fn fs_main(in: FragmentInput) -> @location(0) vec4<f32> {
+    return text.color;
+}
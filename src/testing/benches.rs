@@ -1,7 +1,7 @@
-use crate::core::elements::CellConnection;
 use crate::core::sim::{SimContext, SimulationState};
 use crate::core::{elements::Cell, features::CellType, genes::Gene};
 use crate::graphics::models::space::AABB;
+use crate::utils::vector::Vec2d;
 use glam::Vec2;
 use rand::prelude::*;
 use std::f64::consts::TAU;
@@ -13,21 +13,21 @@ pub fn organism_lookn_cells(context: SimContext) -> SimulationState {
     let mut cell_alloc = SimulationState::new(context);
 
     // Insert cells at center and corners with different cell types
-    cell_alloc.cells.insert_alloc_vec(vec![
-        Cell::new(Vec2::new(0.0, 0.0).into(), CellType::Neural),
+    cell_alloc.spawn_vec(vec![
+        Cell::new(Vec2d::ZERO, CellType::Neural),
         Cell::new(bound.corners().bl.into(), CellType::Spore),
         Cell::new(bound.corners().br.into(), CellType::Intestinal),
         Cell::new(bound.corners().tl.into(), CellType::Muscle),
         Cell::new(bound.corners().tr.into(), CellType::Kidney),
-    ]);
+    ]).unwrap();
 
     let q = TAU / 4.0;
 
     // Connect the central neural cell to each corner cell
-    cell_alloc.connections.push(CellConnection::new(0, 0. * q, 1, 0.0));
-    cell_alloc.connections.push(CellConnection::new(0, 1. * q, 2, 0.0));
-    cell_alloc.connections.push(CellConnection::new(0, 2. * q, 3, 0.0));
-    cell_alloc.connections.push(CellConnection::new(0, 3. * q, 4, 0.0));
+    cell_alloc.connect(0, 0. * q, 1, 0.0).unwrap();
+    cell_alloc.connect(0, 1. * q, 2, 0.0).unwrap();
+    cell_alloc.connect(0, 2. * q, 3, 0.0).unwrap();
+    cell_alloc.connect(0, 3. * q, 4, 0.0).unwrap();
 
     cell_alloc
 }
@@ -62,9 +62,7 @@ pub fn organism_single_cell(context: SimContext) -> SimulationState {
     let mut state = SimulationState::new(context);
 
     // Insert one cell in the center
-    state.cells.insert_alloc_vec(vec![
-        Cell::new(center.into(), CellType::Fat)
-    ]);
+    state.spawn(Cell::new(center.into(), CellType::Fat)).unwrap();
 
     state
 }
\ No newline at end of file
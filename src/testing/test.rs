@@ -1,6 +1,39 @@
-use crate::graphics::models::space::SrtTransform;
-use glam::{Vec2, Vec4};
-use crate::utils::{algorithms::CSR, data::IdxPair};
+use crate::app::components::{FixedStepClock, TitleStats, UndoStack};
+use crate::app::tile::TileViewManager;
+use crate::gpu::context::GpuContext;
+use crate::graphics::renderer::{RenderPhase, TileRenderer};
+use crate::gpu::buffers::GpuBuffer;
+use crate::gpu::capture::downsample;
+use crate::graphics::camera::{Camera, FitMode};
+use crate::graphics::layers::{DebugPrimitiveTile, SimulationRenderResources, TileView};
+use crate::graphics::vectors::VectorFieldTile;
+use crate::graphics::loaders::{ColorMode, EnvironmentRenderLoader};
+use crate::graphics::models::cpu::{Color, ShapeDesc};
+use crate::graphics::models::gpu::{EdgeInfoUniform, GpuCircleInstance, GpuQuadRenderInstance, GpuVertex};
+use crate::graphics::models::space::{QuadVerts, SrtTransform, AABB, OBB};
+use glam::{vec2, Vec2, Vec4};
+use std::f64::consts::{PI, TAU};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use taffy::{Dimension, Size, Style};
+use crate::utils::{algorithms::CSR, data::{Heap, IdxPair}};
+use crate::core::sim::{ConnectError, SimContext, SimEvent, SimulationState, SpawnError};
+use crate::core::elements::{Cell, CellConnection, ConnectionKind};
+use crate::core::features::{CellType, ThemeConfig};
+use crate::core::genes::Gene;
+use crate::core::growth::{FAT_PER_SIZE, GROWTH_FAT_THRESHOLD, MAX_GROWTH_FACTOR};
+use crate::core::resources::LocalResources;
+use crate::physics::collision::{collide, CollisionShape, Placed};
+use crate::physics::forces::{ForceAppl, GravityField};
+use crate::physics::objects::{Disk, ObjectData2D, Rectangle, RegularPolygon, Ring};
+use crate::utils::grid::SpatialHash;
+use crate::utils::vector::Vec2d;
+use crate::testing::benches;
+
+/// Returns the simulation parameters shared by tests, matching the app's defaults.
+fn test_context() -> SimContext {
+    SimContext::builder().build()
+}
 
 /// Tests that transforming a point by an SrtTransform and then applying the inverse
 /// returns the original point (within floating point precision).
@@ -49,3 +82,2440 @@ fn test_csr() {
 
     assert_eq!(groups, expected_groups);
 }
+
+/// Tests that `connect` rejects invalid or duplicate connections
+/// and that `disconnect` works regardless of argument order.
+#[test]
+fn test_connect_validation() {
+    let mut state = benches::organism_lookn_cells(test_context());
+
+    assert_eq!(state.connect(1, 0.0, 1, 0.0), Err(ConnectError::SelfConnection(1)));
+    assert_eq!(state.connect(1, 0.0, 99, 0.0), Err(ConnectError::MissingCell(99)));
+    assert_eq!(state.connect(1, 0.0, 0, 0.0), Err(ConnectError::AlreadyConnected));
+
+    state.remove(4);
+    assert_eq!(state.connect(1, 0.0, 4, 0.0), Err(ConnectError::MissingCell(4)));
+
+    assert_eq!(state.connect(1, 0.0, 2, 0.0), Ok(()));
+    assert!(state.disconnect(2, 1));
+    assert!(!state.disconnect(1, 2));
+}
+
+/// Tests that `min_proportional` and `max_proportional` match the requested aspect ratio
+/// while respectively containing and fitting inside the original box.
+#[test]
+fn test_proportional_aabb() {
+    let world = AABB::from_wh(Vec2::new(15.0, 10.0));
+
+    for aspect in [0.5, 1.0, 16.0 / 9.0, 3.0] {
+        let contain = world.min_proportional(aspect);
+        let cover = world.max_proportional(aspect);
+
+        assert!((contain.width() / contain.height() - aspect).abs() < 1e-5);
+        assert!((cover.width() / cover.height() - aspect).abs() < 1e-5);
+
+        assert!(contain.width() >= world.width() && contain.height() >= world.height());
+        assert!(cover.width() <= world.width() && cover.height() <= world.height());
+    }
+}
+
+/// Tests that a gene tree survives a round trip through RON unchanged.
+#[test]
+fn test_gene_ron_round_trip() {
+    let gene = benches::organism_lookn_gene();
+    let parsed = Gene::from_ron(&gene.to_ron()).expect("Failed to parse gene RON");
+
+    assert_eq!(parsed, gene);
+}
+
+/// Tests that DOT export emits each undirected edge exactly once and skips self entries.
+#[test]
+fn test_csr_to_dot() {
+    let connections = vec![IdxPair::new(0, 1), IdxPair::new(1, 2)];
+    let dot = CSR::adjacent_from_connections(&connections, 2).to_dot_labeled(|node| Some(node.to_string()));
+
+    assert_eq!(dot.matches("--").count(), 2);
+    assert!(dot.contains("0 -- 1;"));
+    assert!(dot.contains("1 -- 2;"));
+    assert!(!dot.contains("1 -- 0;"));
+}
+
+/// Tests that the topology export labels live cells by type and leaves out removed ones.
+#[test]
+fn test_topology_dot() {
+    let mut state = SimulationState::new(test_context());
+    for x in 0..3 {
+        state.spawn(Cell::new(Vec2d::new(x as f64, 0.0), CellType::Muscle)).unwrap();
+    }
+    state.connect_auto(0, 1).unwrap();
+    state.connect_auto(1, 2).unwrap();
+    state.remove(2);
+
+    let dot = state.topology_dot();
+    assert!(dot.contains("0 [label=\"0: Muscle\"];"));
+    assert!(!dot.contains("2 [label"));
+    assert_eq!(dot.matches("--").count(), 1);
+}
+
+/// Tests that the read-only edge point matches where the edge lever applies its force.
+#[test]
+fn test_edge_point_matches_lever() {
+    let mut cell = Cell::new(Vec2d::new(1.5, -2.0), CellType::Muscle);
+    cell.angle = 0.7;
+
+    let edge = cell.edge_point(1.2);
+    assert!((edge.distance(cell.position) - cell.radius()).abs() < 1e-12);
+    assert_eq!(cell.edge_lever(1.2).pos(), edge);
+}
+
+/// Tests that the edge point of a spinning cell moves even when its center is still.
+#[test]
+fn test_lever_velocity_includes_spin() {
+    let mut cell = Cell::new(Vec2d::ZERO, CellType::Neural);
+    cell.angular_velocity = 2.0;
+
+    let lever = cell.edge_lever(0.0);
+    let expected = lever.application.perp() * 2.0;
+
+    assert!(lever.vel().length() > 0.0);
+    assert!((lever.vel() - expected).length() < 1e-12);
+}
+
+/// Tests that `connect_auto` points each connection angle at the other cell,
+/// relative to the cell's own rotation.
+#[test]
+fn test_connect_auto_angles() {
+    let mut state = SimulationState::new(test_context());
+    let q = TAU / 4.0;
+
+    let mut center = Cell::new(Vec2d::ZERO, CellType::Neural);
+    center.angle = 0.5;
+    let mut cells = vec![center];
+    for i in 0..4 {
+        cells.push(Cell::new(Vec2d::from_angle(i as f64 * q) * 2.0, CellType::Muscle));
+    }
+    state.spawn_vec(cells).unwrap();
+
+    for i in 0..4 {
+        state.connect_auto(0, i + 1).unwrap();
+
+        let connection = &state.connections[i];
+        let angle_error = |a: f64, b: f64| {
+            let diff = (a - b).rem_euclid(TAU);
+            diff.min(TAU - diff)
+        };
+        for angle in [connection.angle_a, connection.angle_b] {
+            assert!((-PI..PI).contains(&angle), "{angle} out of range");
+        }
+        assert!(angle_error(connection.angle_a, i as f64 * q - 0.5) < 1e-9);
+        assert!(angle_error(connection.angle_b, i as f64 * q + TAU / 2.0) < 1e-9);
+    }
+}
+
+/// Tests the hex, float, and interpolating `Color` constructors against the named constants.
+#[test]
+fn test_color_constructors() {
+    assert_eq!(Color::from_hex(0xFF0000FF), Color::RED);
+    assert_eq!(Color::rgb(128, 128, 128), Color::GRAY);
+    assert_eq!(Color::rgba_f32(0.0, 0.0, 1.0, 1.0), Color::BLUE);
+
+    assert_eq!(Color::BLACK.lerp(Color::RED, 0.0), Color::BLACK);
+    assert_eq!(Color::BLACK.lerp(Color::RED, 1.0), Color::RED);
+    assert_eq!(Color::BLACK.lerp(Color::from_hex(0xFEFEFEFF), 0.5), Color::rgb(127, 127, 127));
+}
+
+/// Tests that stable cell IDs keep resolving to the same cells across removal and compaction.
+#[test]
+fn test_uid_survives_compaction() {
+    let mut state = benches::organism_lookn_cells(test_context());
+    let kidney = state.cells.get(4).uid;
+    let removed = state.cells.get(1).uid;
+
+    state.remove(1);
+    state.compact();
+
+    assert!(state.by_uid(removed).is_none());
+    assert_eq!(state.id_of(kidney), Some(3));
+    assert!(matches!(state.by_uid(kidney).unwrap().typ, CellType::Kidney));
+    assert!(state.connections.iter().any(|c| c.links(0, 3)));
+}
+
+/// Tests that close pairs are found across grid buckets and match a brute-force search.
+#[test]
+fn test_close_pairs() {
+    let mut state = SimulationState::new(test_context());
+    let positions = [(0.0, 0.0), (0.9, 0.0), (1.1, 0.0), (-0.5, -0.5), (3.0, 3.0), (2.5, 3.0)];
+    for (x, y) in positions {
+        state.spawn(Cell::new(Vec2d::new(x, y), CellType::Neural)).unwrap();
+    }
+    state.remove(2);
+
+    let reach = 1.0;
+    let mut pairs = state.close_pairs(reach);
+    pairs.sort();
+    let mut expected = Vec::new();
+    for (a, cell_a) in state.cells.iter_indexed() {
+        for (b, cell_b) in state.cells.iter_indexed() {
+            if a < b && cell_a.position.distance(cell_b.position) <= reach {
+                expected.push((a, b));
+            }
+        }
+    }
+    assert_eq!(pairs, expected);
+    assert_eq!(pairs, vec![(0, 1), (0, 3), (4, 5)]);
+    assert!(state.close_pairs(0.0).is_empty());
+}
+
+/// Tests that compaction drops connections left behind by freeing a cell directly on the heap.
+#[test]
+fn test_compact_drops_stale_connections() {
+    let mut state = benches::organism_lookn_cells(test_context());
+    let kidney = state.cells.get(4).uid;
+    state.remove(1);
+    state.cells.free(2);
+
+    state.compact();
+
+    assert_eq!(state.cells.len(), 3);
+    assert_eq!(state.id_of(kidney), Some(2));
+    assert!(state.connections.iter().all(|c| state.cells.is_initialized(c.id_a) && state.cells.is_initialized(c.id_b)));
+}
+
+/// Micro-benchmark comparing separate `corners` + axis computation against the
+/// single-pass `OBB::frame`. Run with `cargo test bench_obb_frame -- --ignored --nocapture`.
+#[test]
+#[ignore]
+fn bench_obb_frame() {
+    use std::hint::black_box;
+    use std::time::Instant;
+
+    let boxes: Vec<OBB> = (0..10_000)
+        .map(|i| OBB { center: Vec2::splat(i as f32), half: Vec2::new(1.0, 2.0), angle: i as f32 * 0.01 })
+        .collect();
+
+    let start = Instant::now();
+    for _ in 0..100 {
+        for obb in &boxes {
+            let corners = obb.corners();
+            let axes = [Vec2::from_angle(obb.angle), Vec2::from_angle(obb.angle).perp()];
+            black_box((corners, axes));
+        }
+    }
+    let naive = start.elapsed();
+
+    let start = Instant::now();
+    for _ in 0..100 {
+        for obb in &boxes {
+            black_box(obb.frame());
+        }
+    }
+    let single_pass = start.elapsed();
+
+    println!("naive: {:?}, single pass: {:?}", naive, single_pass);
+}
+
+/// Benchmark comparing BFS grouping with union-find grouping on random graphs of increasing density.
+/// Run with `cargo test --release bench_grouping -- --ignored --nocapture`.
+#[test]
+#[ignore]
+fn bench_grouping() {
+    use rand::prelude::*;
+    use std::hint::black_box;
+    use std::time::Instant;
+
+    let mut rng = StdRng::seed_from_u64(7);
+    let nodes = 10_000;
+    for edges_per_node in [0.25, 0.5, 1.0, 2.0, 4.0] {
+        let connections: Vec<IdxPair> = (0..(nodes as f64 * edges_per_node) as usize)
+            .map(|_| IdxPair::new(rng.random_range(0..nodes), rng.random_range(0..nodes)))
+            .collect();
+
+        let start = Instant::now();
+        for _ in 0..100 {
+            black_box(CSR::groups_from_connections(&connections, nodes - 1));
+        }
+        let bfs = start.elapsed();
+
+        let start = Instant::now();
+        for _ in 0..100 {
+            black_box(CSR::groups_union_find(&connections, nodes - 1));
+        }
+        let union_find = start.elapsed();
+
+        println!("{edges_per_node} edges/node: bfs {bfs:?}, union-find {union_find:?}");
+    }
+}
+
+/// Benchmark comparing the SDF and mesh render backends drawing 10k unconnected cells
+/// into a 1024x1024 offscreen target, including the loader's CPU preparation.
+/// Run with `cargo test --release bench_render_backends -- --ignored --nocapture`.
+#[test]
+#[ignore]
+fn bench_render_backends() {
+    use rand::prelude::*;
+    use std::time::Instant;
+    use wgpu::util::DeviceExt;
+
+    const SIZE: u32 = 1024;
+    const FORMAT: wgpu::TextureFormat = wgpu::TextureFormat::Rgba8Unorm;
+    const FRAMES: u32 = 20;
+
+    let (device, queue) = headless_device().expect("No GPU adapter available");
+
+    let mut rng = rand::rngs::StdRng::seed_from_u64(3);
+    let bounds = AABB::UNIT * 100.0;
+    let mut state = SimulationState::new(test_context());
+    state.spawn_vec((0..10_000)
+        .map(|_| Cell::new(benches::random_pos_in_bounds(&mut rng, bounds).into(), CellType::Neural))
+        .collect()).unwrap();
+
+    let mut loader = EnvironmentRenderLoader::new();
+    let start = Instant::now();
+    for _ in 0..FRAMES {
+        loader.run(&state, None);
+    }
+    let sdf_load = start.elapsed() / FRAMES;
+    let start = Instant::now();
+    for _ in 0..FRAMES {
+        loader.run_circles(&state, None);
+    }
+    let mesh_load = start.elapsed() / FRAMES;
+    loader.run(&state, None);
+
+    let buffer = |label: &str, contents: &[u8], usage: wgpu::BufferUsages| {
+        device.create_buffer_init(&wgpu::util::BufferInitDescriptor { label: Some(label), contents, usage })
+    };
+    let projection = glam::Mat4::orthographic_rh(-100.0, 100.0, -100.0, 100.0, -1.0, 1.0).to_cols_array_2d();
+    let projection = buffer("Bench Projection", bytemuck::cast_slice(&projection), wgpu::BufferUsages::UNIFORM);
+    let edge = EdgeInfoUniform::new(SimulationRenderResources::DEFAULT_EDGE_FEATHER);
+    let edge = buffer("Bench Edge", bytemuck::bytes_of(&edge), wgpu::BufferUsages::UNIFORM);
+    let quad = buffer("Bench Quad", bytemuck::cast_slice(&AABB::UNIT.corners().ccw_mesh()), wgpu::BufferUsages::VERTEX);
+    let circle_fan = SimulationRenderResources::circle_fan();
+    let circle = buffer("Bench Circle", bytemuck::cast_slice(&circle_fan), wgpu::BufferUsages::VERTEX);
+    let quads = buffer("Bench Quads", bytemuck::cast_slice(&loader.gpu_render_instances), wgpu::BufferUsages::VERTEX);
+    let indices = buffer("Bench Indices", bytemuck::cast_slice(&loader.gpu_primitive_indices), wgpu::BufferUsages::STORAGE);
+    let primitives = buffer("Bench Primitives", bytemuck::cast_slice(&loader.gpu_primitives), wgpu::BufferUsages::STORAGE);
+    let quad_count = loader.gpu_render_instances.len() as u32;
+    loader.run_circles(&state, None);
+    let circles = buffer("Bench Circles", bytemuck::cast_slice(&loader.gpu_circle_instances), wgpu::BufferUsages::VERTEX);
+    let circle_count = loader.gpu_circle_instances.len() as u32;
+
+    let pipeline = |source: String, buffers: &[wgpu::VertexBufferLayout]| {
+        let shader = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+            label: Some("Bench Shader"),
+            source: wgpu::ShaderSource::Wgsl(source.into()),
+        });
+        device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+            label: Some("Bench Pipeline"),
+            layout: None,
+            vertex: wgpu::VertexState {
+                module: &shader,
+                entry_point: Some("vs_main"),
+                buffers,
+                compilation_options: Default::default(),
+            },
+            fragment: Some(wgpu::FragmentState {
+                module: &shader,
+                entry_point: Some("fs_main"),
+                targets: &[Some(wgpu::ColorTargetState {
+                    format: FORMAT,
+                    blend: Some(wgpu::BlendState::ALPHA_BLENDING),
+                    write_mask: wgpu::ColorWrites::ALL,
+                })],
+                compilation_options: Default::default(),
+            }),
+            primitive: Default::default(),
+            depth_stencil: None,
+            multisample: Default::default(),
+            multiview: None,
+            cache: None,
+        })
+    };
+    let sdf_pipeline = pipeline(
+        format!("{}\n{}", include_str!("../shaders/primitive_ren.wgsl"), include_str!("../shaders/primitive_utils.wgsl")),
+        &[GpuVertex::desc(), GpuQuadRenderInstance::desc()],
+    );
+    let mesh_pipeline = pipeline(
+        include_str!("../shaders/circle_mesh.wgsl").to_string(),
+        &[GpuVertex::desc(), GpuCircleInstance::desc()],
+    );
+    let bind = |pipeline: &wgpu::RenderPipeline, group: u32, buffers: &[&wgpu::Buffer]| {
+        let entries: Vec<_> = buffers
+            .iter()
+            .enumerate()
+            .map(|(i, buffer)| wgpu::BindGroupEntry { binding: i as u32, resource: buffer.as_entire_binding() })
+            .collect();
+        device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: Some("Bench Bind Group"),
+            layout: &pipeline.get_bind_group_layout(group),
+            entries: &entries,
+        })
+    };
+    let sdf_binds = [bind(&sdf_pipeline, 0, &[&projection, &edge]), bind(&sdf_pipeline, 1, &[&indices, &primitives])];
+    let mesh_binds = [bind(&mesh_pipeline, 0, &[&projection])];
+
+    let target = device.create_texture(&wgpu::TextureDescriptor {
+        label: Some("Bench Target"),
+        size: wgpu::Extent3d { width: SIZE, height: SIZE, depth_or_array_layers: 1 },
+        mip_level_count: 1,
+        sample_count: 1,
+        dimension: wgpu::TextureDimension::D2,
+        format: FORMAT,
+        usage: wgpu::TextureUsages::RENDER_ATTACHMENT,
+        view_formats: &[],
+    });
+    let view = target.create_view(&Default::default());
+
+    let time_frames = |pipeline: &wgpu::RenderPipeline, binds: &[wgpu::BindGroup], mesh: &wgpu::Buffer, vertices: u32, instances: (&wgpu::Buffer, u32)| {
+        // The first frame also includes compiling the pipeline, so it is left out of the timing.
+        let mut start = Instant::now();
+        for frame in 0..=FRAMES {
+            if frame == 1 {
+                start = Instant::now();
+            }
+            let mut encoder = device.create_command_encoder(&Default::default());
+            {
+                let mut pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+                    label: Some("Bench Pass"),
+                    color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+                        view: &view,
+                        resolve_target: None,
+                        ops: wgpu::Operations {
+                            load: wgpu::LoadOp::Clear(wgpu::Color::BLACK),
+                            store: wgpu::StoreOp::Store,
+                        },
+                    })],
+                    depth_stencil_attachment: None,
+                    timestamp_writes: None,
+                    occlusion_query_set: None,
+                });
+                pass.set_pipeline(pipeline);
+                for (group, bind) in binds.iter().enumerate() {
+                    pass.set_bind_group(group as u32, bind, &[]);
+                }
+                pass.set_vertex_buffer(0, mesh.slice(..));
+                pass.set_vertex_buffer(1, instances.0.slice(..));
+                pass.draw(0..vertices, 0..instances.1);
+            }
+            queue.submit([encoder.finish()]);
+            device.poll(wgpu::Maintain::Wait);
+        }
+        start.elapsed() / FRAMES
+    };
+    let sdf_draw = time_frames(&sdf_pipeline, &sdf_binds, &quad, 6, (&quads, quad_count));
+    let mesh_draw = time_frames(&mesh_pipeline, &mesh_binds, &circle, circle_fan.len() as u32, (&circles, circle_count));
+
+    println!("sdf: load {sdf_load:?}, draw {sdf_draw:?}");
+    println!("mesh: load {mesh_load:?}, draw {mesh_draw:?}");
+}
+
+/// Benchmark comparing three `write_array` queue writes against one staging belt encoder
+/// for the per-frame simulation uploads of 5000 cells.
+/// Run with `cargo test --release bench_batched_uploads -- --ignored --nocapture`.
+#[test]
+#[ignore]
+fn bench_batched_uploads() {
+    use rand::prelude::*;
+    use std::time::Instant;
+    use wgpu::util::StagingBelt;
+
+    const FRAMES: u32 = 200;
+
+    let (device, queue) = headless_device().expect("No GPU adapter available");
+
+    let mut rng = rand::rngs::StdRng::seed_from_u64(5);
+    let bounds = AABB::UNIT * 100.0;
+    let mut state = SimulationState::new(test_context());
+    state.spawn_vec((0..5000)
+        .map(|_| Cell::new(benches::random_pos_in_bounds(&mut rng, bounds).into(), CellType::Neural))
+        .collect()).unwrap();
+    let mut loader = EnvironmentRenderLoader::new();
+    loader.run(&state, None);
+
+    let vertex = wgpu::BufferUsages::VERTEX | wgpu::BufferUsages::COPY_DST;
+    let storage = wgpu::BufferUsages::STORAGE | wgpu::BufferUsages::COPY_DST;
+    let instances = GpuBuffer::new(&device, vertex, "Upload Bench Instances", loader.gpu_render_instances.len());
+    let indices = GpuBuffer::new(&device, storage, "Upload Bench Indices", loader.gpu_primitive_indices.len());
+    let primitives = GpuBuffer::new(&device, storage, "Upload Bench Primitives", loader.gpu_primitives.len());
+
+    let start = Instant::now();
+    for _ in 0..FRAMES {
+        primitives.write_array(&queue, &loader.gpu_primitives);
+        indices.write_array(&queue, &loader.gpu_primitive_indices);
+        instances.write_array(&queue, &loader.gpu_render_instances);
+        queue.submit([]);
+        device.poll(wgpu::Maintain::Wait);
+    }
+    let queue_writes = start.elapsed() / FRAMES;
+
+    let mut belt = StagingBelt::new(1 << 20);
+    let start = Instant::now();
+    for _ in 0..FRAMES {
+        let mut encoder = device.create_command_encoder(&wgpu::CommandEncoderDescriptor { label: None });
+        primitives.stage_array(&device, &mut belt, &mut encoder, &loader.gpu_primitives);
+        indices.stage_array(&device, &mut belt, &mut encoder, &loader.gpu_primitive_indices);
+        instances.stage_array(&device, &mut belt, &mut encoder, &loader.gpu_render_instances);
+        belt.finish();
+        queue.submit(std::iter::once(encoder.finish()));
+        belt.recall();
+        device.poll(wgpu::Maintain::Wait);
+    }
+    let staging_belt = start.elapsed() / FRAMES;
+
+    println!("5000 cells: write_array {queue_writes:?}/frame, staging belt {staging_belt:?}/frame");
+}
+
+/// Tests the rigid body inertia formulas against known closed-form cases.
+#[test]
+fn test_object_inertia() {
+    let close = |a: f64, b: f64| (a - b).abs() < 1e-9;
+
+    // A disk of radius r has inertia m * r^2 / 2.
+    let disk = Disk::new(2.0, 0.5);
+    assert!(close(disk.mass(), 2.0 * PI));
+    assert!(close(disk.rotational_inertia(), disk.mass() * 2.0));
+
+    // A ring with radii r1 and r2 has inertia m * (r1^2 + r2^2) / 2; without a hole it is a disk.
+    let ring = Ring::new(1.0, 2.0, 0.5);
+    assert!(close(ring.mass(), 1.5 * PI));
+    assert!(close(ring.rotational_inertia(), ring.mass() * (1.0 + 4.0) / 2.0));
+    let solid = Ring::new(0.0, 2.0, 0.5);
+    assert!(close(solid.mass(), disk.mass()));
+    assert!(close(solid.rotational_inertia(), disk.rotational_inertia()));
+
+    // A w by h rectangle has inertia m * (w^2 + h^2) / 12.
+    let rect = Rectangle::new(2.0, 3.0, 0.5);
+    assert!(close(rect.mass(), 3.0));
+    assert!(close(rect.rotational_inertia(), rect.mass() * (4.0 + 9.0) / 12.0));
+
+    // A square polygon with circumradius r has side s = r * sqrt(2) and inertia m * s^2 / 6,
+    // the same as a rectangle of that side.
+    let side = 2.0_f64.sqrt();
+    let square = RegularPolygon::new(4, 1.0, 3.0);
+    assert!(close(square.mass(), side * side * 3.0));
+    assert!(close(square.rotational_inertia(), square.mass() * side * side / 6.0));
+    let rect = Rectangle::new(side, side, 3.0);
+    assert!(close(square.rotational_inertia(), rect.rotational_inertia()));
+
+    // An equilateral triangle with side a has inertia m * a^2 / 12.
+    let triangle = RegularPolygon::new(3, 1.0, 1.0);
+    let a = 3.0_f64.sqrt();
+    assert!(close(triangle.rotational_inertia(), triangle.mass() * a * a / 12.0));
+
+    // At equal mass, a square resists rotation differently than a disk.
+    let disk = Disk::from_mass(square.mass(), 1.0);
+    assert!(close(disk.mass(), square.mass()));
+    assert!(!close(square.rotational_inertia(), disk.rotational_inertia()));
+}
+
+/// Tests that connected cells starting at the same position stay finite after ticking.
+#[test]
+fn test_coincident_cells_stay_finite() {
+    let mut state = SimulationState::new(test_context());
+    state.spawn_vec(vec![
+        Cell::new(Vec2d::ZERO, CellType::Neural),
+        Cell::new(Vec2d::ZERO, CellType::Muscle),
+    ]).unwrap();
+    state.connect(0, 0.0, 1, 0.0).unwrap();
+
+    for _ in 0..10 {
+        state.tick(1.0 / 60.0);
+    }
+
+    for cell in state.cells.flatten_iter() {
+        assert!(cell.position.x.is_finite() && cell.position.y.is_finite());
+        assert!(cell.angle.is_finite());
+    }
+}
+
+/// Tests that the CSV export writes one row per live cell, matching the header's columns.
+#[test]
+fn test_export_snapshot_csv() {
+    let mut state = SimulationState::new(test_context());
+    state.spawn_vec(vec![
+        Cell::new(Vec2d::ZERO, CellType::Neural),
+        Cell::new(Vec2d::new(2.0, 0.0), CellType::Fat),
+        Cell::new(Vec2d::new(4.0, 0.0), CellType::Muscle),
+    ]).unwrap();
+    state.remove(1);
+
+    let mut out = Vec::new();
+    state.export_snapshot_csv(&mut out).unwrap();
+    let text = String::from_utf8(out).unwrap();
+
+    let columns = SimulationState::SNAPSHOT_CSV_HEADER.split(',').count();
+    let rows: Vec<&str> = text.lines().collect();
+    assert_eq!(rows.len(), 2);
+    assert!(rows.iter().all(|row| row.split(',').count() == columns));
+    assert!(rows[1].starts_with("0,3,Muscle,4,0,"));
+}
+
+/// Tests that growing from a gene places every stem at the given radius, connected to its parent,
+/// and stops at the depth limit.
+#[test]
+fn test_grow_from_gene() {
+    let branch = Gene { stems: vec![Gene::leaf_node(CellType::HairFollicle), Gene::leaf_node(CellType::HairFollicle)], typ: CellType::Muscle };
+    let gene = Gene { stems: vec![branch.clone(), branch.clone(), branch], typ: CellType::Neural };
+
+    let mut state = SimulationState::new(test_context());
+    let root = state.grow_from_gene(Vec2d::ZERO, &gene, 8, 2.0).unwrap();
+    assert_eq!(state.cells.get(root).typ, CellType::Neural);
+    assert_eq!(state.cells.len(), 10);
+    assert_eq!(state.connections.len(), 9);
+
+    for connection in state.connections.iter() {
+        let (a, b) = (state.cells.get(connection.id_a), state.cells.get(connection.id_b));
+        assert!((a.position.distance(b.position) - 2.0).abs() < 1e-9);
+    }
+
+    let mut shallow = SimulationState::new(test_context());
+    shallow.grow_from_gene(Vec2d::ZERO, &gene, 1, 2.0).unwrap();
+    assert_eq!(shallow.cells.len(), 4);
+}
+
+/// Tests that cells age with simulated time and are drawn grayer as they approach `max_age`.
+#[test]
+fn test_cell_age_color() {
+    let mut state = SimulationState::new(test_context());
+    state.spawn_vec(vec![
+        Cell::new(Vec2d::ZERO, CellType::Muscle),
+        Cell::new(Vec2d::new(5.0, 0.0), CellType::Muscle),
+    ]).unwrap();
+    for _ in 0..10 {
+        state.tick(0.1);
+    }
+    assert!((state.cells.get(0).age - 1.0).abs() < 1e-9);
+
+    state.cells.get_mut(1).age = EnvironmentRenderLoader::DEFAULT_MAX_AGE;
+    let mut loader = EnvironmentRenderLoader::new();
+    loader.run_circles(&state, None);
+    let [young, old] = [0, 1].map(|i| loader.gpu_circle_instances[i].color);
+
+    // A freshly spawned cell keeps nearly its type color, an old one is fully gray.
+    let type_color = CellType::Muscle.get_membrane_primitive(&ThemeConfig::default()).color.to_f32_array();
+    assert!(young.iter().zip(type_color).all(|(a, b)| (a - b).abs() < 0.01));
+    assert!(old[0] == old[1] && old[1] == old[2]);
+    assert_eq!(old[3], young[3]);
+}
+
+/// Tests that cells are drawn more transparent as their energy runs out, and vanish at none.
+#[test]
+fn test_cell_health_alpha() {
+    let mut state = SimulationState::new(test_context());
+    for (i, fraction) in [1.0, 0.5, 0.1, 0.0].into_iter().enumerate() {
+        let mut cell = Cell::new(Vec2d::new(5.0 * i as f64, 0.0), CellType::Neural);
+        cell.resources = LocalResources::new(LocalResources::MAX_ENERGY * fraction, 0.0);
+        state.spawn(cell).unwrap();
+    }
+    state.tick(0.01);
+    assert!((state.cells.get(1).health - 0.5).abs() < 1e-6);
+
+    let mut loader = EnvironmentRenderLoader::new();
+    loader.run_circles(&state, None);
+    let alphas: Vec<f32> = loader.gpu_circle_instances.iter().map(|instance| instance.color[3]).collect();
+    assert_eq!(alphas[0], 1.0);
+    assert!(alphas.windows(2).all(|pair| pair[0] > pair[1]));
+    assert_eq!(alphas[3], 0.0);
+}
+
+/// Tests that a loader origin keeps cell transforms precise far from the world origin.
+#[test]
+fn test_render_loader_origin() {
+    let far = Vec2d::new(1.0e6, -1.0e6);
+    let mut state = SimulationState::new(test_context());
+    state.spawn(Cell::new(far + Vec2d::new(0.3, 0.7), CellType::Fat)).unwrap();
+
+    // At a million, neighbouring f32 values are 1/16 apart.
+    let absolute = state.cells.get(0).get_transform().translate;
+    assert!((absolute.x as f64 - (far.x + 0.3)).abs() > 1e-3);
+
+    let mut loader = EnvironmentRenderLoader::new();
+    loader.origin = far;
+    loader.run_circles(&state, Some(AABB::new(Vec2::ZERO, Vec2::ONE)));
+    let [x, y] = loader.gpu_circle_instances[0].translation;
+    assert!((x - 0.3).abs() < 1e-6 && (y - 0.7).abs() < 1e-6);
+}
+
+/// Tests that the view shared with overlays projects origin-relative positions and maps pixels back to the world.
+#[test]
+fn test_tile_view() {
+    let view = TileView {
+        view: SrtTransform { translate: Vec2::new(1.0e5, 2.0), rotate: 0.0, scale: Vec2::new(4.0, 2.0) },
+        origin: Vec2d::new(1.0e5, 0.0),
+        alpha: 0.5,
+        size: Vec2::new(800.0, 400.0),
+    };
+
+    // The view's center lies 2 units above the origin, and lands mid-tile.
+    let center = view.relative_projection().project_point3(Vec2::new(0.0, 2.0).extend(0.0));
+    assert!(center.truncate().length() < 1e-6);
+    assert_eq!(view.screen_to_world(Vec2::new(400.0, 200.0)), Vec2::new(1.0e5, 2.0));
+
+    // The top-right corner is one half-extent away on each axis, with y pointing down on screen.
+    let corner = view.relative_projection().project_point3(Vec2::new(4.0, 4.0).extend(0.0));
+    assert!((corner.truncate() - Vec2::ONE).length() < 1e-6);
+    assert_eq!(view.screen_to_world(Vec2::new(800.0, 0.0)), Vec2::new(1.0e5 + 4.0, 4.0));
+}
+
+/// Tests that every cell type has a distinct display name that maps back to it and is also its display form.
+#[test]
+fn test_cell_type_names() {
+    let mut names: Vec<&str> = CellType::LIST.iter().map(CellType::name).collect();
+    names.sort();
+    names.dedup();
+    assert_eq!(names.len(), CellType::LIST.len());
+    for &typ in CellType::LIST {
+        assert_eq!(CellType::from_name(typ.name()), Some(typ));
+        assert_eq!(typ.to_string(), typ.name());
+    }
+    assert_eq!(CellType::HairFollicle.name(), "Hair Follicle");
+    assert_eq!(CellType::from_name("hair follicle"), Some(CellType::HairFollicle));
+    assert_eq!(CellType::from_name("HairFollicle"), None);
+}
+
+/// Tests that interpolated transforms move from the previous tick to the current state,
+/// turning the short way across the angle wrap.
+#[test]
+fn test_transform_interpolation() {
+    let mut cell = Cell::new(Vec2d::ZERO, CellType::Neural);
+    cell.previous_position = Vec2d::new(-1.0, 0.0);
+    cell.position = Vec2d::new(1.0, 2.0);
+    cell.previous_angle = 3.0;
+    cell.angle = -3.0;
+
+    let at = |alpha: f64| cell.get_transform_interpolated(Vec2d::ZERO, alpha);
+    assert_eq!(at(0.0).translate, Vec2::new(-1.0, 0.0));
+    assert_eq!(at(0.5).translate, Vec2::new(0.0, 1.0));
+    assert_eq!((at(1.0).translate, at(1.0).rotate), (cell.get_transform().translate, cell.get_transform().rotate));
+
+    // From 3 to -3 radians is about 0.28 radians forwards through PI, not 6 backwards.
+    let halfway = at(0.5).rotate as f64;
+    assert!((halfway - (3.0 + (TAU - 6.0) / 2.0)).abs() < 1e-6, "{halfway}");
+
+    // A tick records where the cell started.
+    let mut state = SimulationState::new(test_context());
+    state.spawn(Cell::new(Vec2d::ZERO, CellType::Neural)).unwrap();
+    state.cells.get_mut(0).velocity = Vec2d::new(1.0, 0.0);
+    state.tick(1.0 / 60.0);
+    let cell = state.cells.get(0);
+    assert_eq!(cell.previous_position, Vec2d::ZERO);
+    assert!(cell.position.x > 0.0);
+}
+
+/// Tests that disconnecting a cell severs only its own connections and keeps it alive.
+#[test]
+fn test_disconnect_all() {
+    let mut state = SimulationState::new(test_context());
+    state.spawn_vec((0..4).map(|i| Cell::new(Vec2d::new(i as f64 * 2.0, 0.0), CellType::Neural)).collect()).unwrap();
+    state.connect_auto(0, 1).unwrap();
+    state.connect_auto(1, 2).unwrap();
+    state.connect_auto(2, 3).unwrap();
+    state.connect_auto(3, 1).unwrap();
+
+    assert_eq!(state.disconnect_all(1), 3);
+    assert!(state.cells.is_initialized(1));
+    assert_eq!(state.cells.len(), 4);
+    assert_eq!(state.connections.len(), 1);
+    assert!(state.connections[0].links(2, 3));
+
+    assert_eq!(state.disconnect_all(1), 0);
+}
+
+/// Tests that merging connects separate organisms, but not cells already in one.
+#[test]
+fn test_merge() {
+    let mut state = SimulationState::new(test_context());
+    state.spawn_vec((0..5).map(|i| Cell::new(Vec2d::new(i as f64 * 2.0, 0.0), CellType::Neural)).collect()).unwrap();
+    state.connect_auto(0, 1).unwrap();
+    state.connect_auto(1, 2).unwrap();
+    state.connect_auto(3, 4).unwrap();
+
+    assert_eq!(state.component_of(0), [0, 1, 2]);
+    assert_eq!(state.merge(0, 2), Err(ConnectError::SameComponent));
+    assert_eq!(state.merge(0, 0), Err(ConnectError::SelfConnection(0)));
+    assert_eq!(state.merge(0, 7), Err(ConnectError::MissingCell(7)));
+
+    state.merge(2, 3).unwrap();
+    assert!(state.connections.last().unwrap().links(2, 3));
+    assert_eq!(state.component_of(4), [0, 1, 2, 3, 4]);
+    assert_eq!(state.merge(4, 0), Err(ConnectError::SameComponent));
+
+    // Connections left to a cell freed directly on the heap no longer join its neighbors.
+    state.cells.free(1);
+    assert_eq!(state.component_of(0), [0]);
+    assert_eq!(state.component_of(2), [2, 3, 4]);
+    state.merge(0, 2).unwrap();
+}
+
+/// Tests that the render loader emits one instance per connected group, using flattened indices.
+#[test]
+fn test_render_loader_groups() {
+    let mut state = SimulationState::new(test_context());
+    state.spawn_vec((0..6).map(|i| Cell::new(Vec2d::new(i as f64 * 2.0, 0.0), CellType::Neural)).collect()).unwrap();
+    state.connect_auto(1, 2).unwrap();
+    state.connect_auto(2, 3).unwrap();
+    state.connect_auto(4, 5).unwrap();
+    state.remove(0);
+
+    let mut loader = EnvironmentRenderLoader::new();
+    loader.run(&state, None);
+
+    // Slot 0 is freed, so cells 1..=5 flatten to 0..=4.
+    let groups: Vec<Vec<u32>> = loader.gpu_render_instances.iter().map(|instance| {
+        let mut group: Vec<u32> = loader.gpu_primitive_indices[instance.start_i as usize..instance.end_i as usize]
+            .iter()
+            .map(|index| index.index)
+            .collect();
+        group.sort();
+        group
+    }).collect();
+    assert_eq!(groups, vec![vec![0, 1, 2], vec![3, 4]]);
+    assert_eq!(loader.gpu_primitives.len(), 5);
+
+    // Coloring by group gives each organism one color, distinct from the other's.
+    loader.color_mode = ColorMode::ByGroup;
+    loader.run(&state, None);
+    // The color follows the 4x4 projection matrix in the primitive layout.
+    let colors: Vec<[f32; 4]> = loader
+        .gpu_primitives
+        .iter()
+        .map(|&primitive| bytemuck::cast::<_, [[f32; 4]; 6]>(primitive)[4])
+        .collect();
+    assert!(colors[0] == colors[1] && colors[1] == colors[2]);
+    assert_eq!(colors[3], colors[4]);
+    assert_ne!(colors[0], colors[3]);
+
+    // A view around the first organism culls the second.
+    loader.run(&state, Some(AABB::new(Vec2::new(3.0, 0.0), Vec2::new(2.0, 2.0))));
+    assert_eq!(loader.gpu_render_instances.len(), 1);
+    assert_eq!(loader.culled_count, 1);
+
+    // The mesh backend gets one circle per cell, with the same culling and group colors.
+    loader.run_circles(&state, None);
+    assert_eq!(loader.gpu_circle_instances.len(), 5);
+    let circle_colors: Vec<[f32; 4]> = loader.gpu_circle_instances.iter().map(|circle| circle.color).collect();
+    assert_eq!(circle_colors, colors);
+    assert_eq!(loader.gpu_circle_instances[0].translation, [2.0, 0.0]);
+    loader.run_circles(&state, Some(AABB::new(Vec2::new(3.0, 0.0), Vec2::new(2.0, 2.0))));
+    assert_eq!(loader.gpu_circle_instances.len(), 3);
+    assert_eq!(loader.culled_count, 2);
+
+    // Once every cell is removed, the previous frame's data is cleared rather than grouping underflowing.
+    for id in 1..6 {
+        state.remove(id);
+    }
+    loader.run(&state, None);
+    assert!(loader.gpu_render_instances.is_empty());
+    assert!(loader.gpu_primitive_indices.is_empty());
+    assert!(loader.gpu_primitives.is_empty());
+}
+
+/// Tests spatial hash radius queries, including points exactly on cell edges and negative coordinates.
+#[test]
+fn test_spatial_hash() {
+    let mut grid = SpatialHash::new(1.0);
+    grid.insert(0usize, Vec2d::new(0.0, 0.0));
+    grid.insert(1, Vec2d::new(1.0, 0.0));
+    grid.insert(2, Vec2d::new(-1.0, 0.0));
+    grid.insert(3, Vec2d::new(2.0, 2.0));
+    grid.insert(4, Vec2d::new(0.5, -1.0));
+
+    let query = |grid: &SpatialHash<usize>, center: Vec2d, r: f64| {
+        let mut ids: Vec<usize> = grid.query_radius(center, r).collect();
+        ids.sort();
+        ids
+    };
+
+    // Points exactly at the query radius lie on cell edges and are included.
+    assert_eq!(query(&grid, Vec2d::ZERO, 1.0), vec![0, 1, 2]);
+    // A query centered on an edge sees both neighboring cells.
+    assert_eq!(query(&grid, Vec2d::new(1.0, 0.0), 0.0), vec![1]);
+    assert_eq!(query(&grid, Vec2d::new(0.5, 0.0), 0.5), vec![0, 1]);
+    assert_eq!(query(&grid, Vec2d::new(0.5, -0.5), 0.5), vec![4]);
+    assert_eq!(query(&grid, Vec2d::new(2.0, 2.0), 0.1), vec![3]);
+    assert!(query(&grid, Vec2d::new(10.0, 10.0), 1.0).is_empty());
+
+    grid.clear();
+    assert!(query(&grid, Vec2d::ZERO, 100.0).is_empty());
+}
+
+/// Tests that an organism with undamped springs keeps its mechanical energy while it oscillates.
+///
+/// Viscosity, collisions and spring damping are all disabled, so the integrator is the only source of drift,
+/// kept small by sub-stepping.
+#[test]
+fn test_undamped_energy_is_conserved() {
+    let context = SimContext::builder().viscosity(0.0, 0.0).collision_stiffness(0.0).spring_iterations(16).build();
+    let mut state = SimulationState::new(context);
+
+    // A neural cell with four passive neighbors, stretched past their rest lengths so the springs start loaded.
+    let corners = [(-1.0, -1.0), (1.0, -1.0), (-1.0, 1.0), (1.0, 1.0)];
+    state.spawn(Cell::new(Vec2d::ZERO, CellType::Neural)).unwrap();
+    for (x, y) in corners {
+        state.spawn(Cell::new(Vec2d::new(x, y) * 1.6, CellType::Kidney)).unwrap();
+    }
+    for (i, (x, y)) in corners.into_iter().enumerate() {
+        let b = i + 1;
+        let angle_a = f64::atan2(y, x);
+        state.connect_with(CellConnection::new(0, angle_a, b, angle_a + PI).with_damping_ratio(0.0)).unwrap();
+    }
+
+    let total = |state: &SimulationState| state.total_kinetic_energy() + state.total_potential_energy();
+    let initial = total(&state);
+    assert!(initial > 0.0);
+
+    for _ in 0..600 {
+        state.tick(1.0 / 60.0);
+        let energy = total(&state);
+        assert!((energy - initial).abs() <= initial * 0.02, "{energy} drifted from {initial}");
+    }
+}
+
+/// Tests that a muscle cell's behavior contracts its connection over half a cycle.
+#[test]
+fn test_muscle_contracts() {
+    let mut state = SimulationState::new(test_context());
+    state.spawn_vec(vec![
+        Cell::new(Vec2d::ZERO, CellType::Neural),
+        Cell::new(Vec2d::new(1.0, 0.0), CellType::Muscle),
+    ]).unwrap();
+    state.connect_auto(0, 1).unwrap();
+    let relaxed = state.cells.get(0).position.distance(state.cells.get(1).position);
+
+    // The default muscle cycle peaks after half of its two second period.
+    for _ in 0..60 {
+        state.tick(1.0 / 60.0);
+    }
+
+    assert!(state.cells.get(1).contraction > 0.3);
+    let contracted = state.cells.get(0).position.distance(state.cells.get(1).position);
+    assert!(contracted < relaxed - 0.05, "{contracted} vs {relaxed}");
+}
+
+/// Tests that a signal injected into a chain of neural cells reaches near cells before far ones.
+#[test]
+fn test_signal_ripples_outward() {
+    let mut state = SimulationState::new(test_context());
+    state.spawn_vec((0..4).map(|i| Cell::new(Vec2d::new(i as f64, 0.0), CellType::Neural)).collect()).unwrap();
+    for i in 0..3 {
+        state.connect_auto(i, i + 1).unwrap();
+    }
+
+    state.inject_signal(0, 1.0);
+    // Cells that aren't alive are skipped rather than panicking.
+    state.inject_signal(4, 1.0);
+    state.signal_pass(1.0 / 60.0);
+    let signals: Vec<f32> = state.cells.flatten_iter().map(|cell| cell.signal).collect();
+    assert!(signals[1] > 0.0 && signals[2] == 0.0);
+
+    // Given time, the signal crosses the chain, weakening with distance.
+    for _ in 0..10 {
+        state.signal_pass(1.0 / 60.0);
+    }
+    let signals: Vec<f32> = state.cells.flatten_iter().map(|cell| cell.signal).collect();
+    assert!(signals[3] > 0.0);
+    assert!(signals[0] > signals[1] && signals[1] > signals[2] && signals[2] > signals[3]);
+}
+
+/// Tests that freeing a cell directly on the heap, bypassing `remove`, does not crash the next tick
+/// and that its stale connection is pruned.
+#[test]
+fn test_stale_connection_skipped() {
+    let mut state = benches::organism_lookn_cells(test_context());
+    state.cells.free(1);
+    assert!(state.connection_pair(0).is_none());
+
+    state.tick(1.0 / 60.0);
+    assert_eq!(state.connections.len(), 3);
+    assert!(state.connections.iter().all(|c| !c.points_toward(1)));
+}
+
+/// Tests that physics sub-steps keep a stretched organism settling at a coarse time step.
+#[test]
+fn test_spring_sub_steps_settle() {
+    let settle = |spring_iterations: usize| {
+        let context = SimContext::builder().viscosity(0.0, 0.0).spring_iterations(spring_iterations).build();
+        let mut state = benches::organism_lookn_cells(context);
+        for cell in state.cells.flatten_iter_mut() {
+            cell.position = cell.position * 1.2;
+        }
+        let initial = state.total_kinetic_energy() + state.total_potential_energy();
+        for _ in 0..200 {
+            state.tick(1.0 / 10.0);
+        }
+        (state.total_kinetic_energy() + state.total_potential_energy()) / initial
+    };
+
+    // A single step per tick gains energy at this dt; sub-stepping lets damping win.
+    let (coarse, fine) = (settle(1), settle(8));
+    assert!(fine < 0.01 && fine < coarse, "{fine} vs {coarse}");
+}
+
+/// Tests that a grid split covers its parent exactly, with equal cells that don't overlap.
+#[test]
+fn test_tile_grid_layout() {
+    let mut manager = TileViewManager::new();
+    let parent = manager.add_leaf(manager.root(), Style {
+        size: Size { width: Dimension::percent(0.5), height: Dimension::percent(1.0) },
+        ..Default::default()
+    });
+    let cells = manager.grid(parent, 2, 2);
+    manager.resize(Vec2::new(800.0, 600.0));
+
+    let parent_aabb = manager.get_aabb(parent);
+    assert_eq!(parent_aabb.wh(), Vec2::new(400.0, 600.0));
+
+    let aabbs: Vec<AABB> = cells.iter().map(|&cell| manager.get_aabb(cell)).collect();
+    let total_area: f32 = aabbs.iter().map(|aabb| aabb.width() * aabb.height()).sum();
+    assert_eq!(total_area, parent_aabb.width() * parent_aabb.height());
+
+    for (i, a) in aabbs.iter().enumerate() {
+        assert_eq!(a.wh(), Vec2::new(200.0, 300.0));
+        assert_eq!(*a & parent_aabb, Some(*a));
+        for b in &aabbs[i + 1..] {
+            assert_eq!(*a & *b, None);
+        }
+    }
+}
+
+/// Tests that horizontal and vertical splits size their children by ratio, side by side without gaps.
+#[test]
+fn test_tile_split_layout() {
+    let mut manager = TileViewManager::new();
+    let parent = manager.add_leaf(manager.root(), Style {
+        size: Size { width: Dimension::percent(1.0), height: Dimension::percent(1.0) },
+        ..Default::default()
+    });
+    let columns = manager.split_horizontal(parent, &[1.0, 3.0]);
+    let rows = manager.split_vertical(columns[0], &[1.0, 1.0, 2.0]);
+    manager.resize(Vec2::new(800.0, 600.0));
+
+    let column_aabbs: Vec<AABB> = columns.iter().map(|&node| manager.get_aabb(node)).collect();
+    assert_eq!(column_aabbs[0].wh(), Vec2::new(200.0, 600.0));
+    assert_eq!(column_aabbs[1].wh(), Vec2::new(600.0, 600.0));
+    assert_eq!(column_aabbs[0].max().x, column_aabbs[1].min().x);
+
+    let row_aabbs: Vec<AABB> = rows.iter().map(|&node| manager.get_aabb(node)).collect();
+    let heights: Vec<f32> = row_aabbs.iter().map(AABB::height).collect();
+    assert_eq!(heights, [150.0, 150.0, 300.0]);
+    for pair in row_aabbs.windows(2) {
+        assert_eq!(pair[0].max().y, pair[1].min().y);
+        assert_eq!(pair[0] & pair[1], None);
+    }
+}
+
+/// Tests that unconnected cells clump together under self-gravity.
+#[test]
+fn test_gravity_clumps_cells() {
+    let context = SimContext::builder()
+        .viscosity(0.0, 0.0)
+        .gravity(GravityField { g: 50.0, softening: 0.5 })
+        .build();
+    let mut state = SimulationState::new(context);
+    state.spawn_vec(vec![
+        Cell::new(Vec2d::new(-3.0, -3.0), CellType::Fat),
+        Cell::new(Vec2d::new(3.0, -3.0), CellType::Fat),
+        Cell::new(Vec2d::new(-3.0, 3.0), CellType::Fat),
+        Cell::new(Vec2d::new(3.0, 3.0), CellType::Fat),
+    ]).unwrap();
+
+    let spread = |state: &SimulationState| {
+        state.cells.flatten_iter().map(|cell| cell.position.length()).sum::<f64>()
+    };
+    let initial = spread(&state);
+
+    for _ in 0..120 {
+        state.tick(1.0 / 60.0);
+    }
+
+    let cells: Vec<_> = state.cells.flatten_iter().collect();
+    assert!(cells.iter().all(|cell| cell.position.x.is_finite() && cell.position.y.is_finite()));
+    assert!(spread(&state) < initial * 0.9, "{} vs {initial}", spread(&state));
+}
+
+/// Tests that quads built from rotated boxes follow the rotation and scale about their center.
+#[test]
+fn test_rotated_quad_verts() {
+    let obb = OBB { center: Vec2::new(1.0, 2.0), half: Vec2::new(2.0, 1.0), angle: std::f32::consts::FRAC_PI_2 };
+    let quad = QuadVerts::from_obb(obb);
+
+    // A quarter turn swaps the box's extents.
+    assert!((quad.max() - quad.min() - Vec2::new(2.0, 4.0)).length() < 1e-5);
+    assert!((quad.center() - obb.center).length() < 1e-5);
+
+    // The fitted AABB, projected from the axes, matches the corners' extents at any angle.
+    for angle in [0.0, 0.3, std::f32::consts::FRAC_PI_2, 2.5] {
+        let obb = OBB { angle, ..obb };
+        let (aabb, corners) = (obb.fit_aabb(), obb.corners());
+        assert!((aabb.min() - corners.min()).length() < 1e-5 && (aabb.max() - corners.max()).length() < 1e-5);
+    }
+
+    let scaled = quad.scaled(2.0);
+    assert!((scaled.center() - obb.center).length() < 1e-5);
+    assert!((scaled.tr - scaled.center() - (quad.tr - quad.center()) * 2.0).length() < 1e-5);
+
+    // The border ring's outer corners match the box, and its inner corners stay inside it.
+    let ring = quad.frame_mesh(&QuadVerts::from_obb(obb.add_padding(-0.5)));
+    let aabb = AABB::from_edges(quad.min(), quad.max());
+    for vertex in ring {
+        let pos = Vec2::from(bytemuck::cast::<_, [f32; 2]>(vertex));
+        assert!((pos - aabb.center).abs().cmple(aabb.half + 1e-5).all());
+    }
+}
+
+#[test]
+fn test_aabb_intersection() {
+    let a = AABB::from_edges(Vec2::new(0.0, 0.0), Vec2::new(2.0, 2.0));
+    let b = AABB::from_edges(Vec2::new(1.0, 1.0), Vec2::new(3.0, 3.0));
+    assert_eq!(a & b, Some(AABB::from_edges(Vec2::new(1.0, 1.0), Vec2::new(2.0, 2.0))));
+
+    // Disjoint along either axis, or merely touching, is no intersection.
+    let far = AABB::from_edges(Vec2::new(5.0, 0.0), Vec2::new(6.0, 2.0));
+    let below = AABB::from_edges(Vec2::new(0.0, 5.0), Vec2::new(2.0, 6.0));
+    let touching = AABB::from_edges(Vec2::new(2.0, 0.0), Vec2::new(4.0, 2.0));
+    assert_eq!(a & far, None);
+    assert_eq!(a & below, None);
+    assert_eq!(a & touching, None);
+
+    // The root centers its children, so the outer two of three full-size tiles
+    // end up beside the window with no clipped bounds.
+    let mut manager = TileViewManager::new();
+    let tiles: Vec<_> = (0..3)
+        .map(|_| {
+            manager.add_leaf(manager.root(), Style {
+                size: Size { width: Dimension::percent(1.0), height: Dimension::percent(1.0) },
+                flex_shrink: 0.0,
+                ..Default::default()
+            })
+        })
+        .collect();
+    manager.resize(Vec2::new(800.0, 600.0));
+    assert_eq!(manager.get_aabb_clipped(tiles[0]), None);
+    assert_eq!(manager.get_aabb_clipped(tiles[1]), Some(manager.get_aabb(tiles[1])));
+    assert_eq!(manager.get_aabb_clipped(tiles[2]), None);
+}
+
+/// Tests that a limited joint bends back into range, while an unlimited one keeps turning.
+#[test]
+fn test_joint_angle_limits() {
+    let max_bend = |limited: bool| {
+        let mut state = SimulationState::new(test_context());
+        state.spawn_vec(vec![
+            Cell::new(Vec2d::ZERO, CellType::Neural),
+            Cell::new(Vec2d::new(1.0, 0.0), CellType::Neural),
+        ]).unwrap();
+        state.connect_auto(0, 1).unwrap();
+        if limited {
+            assert!(state.limit_joint(1, 0, -0.2, 0.2));
+        }
+
+        // Twist the joint with a steady torque on one side.
+        let mut max_bend: f64 = 0.0;
+        for _ in 0..120 {
+            state.cells.get_mut(1).apply_torque(5.0);
+            state.tick(1.0 / 60.0);
+            let connection = &state.connections[0];
+            let bend = connection.joint_angle(state.cells.get(0), state.cells.get(1));
+            max_bend = max_bend.max(bend.abs());
+        }
+        let connection = &state.connections[0];
+        (max_bend, connection.joint_limit_excess(state.cells.get(0), state.cells.get(1)))
+    };
+
+    let (free_bend, _) = max_bend(false);
+    let (limited_bend, final_excess) = max_bend(true);
+    assert!(free_bend > 0.3);
+    assert!(limited_bend < 0.25);
+    assert!(final_excess.abs() < 0.05);
+}
+
+/// Tests that checked heap access returns `None` for free, reserved, and out-of-bounds slots.
+#[test]
+fn test_heap_get_checked() {
+    let mut heap: Heap<u32> = Heap::with_capacity(2);
+    let start = heap.insert_alloc_vec(vec![1, 2]);
+    let reserved = heap.allocate_slots(1);
+    heap.free(start);
+
+    assert_eq!(heap.get_checked(start), None);
+    assert_eq!(heap.get_checked(start + 1), Some(&2));
+    assert_eq!(heap.get_checked(reserved), None);
+    assert_eq!(heap.get_checked(100), None);
+
+    *heap.get_mut_checked(start + 1).unwrap() = 3;
+    assert_eq!(heap.get(start + 1), &3);
+    assert!(heap.get_mut_checked(start).is_none());
+}
+
+/// Tests that `get_mut_pair` returns the requested slots in argument order, whichever index is larger.
+#[test]
+fn test_heap_get_mut_pair() {
+    let mut heap: Heap<u32> = Heap::with_capacity(0);
+    heap.insert_alloc_vec(vec![10, 11, 12, 13, 14]);
+
+    let (a, b) = heap.get_mut_pair(1, 3);
+    assert_eq!((*a, *b), (11, 13));
+    *a = 21;
+    *b = 23;
+
+    let (a, b) = heap.get_mut_pair(3, 1);
+    assert_eq!((*a, *b), (23, 21));
+    *a = 33;
+    *b = 31;
+
+    let values: Vec<u32> = heap.flatten_iter().copied().collect();
+    assert_eq!(values, [10, 31, 12, 33, 14]);
+}
+
+/// Tests that `get_mut_pair` refuses to alias a slot with itself.
+#[test]
+#[should_panic(expected = "Indices must be different")]
+fn test_heap_get_mut_pair_same_index() {
+    let mut heap: Heap<u32> = Heap::with_capacity(0);
+    heap.insert_alloc_vec(vec![10, 11]);
+    heap.get_mut_pair(1, 1);
+}
+
+/// Tests that `get_mut_pair` panics on a free slot passed first, below the other index.
+#[test]
+#[should_panic(expected = "Slot at index 1 not initialized")]
+fn test_heap_get_mut_pair_free_lower() {
+    let mut heap: Heap<u32> = Heap::with_capacity(0);
+    heap.insert_alloc_vec(vec![10, 11, 12, 13]);
+    heap.free(1);
+    heap.get_mut_pair(1, 3);
+}
+
+/// Tests that `get_mut_pair` panics on a free slot passed first, above the other index.
+#[test]
+#[should_panic(expected = "Slot at index 3 not initialized")]
+fn test_heap_get_mut_pair_free_upper() {
+    let mut heap: Heap<u32> = Heap::with_capacity(0);
+    heap.insert_alloc_vec(vec![10, 11, 12, 13]);
+    heap.free(3);
+    heap.get_mut_pair(3, 1);
+}
+
+/// Tests that spawning past `max_cells` fails without touching the heap, and frees up after removal.
+#[test]
+fn test_max_cells() {
+    let mut state = SimulationState::new(SimContext::builder().max_cells(3).build());
+    let cell = |x: f64| Cell::new(Vec2d::new(x, 0.0), CellType::Fat);
+
+    state.spawn_vec(vec![cell(0.0), cell(1.0)]).unwrap();
+    let capacity = state.cells.capacity();
+    assert_eq!(state.spawn_vec(vec![cell(2.0), cell(3.0)]), Err(SpawnError::PopulationFull));
+    assert_eq!(state.cells.len(), 2);
+    assert_eq!(state.cells.capacity(), capacity);
+
+    let last = state.spawn(cell(2.0)).unwrap();
+    assert_eq!(state.spawn(cell(3.0)), Err(SpawnError::PopulationFull));
+    assert_eq!(state.by_uid(state.cells.get(last).uid).unwrap().position, Vec2d::new(2.0, 0.0));
+
+    // Gene growth stops at the cap, keeping what it grew.
+    state.remove(0);
+    let gene = Gene { stems: vec![Gene::leaf_node(CellType::Muscle); 2], typ: CellType::Neural };
+    assert_eq!(state.grow_from_gene(Vec2d::ZERO, &gene, 4, 2.0), Err(SpawnError::PopulationFull));
+    assert_eq!(state.cells.len(), 3);
+}
+
+/// Tests camera projection, panning, and zooming about an anchor.
+#[test]
+fn test_camera() {
+    let tile = Vec2::new(800.0, 400.0);
+    let mut camera = Camera::new(AABB::new(Vec2::new(10.0, 0.0), Vec2::new(2.0, 2.0)));
+
+    // Contain widens the square view to the tile's 2:1 aspect ratio.
+    assert!(camera.world_to_screen(Vec2::new(10.0, 0.0), tile).abs_diff_eq(tile * 0.5, 1e-3));
+    assert!(camera.world_to_screen(Vec2::new(6.0, 2.0), tile).abs_diff_eq(Vec2::ZERO, 1e-3));
+
+    // Panning a full half-extent right moves by the fitted width.
+    camera.pan(Vec2::X, 2.0);
+    assert!(camera.view.center.abs_diff_eq(Vec2::new(14.0, 0.0), 1e-5));
+
+    let anchor = Vec2::new(15.0, 1.0);
+    let before = camera.world_to_screen(anchor, tile);
+    camera.zoom_at(anchor, 2.0);
+    assert!(camera.world_to_screen(anchor, tile).abs_diff_eq(before, 1e-3));
+    assert!(camera.view.half.abs_diff_eq(Vec2::new(1.0, 1.0), 1e-5));
+
+    camera.rotate = 0.5;
+    let screen = camera.world_to_screen(anchor, tile);
+    assert!(camera.screen_to_world(screen, tile).abs_diff_eq(anchor, 1e-3));
+
+    // Zooming is clamped to the limits, and invalid factors are ignored.
+    (camera.min_zoom, camera.max_zoom) = (0.5, 4.0);
+    camera.zoom_at(anchor, 100.0);
+    assert!((camera.zoom() - 4.0).abs() < 1e-5);
+    camera.zoom_at(anchor, 1e-6);
+    assert!((camera.zoom() - 0.5).abs() < 1e-5);
+    camera.zoom_at(anchor, 0.0);
+    camera.zoom_at(anchor, f32::NAN);
+    assert!((camera.zoom() - 0.5).abs() < 1e-5);
+
+    // A zero-height tile doesn't collapse the projection.
+    assert!(camera.projection_matrix(f32::INFINITY).is_finite());
+}
+
+/// Tests how each fit mode maps a square view onto a 2:1 tile, cycling through all three.
+#[test]
+fn test_fit_modes() {
+    let tile = Vec2::new(800.0, 400.0);
+    let mut camera = Camera::new(AABB::new(Vec2::ZERO, Vec2::new(2.0, 2.0)));
+    let edges = |camera: &Camera| {
+        let right = camera.world_to_screen(Vec2::new(2.0, 0.0), tile).x;
+        let top = camera.world_to_screen(Vec2::new(0.0, 1.0), tile).y;
+        (right, top)
+    };
+
+    assert_eq!(camera.fit_mode, FitMode::Contain);
+    let (right, top) = edges(&camera);
+    assert!((right - 600.0).abs() < 1e-3 && (top - 100.0).abs() < 1e-3);
+
+    camera.fit_mode = camera.fit_mode.next();
+    assert_eq!(camera.fit_mode, FitMode::Cover);
+    let (right, top) = edges(&camera);
+    assert!((right - 800.0).abs() < 1e-3 && top.abs() < 1e-3);
+
+    camera.fit_mode = camera.fit_mode.next();
+    assert_eq!(camera.fit_mode, FitMode::Stretch);
+    let (right, top) = edges(&camera);
+    assert!((right - 800.0).abs() < 1e-3 && (top - 100.0).abs() < 1e-3);
+
+    assert_eq!(camera.fit_mode.next(), FitMode::Contain);
+}
+
+/// Tests that accumulated angles wrap back near zero and that rotation lerps the short way.
+#[test]
+fn test_angle_normalization() {
+    let mut cell = Cell::new(Vec2d::ZERO, CellType::Neural);
+    cell.angle = 1000.0 * TAU + 0.25;
+    cell.normalize_angle();
+    assert!((cell.angle - 0.25).abs() < 1e-9, "{}", cell.angle);
+
+    let transform = SrtTransform { rotate: 1000.0 * std::f32::consts::TAU, ..Default::default() }.normalized();
+    assert!(transform.rotate.abs() < 1e-3, "{}", transform.rotate);
+
+    // Halfway from just below PI to just above -PI passes through PI, not zero.
+    let a = SrtTransform { rotate: 3.0, ..Default::default() };
+    let b = SrtTransform { rotate: -3.0, ..Default::default() };
+    assert!(a.lerp(b, 0.5).normalized().rotate.abs() > 3.0);
+}
+
+/// Tests that picking finds the cell covering a point, and nothing in empty space.
+#[test]
+fn test_pick_at() {
+    let mut state = SimulationState::new(test_context());
+    state.spawn_vec(vec![
+        Cell::new(Vec2d::ZERO, CellType::Neural),
+        Cell::new(Vec2d::new(0.8, 0.0), CellType::Fat),
+    ]).unwrap();
+
+    assert_eq!(state.pick_at(Vec2d::new(-0.2, 0.1)), Some(0));
+    // Both cells cover this point; the closer center wins.
+    assert_eq!(state.pick_at(Vec2d::new(0.45, 0.0)), Some(1));
+    assert_eq!(state.pick_at(Vec2d::new(0.0, 2.0)), None);
+}
+
+/// Tests polygon outlines for plain polygons, stars, and circles, through a transform.
+#[test]
+fn test_polygon_vertices() {
+    let transform = SrtTransform { translate: Vec2::new(1.0, 2.0), rotate: 0.0, scale: Vec2::splat(2.0) };
+
+    let square = ShapeDesc::Square.polygon_vertices(&transform);
+    assert_eq!(square.len(), 4);
+    assert!(square[0].abs_diff_eq(Vec2::new(3.0, 2.0), 1e-5));
+    assert!(square[1].abs_diff_eq(Vec2::new(1.0, 4.0), 1e-5));
+
+    let star = ShapeDesc::Pentagram.polygon_vertices(&SrtTransform::default());
+    assert_eq!(star.len(), 10);
+    assert!(star.iter().step_by(2).all(|p| (p.length() - 1.0).abs() < 1e-5));
+    assert!(star.iter().skip(1).step_by(2).all(|p| (p.length() - 0.5).abs() < 1e-5));
+
+    let circle = ShapeDesc::Circle.polygon_vertices_with_segments(&transform, 12);
+    assert_eq!(circle.len(), 12);
+    assert!(circle.iter().all(|p| (p.distance(transform.translate) - 2.0).abs() < 1e-5));
+}
+
+/// Golden test: flags any change to the organism's trajectory from springs, damping, or the integrator.
+///
+/// The organism is built without randomness, so the reference values are reproducible.
+/// Re-record them deliberately when a physics change is intended.
+#[test]
+fn test_organism_golden() {
+    let mut state = benches::organism_lookn_cells(test_context());
+    for _ in 0..600 {
+        state.tick(1.0 / 60.0);
+    }
+
+    let expected_positions = [
+        Vec2d::new(-0.0956422660341008, 0.24105609816796814),
+        Vec2d::new(-0.5382378288825759, -0.2978231237145575),
+        Vec2d::new(-0.3126282877163534, -0.749385670350562),
+        Vec2d::new(0.27852569113386066, -0.004058335545584893),
+        Vec2d::new(0.563072043999591, 0.769137778371381),
+    ];
+    let expected_kinetic_energy = 294.4066815914607;
+
+    assert_eq!(state.cells.len(), expected_positions.len());
+    for (cell, expected) in state.cells.flatten_iter().zip(expected_positions) {
+        assert!(cell.position.distance(expected) < 1e-6, "{:?} != {:?}", cell.position, expected);
+    }
+
+    let kinetic_energy = state.total_kinetic_energy();
+    assert!(
+        (kinetic_energy - expected_kinetic_energy).abs() < 1e-6 * expected_kinetic_energy,
+        "{kinetic_energy} != {expected_kinetic_energy}",
+    );
+}
+
+/// Tests that surplus fat grows a cell, pays for the growth, and stops at the size cap.
+#[test]
+fn test_growth_consumes_fat() {
+    let mut state = SimulationState::new(test_context());
+    let mut cell = Cell::new(Vec2d::ZERO, CellType::Fat);
+    cell.resources = LocalResources::new(0.0, 5.0);
+    let (base_size, base_mass) = (cell.size, cell.mass);
+    let id = state.spawn(cell).unwrap();
+
+    state.growth_pass(1.0);
+    let cell = state.cells.get(id);
+    let grown = cell.size - base_size;
+    assert!(grown > 0.0);
+    assert!(cell.mass > base_mass);
+    assert!((cell.resources.fat() - (5.0 - grown as f32 * FAT_PER_SIZE)).abs() < 1e-4);
+
+    // Plenty of fat grows the cell only up to the cap.
+    state.cells.get_mut(id).resources = LocalResources::new(0.0, 1000.0);
+    for _ in 0..100 {
+        state.growth_pass(1.0);
+    }
+    let cell = state.cells.get(id);
+    assert!((cell.size - base_size * MAX_GROWTH_FACTOR).abs() < 1e-9);
+    assert!(cell.resources.fat() > GROWTH_FAT_THRESHOLD);
+}
+
+/// Tests conversions between `Vec2d`, glam's `Vec2`, and arrays.
+#[test]
+fn test_vec2d_conversions() {
+    let v = Vec2d::new(1.5, -2.25);
+    assert_eq!(Vec2::from(v), Vec2::new(1.5, -2.25));
+    assert_eq!(Vec2d::from(v.to_glam()), v);
+    assert_eq!(v.to_array(), [1.5, -2.25]);
+    assert_eq!(Vec2d::from([1.5, -2.25]), v);
+}
+
+/// Tests that a rope connection lets cells drift closer but pulls them back when stretched.
+#[test]
+fn test_rope_connection() {
+    let mut state = SimulationState::new(test_context());
+    state.spawn_vec(vec![
+        Cell::new(Vec2d::ZERO, CellType::Neural),
+        Cell::new(Vec2d::new(0.5, 0.0), CellType::Neural),
+    ]).unwrap();
+    state.connect_auto(0, 1).unwrap();
+    assert!(state.set_connection_kind(1, 0, ConnectionKind::Rope));
+
+    let rest = state.connections[0].rest_length(state.cells.get(0), state.cells.get(1));
+    assert!(rest > 0.5);
+
+    // Slack: no force, so the overlapping cells stay put.
+    state.tick(1.0 / 60.0);
+    assert_eq!(state.cells.get(1).position, Vec2d::new(0.5, 0.0));
+    assert_eq!(state.total_potential_energy(), 0.0);
+
+    // Taut: pulled apart, the rope draws the cells back towards its length.
+    state.cells.get_mut(1).position = Vec2d::new(rest + 1.0, 0.0);
+    for _ in 0..120 {
+        state.tick(1.0 / 60.0);
+    }
+    let distance = state.cells.get(0).position.distance(state.cells.get(1).position);
+    assert!(distance < rest + 0.5, "{distance}");
+}
+
+/// Tests that the fixed-step clock carries remainders and drops time beyond the substep cap.
+#[test]
+fn test_fixed_step_clock() {
+    let step = Duration::from_millis(10);
+    let mut clock = FixedStepClock::new(step, 5);
+
+    assert_eq!(clock.advance(Duration::from_millis(25)), (2, Duration::ZERO));
+    assert_eq!(clock.until_next(), Duration::from_millis(5));
+    assert_eq!(clock.accumulated(), Duration::from_millis(5));
+    assert_eq!(clock.advance(Duration::from_millis(5)), (1, Duration::ZERO));
+
+    // A one-second stall runs only the capped ticks and drops the rest.
+    assert_eq!(clock.advance(Duration::from_millis(1003)), (5, Duration::from_millis(950)));
+    assert_eq!(clock.until_next(), Duration::from_millis(7));
+}
+
+/// Fragment harness filling each pixel inside `shape_sdf` with the shape color,
+/// over a viewport mapped to `[-SHAPE_TEST_EXTENT, SHAPE_TEST_EXTENT]` in unit-shape space.
+const SHAPE_TEST_SHADER: &str = r#"
+struct ShapeTest {
+    color: vec4<f32>,
+    shape: u32,
+    extent: f32,
+};
+
+@group(0) @binding(0)
+var<uniform> test: ShapeTest;
+
+struct FragmentInput {
+    @builtin(position) position: vec4<f32>,
+    @location(0) unit_pos: vec2<f32>,
+};
+
+@vertex
+fn vs_main(@builtin(vertex_index) index: u32) -> FragmentInput {
+    let clip = vec2(f32((index << 1u) & 2u), f32(index & 2u)) * 2.0 - 1.0;
+    var out: FragmentInput;
+    out.position = vec4(clip, 0.0, 1.0);
+    out.unit_pos = clip * test.extent;
+    return out;
+}
+
+@fragment
+fn fs_main(in: FragmentInput) -> @location(0) vec4<f32> {
+    if (shape_sdf(test.shape, in.unit_pos) > 0.0) {
+        discard;
+    }
+    return test.color;
+}
+"#;
+
+/// Half-width of unit-shape space shown by the shape harness; every unit shape fits inside.
+const SHAPE_TEST_EXTENT: f32 = 1.25;
+
+/// Returns a device and queue without a window, or `None` if no GPU adapter is available.
+fn headless_device() -> Option<(wgpu::Device, wgpu::Queue)> {
+    let instance = wgpu::Instance::new(&wgpu::InstanceDescriptor::from_env_or_default());
+    let adapter = pollster::block_on(instance.request_adapter(&wgpu::RequestAdapterOptions::default()))?;
+    let device = pollster::block_on(adapter.request_device(&wgpu::DeviceDescriptor::default(), None))
+        .expect("Failed to create device and queue");
+    Some(device)
+}
+
+/// Tests that the simulation shaders, including the edge feathering, pass validation.
+///
+/// Needs a GPU adapter, so it only runs with `cargo test -- --include-ignored`.
+#[test]
+#[ignore = "requires GPU adapter"]
+fn test_simulation_shaders_validate() {
+    let (device, _queue) = headless_device().expect("No GPU adapter available");
+
+    let sources = [
+        format!("{}\n{}", include_str!("../shaders/primitive_ren.wgsl"), include_str!("../shaders/primitive_utils.wgsl")),
+        include_str!("../shaders/circle_mesh.wgsl").to_string(),
+    ];
+    for source in sources {
+        device.push_error_scope(wgpu::ErrorFilter::Validation);
+        let _shader = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+            label: Some("Validation Test Shader"),
+            source: wgpu::ShaderSource::Wgsl(source.into()),
+        });
+        let error = pollster::block_on(device.pop_error_scope());
+        assert!(error.is_none(), "{error:?}");
+    }
+}
+
+/// Renders every `ShapeDesc` through the shader's `shape_sdf` into an offscreen texture,
+/// checking the center is filled with the shape color and a corner outside stays cleared.
+///
+/// Needs a GPU adapter, so it only runs with `cargo test -- --include-ignored`.
+#[test]
+#[ignore = "requires GPU adapter"]
+fn test_shape_sdf_rendering() {
+    const SIZE: u32 = 64;
+    const FORMAT: wgpu::TextureFormat = wgpu::TextureFormat::Rgba8Unorm;
+
+    let (device, queue) = headless_device().expect("No GPU adapter available");
+
+    let source = format!("{SHAPE_TEST_SHADER}\n{}", include_str!("../shaders/primitive_utils.wgsl"));
+    let shader = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+        label: Some("Shape Test Shader"),
+        source: wgpu::ShaderSource::Wgsl(source.into()),
+    });
+
+    let uniform = device.create_buffer(&wgpu::BufferDescriptor {
+        label: Some("Shape Test Uniform"),
+        size: 32,
+        usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+        mapped_at_creation: false,
+    });
+    let pipeline = device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+        label: Some("Shape Test Pipeline"),
+        layout: None,
+        vertex: wgpu::VertexState {
+            module: &shader,
+            entry_point: Some("vs_main"),
+            buffers: &[],
+            compilation_options: Default::default(),
+        },
+        fragment: Some(wgpu::FragmentState {
+            module: &shader,
+            entry_point: Some("fs_main"),
+            targets: &[Some(FORMAT.into())],
+            compilation_options: Default::default(),
+        }),
+        primitive: Default::default(),
+        depth_stencil: None,
+        multisample: Default::default(),
+        multiview: None,
+        cache: None,
+    });
+    let bind = device.create_bind_group(&wgpu::BindGroupDescriptor {
+        label: Some("Shape Test Bind Group"),
+        layout: &pipeline.get_bind_group_layout(0),
+        entries: &[wgpu::BindGroupEntry { binding: 0, resource: uniform.as_entire_binding() }],
+    });
+
+    let target = device.create_texture(&wgpu::TextureDescriptor {
+        label: Some("Shape Test Target"),
+        size: wgpu::Extent3d { width: SIZE, height: SIZE, depth_or_array_layers: 1 },
+        mip_level_count: 1,
+        sample_count: 1,
+        dimension: wgpu::TextureDimension::D2,
+        format: FORMAT,
+        usage: wgpu::TextureUsages::RENDER_ATTACHMENT | wgpu::TextureUsages::COPY_SRC,
+        view_formats: &[],
+    });
+    let view = target.create_view(&Default::default());
+
+    // Rows are already 256-byte aligned at this width.
+    let bytes_per_row = SIZE * 4;
+    let readback = device.create_buffer(&wgpu::BufferDescriptor {
+        label: Some("Shape Test Readback"),
+        size: (bytes_per_row * SIZE) as wgpu::BufferAddress,
+        usage: wgpu::BufferUsages::COPY_DST | wgpu::BufferUsages::MAP_READ,
+        mapped_at_creation: false,
+    });
+
+    let shapes = [
+        ShapeDesc::Circle,
+        ShapeDesc::Triangle,
+        ShapeDesc::Square,
+        ShapeDesc::Pentagon,
+        ShapeDesc::Pentagram,
+        ShapeDesc::Hexagon,
+        ShapeDesc::Hexagram,
+        ShapeDesc::Heptagon,
+        ShapeDesc::Heptagram,
+        ShapeDesc::Octagon,
+        ShapeDesc::Octagram,
+        ShapeDesc::Nonagon,
+        ShapeDesc::Enneagram,
+        ShapeDesc::Decagon,
+        ShapeDesc::Decagram,
+    ];
+    for shape in shapes {
+        let color = Color::rgb(200, 100, 50);
+        let mut data = [0u8; 32];
+        data[..16].copy_from_slice(bytemuck::cast_slice(&color.to_f32_array()));
+        data[16..20].copy_from_slice(&(shape as u32).to_ne_bytes());
+        data[20..24].copy_from_slice(&SHAPE_TEST_EXTENT.to_ne_bytes());
+        queue.write_buffer(&uniform, 0, &data);
+
+        let mut encoder = device.create_command_encoder(&Default::default());
+        {
+            let mut pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+                label: Some("Shape Test Pass"),
+                color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+                    view: &view,
+                    resolve_target: None,
+                    ops: wgpu::Operations {
+                        load: wgpu::LoadOp::Clear(wgpu::Color::TRANSPARENT),
+                        store: wgpu::StoreOp::Store,
+                    },
+                })],
+                depth_stencil_attachment: None,
+                timestamp_writes: None,
+                occlusion_query_set: None,
+            });
+            pass.set_pipeline(&pipeline);
+            pass.set_bind_group(0, &bind, &[]);
+            pass.draw(0..3, 0..1);
+        }
+        encoder.copy_texture_to_buffer(
+            target.as_image_copy(),
+            wgpu::TexelCopyBufferInfo {
+                buffer: &readback,
+                layout: wgpu::TexelCopyBufferLayout {
+                    offset: 0,
+                    bytes_per_row: Some(bytes_per_row),
+                    rows_per_image: None,
+                },
+            },
+            target.size(),
+        );
+        queue.submit([encoder.finish()]);
+
+        let slice = readback.slice(..);
+        slice.map_async(wgpu::MapMode::Read, |result| result.expect("Failed to map readback buffer"));
+        device.poll(wgpu::Maintain::Wait);
+        let pixels = slice.get_mapped_range().to_vec();
+        readback.unmap();
+
+        let pixel = |x: u32, y: u32| {
+            let i = (y * bytes_per_row + x * 4) as usize;
+            [pixels[i], pixels[i + 1], pixels[i + 2], pixels[i + 3]]
+        };
+        let center = SIZE / 2;
+        assert_eq!(pixel(center, center), [color.r, color.g, color.b, color.a], "{shape:?} center");
+        assert_eq!(pixel(0, 0), [0; 4], "{shape:?} corner");
+
+        // Stars are notched between their points, where the matching polygon is still filled.
+        if let (true, Some(points)) = (shape.is_star(), shape.sides()) {
+            let unit_pixel = |unit: Vec2| {
+                let screen = (vec2(unit.x, -unit.y) / SHAPE_TEST_EXTENT + 1.0) * 0.5 * SIZE as f32;
+                pixel(screen.x as u32, screen.y as u32)
+            };
+            assert_eq!(unit_pixel(vec2(0.85, 0.0))[3], 255, "{shape:?} point");
+            assert_eq!(unit_pixel(Vec2::from_angle(PI as f32 / points as f32) * 0.7), [0; 4], "{shape:?} notch");
+        }
+    }
+}
+
+/// Renders more primitives than the initial 100-element buffers hold through the simulation shader,
+/// growing the buffers and rebuilding the cell data bind group as `SimulationTile` does,
+/// then checks every cell shows up rather than only the first 100.
+///
+/// Needs a GPU adapter, so it only runs with `cargo test -- --include-ignored`.
+#[test]
+#[ignore = "requires GPU adapter"]
+fn test_render_grown_buffers() {
+    const WIDTH: u32 = 320;
+    const HEIGHT: u32 = 200;
+    const PIXELS_PER_UNIT: f32 = 10.0;
+    const FORMAT: wgpu::TextureFormat = wgpu::TextureFormat::Rgba8Unorm;
+
+    let (device, queue) = headless_device().expect("No GPU adapter available");
+
+    // A 15 by 10 grid of unconnected cells, two units apart.
+    let mut state = SimulationState::new(test_context());
+    let positions: Vec<Vec2d> = (0..15)
+        .flat_map(|i| (0..10).map(move |j| Vec2d::new(-14.0 + 2.0 * i as f64, -9.0 + 2.0 * j as f64)))
+        .collect();
+    state.spawn_vec(positions.iter().map(|&position| Cell::new(position, CellType::Neural)).collect()).unwrap();
+    let mut loader = EnvironmentRenderLoader::new();
+    loader.run(&state, None);
+    assert!(loader.gpu_primitives.len() > 100);
+
+    let storage = wgpu::BufferUsages::STORAGE | wgpu::BufferUsages::COPY_DST | wgpu::BufferUsages::COPY_SRC;
+    let vertex = wgpu::BufferUsages::VERTEX | wgpu::BufferUsages::COPY_DST;
+    let uniform = wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST;
+    let mut instance_buff = GpuBuffer::<GpuQuadRenderInstance>::new(&device, vertex, "Grow Test Instances", 100);
+    let mut index_buff = GpuBuffer::new(&device, storage, "Grow Test Indices", 100);
+    let mut primitive_buff = GpuBuffer::new(&device, storage, "Grow Test Primitives", 100);
+    let projection_buff = GpuBuffer::new(&device, uniform, "Grow Test Projection", 1);
+    let edge_buff = GpuBuffer::new(&device, uniform, "Grow Test Edge", 1);
+    let quad_buff = GpuBuffer::new(&device, vertex, "Grow Test Quad", 6);
+
+    let half = glam::vec2(WIDTH as f32, HEIGHT as f32) * 0.5 / PIXELS_PER_UNIT;
+    let projection = glam::Mat4::orthographic_rh(-half.x, half.x, -half.y, half.y, -1.0, 1.0);
+    projection_buff.write(&queue, &projection.to_cols_array_2d());
+    edge_buff.write(&queue, &EdgeInfoUniform::new(SimulationRenderResources::DEFAULT_EDGE_FEATHER));
+    quad_buff.write_array(&queue, &AABB::UNIT.corners().ccw_mesh());
+
+    let shader = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+        label: Some("Grow Test Shader"),
+        source: wgpu::ShaderSource::Wgsl(
+            format!("{}\n{}", include_str!("../shaders/primitive_ren.wgsl"), include_str!("../shaders/primitive_utils.wgsl")).into(),
+        ),
+    });
+    let pipeline = device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+        label: Some("Grow Test Pipeline"),
+        layout: None,
+        vertex: wgpu::VertexState {
+            module: &shader,
+            entry_point: Some("vs_main"),
+            buffers: &[GpuVertex::desc(), GpuQuadRenderInstance::desc()],
+            compilation_options: Default::default(),
+        },
+        fragment: Some(wgpu::FragmentState {
+            module: &shader,
+            entry_point: Some("fs_main"),
+            targets: &[Some(wgpu::ColorTargetState {
+                format: FORMAT,
+                blend: Some(wgpu::BlendState::ALPHA_BLENDING),
+                write_mask: wgpu::ColorWrites::ALL,
+            })],
+            compilation_options: Default::default(),
+        }),
+        primitive: Default::default(),
+        depth_stencil: None,
+        multisample: Default::default(),
+        multiview: None,
+        cache: None,
+    });
+    let bind = |group: u32, buffers: &[&wgpu::Buffer]| {
+        let entries: Vec<_> = buffers
+            .iter()
+            .enumerate()
+            .map(|(i, buffer)| wgpu::BindGroupEntry { binding: i as u32, resource: buffer.as_entire_binding() })
+            .collect();
+        device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: Some("Grow Test Bind Group"),
+            layout: &pipeline.get_bind_group_layout(group),
+            entries: &entries,
+        })
+    };
+    let projection_bind = bind(0, &[&projection_buff.buffer, &edge_buff.buffer]);
+    let mut cell_data_bind = bind(1, &[&index_buff.buffer, &primitive_buff.buffer]);
+
+    instance_buff.reserve(&device, loader.gpu_render_instances.len());
+    let indices_grew = index_buff.reserve(&device, loader.gpu_primitive_indices.len());
+    let primitives_grew = primitive_buff.reserve(&device, loader.gpu_primitives.len());
+    assert!(indices_grew && primitives_grew);
+    if indices_grew || primitives_grew {
+        cell_data_bind = bind(1, &[&index_buff.buffer, &primitive_buff.buffer]);
+    }
+
+    instance_buff.write_array(&queue, &loader.gpu_render_instances);
+    index_buff.write_array(&queue, &loader.gpu_primitive_indices);
+    primitive_buff.write_array(&queue, &loader.gpu_primitives);
+    let uploaded = primitive_buff.read_back(&device, &queue);
+    assert_eq!(
+        bytemuck::cast_slice::<_, u8>(&uploaded[..loader.gpu_primitives.len()]),
+        bytemuck::cast_slice::<_, u8>(&loader.gpu_primitives),
+    );
+
+    let target = device.create_texture(&wgpu::TextureDescriptor {
+        label: Some("Grow Test Target"),
+        size: wgpu::Extent3d { width: WIDTH, height: HEIGHT, depth_or_array_layers: 1 },
+        mip_level_count: 1,
+        sample_count: 1,
+        dimension: wgpu::TextureDimension::D2,
+        format: FORMAT,
+        usage: wgpu::TextureUsages::RENDER_ATTACHMENT | wgpu::TextureUsages::COPY_SRC,
+        view_formats: &[],
+    });
+    let view = target.create_view(&Default::default());
+
+    // Rows are already 256-byte aligned at this width.
+    let bytes_per_row = WIDTH * 4;
+    let readback = device.create_buffer(&wgpu::BufferDescriptor {
+        label: Some("Grow Test Readback"),
+        size: (bytes_per_row * HEIGHT) as wgpu::BufferAddress,
+        usage: wgpu::BufferUsages::COPY_DST | wgpu::BufferUsages::MAP_READ,
+        mapped_at_creation: false,
+    });
+
+    let mut encoder = device.create_command_encoder(&Default::default());
+    {
+        let mut pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+            label: Some("Grow Test Pass"),
+            color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+                view: &view,
+                resolve_target: None,
+                ops: wgpu::Operations {
+                    load: wgpu::LoadOp::Clear(wgpu::Color::TRANSPARENT),
+                    store: wgpu::StoreOp::Store,
+                },
+            })],
+            depth_stencil_attachment: None,
+            timestamp_writes: None,
+            occlusion_query_set: None,
+        });
+        pass.set_pipeline(&pipeline);
+        pass.set_bind_group(0, &projection_bind, &[]);
+        pass.set_bind_group(1, &cell_data_bind, &[]);
+        pass.set_vertex_buffer(0, quad_buff.buffer.slice(..));
+        pass.set_vertex_buffer(1, instance_buff.buffer.slice(..));
+        pass.draw(0..6, 0..loader.gpu_render_instances.len() as u32);
+    }
+    encoder.copy_texture_to_buffer(
+        target.as_image_copy(),
+        wgpu::TexelCopyBufferInfo {
+            buffer: &readback,
+            layout: wgpu::TexelCopyBufferLayout {
+                offset: 0,
+                bytes_per_row: Some(bytes_per_row),
+                rows_per_image: None,
+            },
+        },
+        target.size(),
+    );
+    queue.submit([encoder.finish()]);
+
+    let slice = readback.slice(..);
+    slice.map_async(wgpu::MapMode::Read, |result| result.expect("Failed to map readback buffer"));
+    device.poll(wgpu::Maintain::Wait);
+    let pixels = slice.get_mapped_range().to_vec();
+    readback.unmap();
+
+    // Membranes are drawn as outlines, so look for any covered pixel within each cell's box.
+    let covered = |x: u32, y: u32| pixels[(y * bytes_per_row + x * 4 + 3) as usize] != 0;
+    for position in &positions {
+        let center = (position.to_glam() * glam::vec2(1.0, -1.0) + half) * PIXELS_PER_UNIT;
+        let reach = (0.5 * PIXELS_PER_UNIT) as u32;
+        let (x, y) = (center.x as u32, center.y as u32);
+        let found = (y - reach..y + reach).any(|y| (x - reach..x + reach).any(|x| covered(x, y)));
+        assert!(found, "cell at {position:?} missing");
+    }
+}
+
+/// Tests that a connection's own rest length and stiffness drive its springs.
+#[test]
+fn test_connection_tunables() {
+    let settle = |primary_k: f64| {
+        let mut state = SimulationState::new(test_context());
+        state.spawn_vec(vec![
+            Cell::new(Vec2d::ZERO, CellType::Neural),
+            Cell::new(Vec2d::new(1.0, 0.0), CellType::Neural),
+        ]).unwrap();
+        let connection = CellConnection::new(0, 0.0, 1, PI).with_rest_length(2.0).with_stiffness(primary_k, 0.0);
+        state.connect_with(connection.clone()).unwrap();
+        assert_eq!(state.connect_with(connection), Err(ConnectError::AlreadyConnected));
+
+        for _ in 0..30 {
+            state.tick(1.0 / 60.0);
+        }
+        state.cells.get(0).position.distance(state.cells.get(1).position)
+    };
+
+    // A stiff spring reaches its 2.0 rest length sooner than a floppy one.
+    let (stiff, floppy) = (settle(200.0), settle(5.0));
+    assert!((stiff - 2.0).abs() < (floppy - 2.0).abs(), "{stiff} vs {floppy}");
+    assert!(stiff > 1.5);
+
+    // The connection keeps the kind and joint limits it was built with.
+    let mut state = SimulationState::new(test_context());
+    state.spawn_vec(vec![Cell::new(Vec2d::ZERO, CellType::Neural), Cell::new(Vec2d::new(1.0, 0.0), CellType::Neural)]).unwrap();
+    let rope = CellConnection::new(0, 0.0, 1, PI).with_kind(ConnectionKind::Rope).with_angle_limits(-0.5, 0.5);
+    state.connect_with(rope).unwrap();
+    let connection = &state.connections[0];
+    assert_eq!(connection.kind, ConnectionKind::Rope);
+    assert_eq!((connection.angle_min, connection.angle_max), (Some(-0.5), Some(0.5)));
+    assert_eq!(state.connect_with(CellConnection::new(1, 0.0, 1, PI)), Err(ConnectError::SelfConnection(1)));
+}
+
+/// Tests that `neighbors` lists each connection from both ends and follows connection changes.
+#[test]
+fn test_neighbors() {
+    let mut state = benches::organism_lookn_cells(test_context());
+    let sorted = |state: &SimulationState, id| {
+        let mut ids: Vec<_> = state.neighbors(id).map(|(other, _)| other).collect();
+        ids.sort();
+        ids
+    };
+
+    assert_eq!(sorted(&state, 0), vec![1, 2, 3, 4]);
+    assert_eq!(sorted(&state, 3), vec![0]);
+    assert!(state.neighbors(0).all(|(other, connection)| connection.links(0, other)));
+    assert!(sorted(&state, 99).is_empty());
+
+    state.disconnect(0, 3);
+    assert_eq!(sorted(&state, 0), vec![1, 2, 4]);
+    assert!(sorted(&state, 3).is_empty());
+
+    state.connect_auto(3, 4).unwrap();
+    assert_eq!(sorted(&state, 4), vec![0, 3]);
+}
+
+/// Tests that undo restores edits in reverse order and forgets the oldest past its depth.
+#[test]
+fn test_undo_stack() {
+    let mut state = benches::organism_lookn_cells(test_context());
+    let mut undo = UndoStack::new(2);
+
+    for id in [4, 3, 2] {
+        undo.push(&state);
+        state.remove(id);
+    }
+    assert_eq!(state.cells.len(), 2);
+
+    assert!(undo.undo(&mut state));
+    assert_eq!(state.cells.len(), 3);
+    assert!(undo.undo(&mut state));
+    assert_eq!(state.cells.len(), 4);
+    assert_eq!(state.neighbors(0).count(), 3);
+
+    // The first removal fell off the bottom of the stack.
+    assert!(!undo.undo(&mut state));
+    assert_eq!(state.cells.len(), 4);
+
+    // Clones are independent of the original.
+    let copy = state.clone();
+    state.remove(0);
+    assert_eq!(copy.cells.len(), 4);
+    assert_eq!(copy.neighbors(0).count(), 3);
+}
+
+/// Tests that supersampled captures are box-filtered down by whole blocks.
+#[test]
+fn test_downsample() {
+    let (red, blue) = (image::Rgba([255, 0, 0, 255]), image::Rgba([0, 0, 255, 255]));
+    let image = image::RgbaImage::from_fn(4, 2, |x, _| if x % 2 == 0 { red } else { blue });
+    let small = downsample(&image, 2);
+    assert_eq!(small.dimensions(), (2, 1));
+    assert_eq!(small.get_pixel(0, 0).0, [128, 0, 128, 255]);
+    assert_eq!(downsample(&image, 1), image);
+}
+
+/// Tests that arrows span their vector from the start and every debug primitive gets its own instance.
+#[test]
+fn test_debug_arrows() {
+    let start = Vec2::new(1.0, 2.0);
+    let [shaft, head] = VectorFieldTile::arrow(start, Vec2::new(0.0, 1.0), Color::YELLOW).unwrap();
+    assert!(shaft.transform.translate.abs_diff_eq(Vec2::new(1.0, 2.5), 1e-5));
+    assert!(head.transform.translate.abs_diff_eq(Vec2::new(1.0, 3.0), 1e-5));
+    assert!((shaft.transform.rotate - std::f32::consts::FRAC_PI_2).abs() < 1e-5);
+    assert!(VectorFieldTile::arrow(start, Vec2::ZERO, Color::RED).is_none());
+
+    let instances = DebugPrimitiveTile::instances(&[shaft, head]);
+    assert_eq!(instances.len(), 2);
+    for (i, instance) in instances.iter().enumerate() {
+        assert_eq!((instance.start_i, instance.end_i), (i as u32, i as u32 + 1));
+    }
+}
+
+/// Tests that the net force survives integration for debug overlays.
+#[test]
+fn test_last_force_recorded() {
+    let mut state = SimulationState::new(test_context());
+    let id = state.spawn(Cell::new(Vec2d::ZERO, CellType::Fat)).unwrap();
+    state.cells.get_mut(id).velocity = Vec2d::new(1.0, 0.0);
+    state.physics_pass(0.01);
+
+    let cell = state.cells.get(id);
+    assert_eq!(cell.force, Vec2d::ZERO);
+    // Viscous drag opposes the motion.
+    assert!(cell.last_force.x < 0.0);
+}
+
+/// Tests that serializing a heap keeps values at their slot indices, leaving freed slots free.
+#[test]
+fn test_heap_serialization() {
+    let mut heap: Heap<u32> = Heap::with_capacity(0);
+    heap.insert_alloc_vec(vec![10, 11, 12, 13, 14]);
+    heap.free(2);
+
+    let saved = ron::to_string(&heap).unwrap();
+    let loaded: Heap<u32> = ron::from_str(&saved).unwrap();
+    assert_eq!(loaded.capacity(), 5);
+    assert_eq!(loaded.len(), 4);
+    assert_eq!(*loaded.get(3), 13);
+    assert!(!loaded.is_initialized(2));
+    assert_eq!(loaded.free_count(), 1);
+
+    assert!(ron::from_str::<Heap<u32>>("(capacity: 1, values: [(1, 5)])").is_err());
+    assert!(ron::from_str::<Heap<u32>>("(capacity: 2, values: [(0, 5), (0, 6)])").is_err());
+}
+
+/// Tests that title stats report the average frame rate once per interval.
+#[test]
+fn test_title_stats() {
+    let mut stats = TitleStats::new(true, Duration::from_secs(1));
+    let start = std::time::Instant::now();
+
+    for frame in 1..60 {
+        assert_eq!(stats.record_frame(start + Duration::from_millis(frame * 10)), None);
+    }
+    let fps = stats.record_frame(start + Duration::from_secs(2)).unwrap();
+    assert!((fps - 30.0).abs() < 1e-3);
+    assert_eq!(stats.record_frame(start + Duration::from_millis(2500)), None);
+}
+
+/// Tests that identical runs produce bit-identical states and render data.
+#[test]
+fn test_deterministic_runs() {
+    let run = || {
+        let mut state = benches::organism_lookn_cells(test_context());
+        for _ in 0..200 {
+            state.tick(1.0 / 60.0);
+        }
+        let mut loader = EnvironmentRenderLoader::new();
+        loader.run(&state, None);
+        (state, loader)
+    };
+    let ((state_a, loader_a), (state_b, loader_b)) = (run(), run());
+
+    for (a, b) in state_a.cells.flatten_iter().zip(state_b.cells.flatten_iter()) {
+        assert_eq!(a.position.x.to_bits(), b.position.x.to_bits());
+        assert_eq!(a.position.y.to_bits(), b.position.y.to_bits());
+        assert_eq!(a.angle.to_bits(), b.angle.to_bits());
+    }
+    let bytes = |loader: &EnvironmentRenderLoader| {
+        [
+            bytemuck::cast_slice::<_, u8>(&loader.gpu_primitives).to_vec(),
+            bytemuck::cast_slice(&loader.gpu_primitive_indices).to_vec(),
+            bytemuck::cast_slice(&loader.gpu_render_instances).to_vec(),
+        ]
+    };
+    assert_eq!(bytes(&loader_a), bytes(&loader_b));
+}
+
+/// Tests that tiles are updated and drawn in the order they were added.
+#[test]
+fn test_tile_order() {
+    let mut manager = TileViewManager::new();
+    let root = manager.root();
+    let tiles = manager.grid(root, 3, 3);
+    let mut added = tiles.clone();
+    added.push(manager.add_leaf(root, Style::default()));
+
+    // Row containers come first, as the grid adds them before their cells.
+    let order: Vec<_> = manager.tile_order().iter().filter(|node| added.contains(node)).copied().collect();
+    assert_eq!(order, added);
+}
+
+/// Tests that viscosity changed at runtime is clamped and slows cells from the next tick.
+#[test]
+fn test_set_viscosity() {
+    let coast = |viscosity: f64| {
+        let mut state = SimulationState::new(test_context());
+        let id = state.spawn(Cell::new(Vec2d::ZERO, CellType::Fat)).unwrap();
+        state.cells.get_mut(id).velocity = Vec2d::new(1.0, 0.0);
+        state.set_viscosity(viscosity);
+        state.tick(0.01);
+        state.cells.get(id).velocity.x
+    };
+    assert!(coast(100.0) < coast(1.0));
+    assert_eq!(coast(0.0), 1.0);
+
+    // Spinning slows with viscosity too.
+    let spin = |viscosity: f64| {
+        let mut state = SimulationState::new(test_context());
+        let id = state.spawn(Cell::new(Vec2d::ZERO, CellType::Fat)).unwrap();
+        state.cells.get_mut(id).angular_velocity = 1.0;
+        state.set_viscosity(viscosity);
+        state.tick(0.01);
+        state.cells.get(id).angular_velocity
+    };
+    assert!(spin(100.0) < spin(1.0));
+    assert_eq!(spin(0.0), 1.0);
+
+    let mut state = SimulationState::new(test_context());
+    state.set_viscosity(-3.0);
+    assert_eq!(state.viscosity(), 0.0);
+    state.set_viscosity(f64::NAN);
+    assert_eq!(state.viscosity(), 0.0);
+}
+
+/// Tests containment, bounds, and GJK overlap across mixed collision shapes.
+#[test]
+fn test_collision_shapes() {
+    let disk = |x: f64, y: f64| Placed { shape: Disk::new(1.0, 1.0), position: Vec2d::new(x, y), angle: 0.0 };
+    let square = Placed {
+        shape: Rectangle::new(2.0, 2.0, 1.0),
+        position: Vec2d::ZERO,
+        angle: TAU / 8.0,
+    };
+
+    // The square turned 45 degrees reaches its corners out along the axes.
+    let bounds = square.aabb();
+    assert!((bounds.max.x - 2f64.sqrt()).abs() < 1e-9 && (bounds.min.y + 2f64.sqrt()).abs() < 1e-9);
+    assert!(square.contains(Vec2d::new(1.3, 0.0)));
+    assert!(!square.contains(Vec2d::new(1.0, 1.0)));
+
+    assert!(collide(&disk(1.9, 0.0), &disk(0.0, 0.0)));
+    assert!(!collide(&disk(2.1, 0.0), &disk(0.0, 0.0)));
+    assert!(collide(&disk(2.3, 0.0), &square));
+    // Bounds overlap near the corner, but the narrow phase separates them.
+    assert!(!collide(&disk(1.6, 1.6), &square));
+
+    // A four-sided polygon has its corners on the axes too, covering the same points.
+    let diamond = Placed { shape: RegularPolygon::new(4, 2f64.sqrt(), 1.0), position: Vec2d::ZERO, angle: 0.0 };
+    for point in [Vec2d::new(1.3, 0.0), Vec2d::new(0.0, -1.3), Vec2d::new(1.0, 1.0), Vec2d::new(0.7, 0.6)] {
+        assert_eq!(diamond.contains(point), square.contains(point));
+    }
+
+    let hexagon = RegularPolygon::new(6, 1.0, 1.0);
+    assert!(hexagon.contains(Vec2d::new(0.99, 0.0)));
+    assert!(!hexagon.contains(Vec2d::new(0.0, 0.9)));
+    assert_eq!(hexagon.support(Vec2d::new(1.0, 0.1)), Vec2d::new(1.0, 0.0));
+
+    // Cells collide through their type's membrane shape.
+    let muscle = Cell::new(Vec2d::new(0.95, 0.0), CellType::Muscle);
+    let neural = Cell::new(Vec2d::ZERO, CellType::Neural);
+    assert!(collide(&muscle.collision_shape(), &neural.collision_shape()));
+    let apart = Cell::new(Vec2d::new(0.0, 1.1), CellType::Muscle);
+    assert!(!collide(&apart.collision_shape(), &neural.collision_shape()));
+
+    // Square cells collide as rectangles with their corners where the rendered square has them.
+    let spore = Cell::new(Vec2d::ZERO, CellType::Spore);
+    let corner = spore.collision_shape().support(Vec2d::new(1.0, 0.01));
+    assert!((corner - Vec2d::new(spore.radius(), 0.0)).length() < 1e-9, "{corner:?}");
+}
+
+/// Tests that ticking pushes overlapping unconnected cells apart, but leaves connected ones to their springs.
+#[test]
+fn test_collision_pass() {
+    let distance_after_ticks = |collision_stiffness: f64, connected: bool| {
+        let context = SimContext::builder().collision_stiffness(collision_stiffness).build();
+        let mut state = SimulationState::new(context);
+        let a = state.spawn(Cell::new(Vec2d::ZERO, CellType::Neural)).unwrap();
+        let b = state.spawn(Cell::new(Vec2d::new(0.5, 0.0), CellType::Muscle)).unwrap();
+        if connected {
+            state.connect_auto(a, b).unwrap();
+        }
+        for _ in 0..120 {
+            state.tick(1.0 / 60.0);
+        }
+        state.cells.get(a).position.distance(state.cells.get(b).position)
+    };
+
+    assert!(distance_after_ticks(100.0, false) > 0.9);
+    assert_eq!(distance_after_ticks(0.0, false), 0.5);
+    assert_eq!(distance_after_ticks(100.0, true), distance_after_ticks(0.0, true));
+}
+
+/// Tests that non-finite cells are reported, and reset in place when quarantine is enabled.
+#[test]
+fn test_quarantine_non_finite_cells() {
+    let mut state = benches::organism_lookn_cells(test_context());
+    assert_eq!(state.validate(), Ok(()));
+
+    state.cells.get_mut(3).position = Vec2d::new(f64::NAN, 0.0);
+    state.cells.get_mut(4).angular_velocity = f64::INFINITY;
+    assert_eq!(state.validate(), Err(vec![3, 4]));
+
+    // Without quarantine, debug builds report each bad cell once, as it turns non-finite.
+    if cfg!(debug_assertions) {
+        let mut unguarded = state.clone();
+        let reported = Arc::new(Mutex::new(Vec::new()));
+        let sink = reported.clone();
+        unguarded.subscribe(move |event| {
+            if let SimEvent::NonFinite { id, .. } = event {
+                sink.lock().unwrap().push(*id);
+            }
+        });
+        unguarded.tick(1.0 / 60.0);
+        assert_eq!(*reported.lock().unwrap(), vec![3, 4]);
+
+        // The bad values spread to neighbors through the springs, which are reported as they go bad.
+        unguarded.tick(1.0 / 60.0);
+        let reported = reported.lock().unwrap().clone();
+        let mut unique = reported.clone();
+        unique.sort();
+        unique.dedup();
+        assert!(reported.len() > 2, "{reported:?}");
+        assert_eq!(unique.len(), reported.len());
+    }
+
+    let quarantined = Arc::new(Mutex::new(Vec::new()));
+    let sink = quarantined.clone();
+    state.subscribe(move |event| {
+        if let SimEvent::Quarantined { id, .. } = event {
+            sink.lock().unwrap().push(*id);
+        }
+    });
+    state.context.quarantine = true;
+    state.tick(1.0 / 60.0);
+
+    assert_eq!(*quarantined.lock().unwrap(), vec![3, 4]);
+    assert_eq!(state.validate(), Ok(()));
+    // Cell 3 hangs off the center cell alone, so it lands back near it.
+    assert!(state.cells.get(3).position.distance(state.cells.get(0).position) < 2.0);
+}
+
+/// Tests that theme overrides replace only the colors and shapes they name.
+#[test]
+fn test_theme_overrides() {
+    let theme = ThemeConfig::from_ron("(type_colors: {Neural: (r: 1, g: 2, b: 3, a: 255)}, type_shapes: {Fat: Square})").unwrap();
+
+    let neural = CellType::Neural.get_membrane_primitive(&theme);
+    assert_eq!(neural.color, Color::rgb(1, 2, 3));
+    assert_eq!(neural.shape, ShapeDesc::Circle);
+    let fat = CellType::Fat.get_membrane_primitive(&theme);
+    assert_eq!((fat.color, fat.shape), (Color::YELLOW, ShapeDesc::Square));
+    let muscle = CellType::Muscle.get_membrane_primitive(&ThemeConfig::default());
+    assert_eq!((muscle.color, muscle.shape), (Color::RED, ShapeDesc::Hexagon));
+
+    // The colorblind theme covers every type with its own color.
+    let colorblind = ThemeConfig::colorblind();
+    let mut colors: Vec<_> = CellType::LIST
+        .iter()
+        .map(|typ| {
+            let Color { r, g, b, a } = typ.get_membrane_primitive(&colorblind).color;
+            [r, g, b, a]
+        })
+        .collect();
+    colors.sort();
+    colors.dedup();
+    assert_eq!(colors.len(), CellType::LIST.len());
+}
+
+/// Tests that selection bounds cover exactly the chosen live cells.
+#[test]
+fn test_bounds_of() {
+    let state = benches::organism_lookn_cells(test_context());
+    let single = AABB::UNIT.transformed(state.cells.get(3).get_transform());
+
+    assert_eq!(state.bounds_of(&[]), None);
+    assert_eq!(state.bounds_of(&[999]), None);
+    assert_eq!(state.bounds_of(&[3, 999]), Some(single));
+
+    let pair = state.bounds_of(&[3, 4]).unwrap();
+    assert_eq!(pair | single, pair);
+    let everything: Vec<_> = state.cells.iter_indexed().map(|(id, _)| id).collect();
+    assert_eq!(state.bounds_of(&everything), Some(state.world_bounds()));
+}
+
+/// Tests that a 16:9 tile clipped by extreme window shapes still draws circles round,
+/// sizing its projection from the clipped area it is drawn in.
+#[test]
+fn test_clipped_tile_aspect() {
+    for window in [Vec2::new(3000.0, 400.0), Vec2::new(400.0, 3000.0), Vec2::new(1600.0, 900.0)] {
+        let mut manager = TileViewManager::new();
+        let tile = manager.add_leaf(manager.root(), Style {
+            size: Size { width: Dimension::percent(0.8), height: Dimension::auto() },
+            aspect_ratio: Some(16.0 / 9.0),
+            ..Default::default()
+        });
+        manager.resize(window);
+
+        let clipped = manager.get_aabb_clipped(tile).unwrap();
+        assert!(clipped.max().cmple(window).all() && clipped.min().cmpge(Vec2::ZERO).all());
+
+        let camera = Camera::new(AABB::new(Vec2::ZERO, Vec2::new(7.5, 5.0)));
+        let size = clipped.wh();
+        let center = camera.world_to_screen(Vec2::ZERO, size);
+        let radii: Vec<f32> = [Vec2::X, Vec2::Y, -Vec2::X, -Vec2::Y]
+            .iter()
+            .map(|&direction| camera.world_to_screen(direction, size).distance(center))
+            .collect();
+        assert!(radii.iter().all(|radius| (radius - radii[0]).abs() < 1e-2 * radii[0]), "{window}: {radii:?}");
+    }
+}
+
+/// Tests that union-find grouping matches BFS grouping, group for group.
+#[test]
+fn test_groups_union_find() {
+    let connections = vec![IdxPair::new(4, 1), IdxPair::new(1, 2), IdxPair::new(3, 5), IdxPair::new(2, 4)];
+    let groups = |csr: CSR| -> Vec<Vec<usize>> {
+        csr.indptr
+            .iter()
+            .map(|range| {
+                let mut group = csr.indices[range.a..range.b].to_vec();
+                group.sort();
+                group
+            })
+            .collect()
+    };
+
+    let union_find = groups(CSR::groups_union_find(&connections, 6));
+    assert_eq!(union_find, vec![vec![0], vec![1, 2, 4], vec![3, 5], vec![6]]);
+    assert_eq!(union_find, groups(CSR::groups_from_connections(&connections, 6)));
+}
+
+/// Tests that partial buffer writes land at their element offset, leaving the rest untouched.
+///
+/// Needs a GPU adapter, so it only runs with `cargo test -- --include-ignored`.
+#[test]
+#[ignore = "requires GPU adapter"]
+fn test_write_array_at() {
+    let (device, queue) = headless_device().expect("No GPU adapter available");
+
+    let buffer: GpuBuffer<[u32; 2]> = GpuBuffer::new(
+        &device,
+        wgpu::BufferUsages::COPY_DST | wgpu::BufferUsages::COPY_SRC,
+        "Partial Write Test",
+        4,
+    );
+    buffer.write_array(&queue, &[[1, 1], [2, 2], [3, 3], [4, 4]]);
+    buffer.write_array_at(&queue, 2, &[[7, 8]]);
+    assert_eq!(buffer.read_back(&device, &queue), vec![[1, 1], [2, 2], [7, 8], [4, 4]]);
+
+    buffer.write_array_at(&queue, 3, &[[9, 9]]);
+    assert_eq!(buffer.read_back(&device, &queue)[3], [9, 9]);
+    let overflow = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| buffer.write_array_at(&queue, 3, &[[0, 0], [0, 0]])));
+    assert!(overflow.is_err());
+}
+
+/// Tests picking a connection end by its edge point and turning it to a new angle.
+#[test]
+fn test_connection_ends() {
+    let mut state = benches::organism_lookn_cells(test_context());
+    assert_eq!(state.connection_ends().count(), 2 * state.connections.len());
+
+    let edge = state.cells.get(0).edge_point(TAU / 4.0);
+    assert_eq!(state.pick_connection_end(edge + Vec2d::new(0.05, 0.0), 0.1), Some((0, 2)));
+    assert_eq!(state.pick_connection_end(Vec2d::new(100.0, 0.0), 0.1), None);
+
+    assert!(state.set_connection_angle(2, 0, TAU));
+    let connection = state.connections.iter().find(|c| c.links(0, 2)).unwrap();
+    let angle_at_2 = if connection.id_a == 2 { connection.angle_a } else { connection.angle_b };
+    assert!(angle_at_2.abs() < 1e-9);
+    assert!(!state.set_connection_angle(1, 2, 0.0));
+}
+
+/// Render layer that draws nothing, standing in for a layer of the given phase.
+struct PhaseLayer(RenderPhase);
+
+impl TileRenderer for PhaseLayer {
+    fn init(&self, _queue: &wgpu::Queue) {}
+    fn resize(&mut self, _size: Vec2, _queue: &wgpu::Queue) {}
+    fn update_render_data(&mut self, _state: &SimulationState, _context: &GpuContext) {}
+    fn render_pipeline<'a>(&'a self, _render_pass: &mut wgpu::RenderPass<'a>) {}
+
+    fn phase(&self) -> RenderPhase {
+        self.0
+    }
+}
+
+/// Tests that a tile's layers are ordered by phase regardless of the order they were given in.
+#[test]
+fn test_render_phase_order() {
+    let mut manager = TileViewManager::new();
+    let tile = manager.add_leaf(manager.root(), Style::default());
+
+    let layers: Vec<Box<dyn TileRenderer>> = vec![
+        Box::new(PhaseLayer(RenderPhase::Border)),
+        Box::new(PhaseLayer(RenderPhase::Overlay)),
+        Box::new(PhaseLayer(RenderPhase::Background)),
+        Box::new(PhaseLayer(RenderPhase::Cells)),
+        Box::new(PhaseLayer(RenderPhase::Overlay)),
+    ];
+    manager.set_renderers(tile, layers);
+
+    let order: Vec<RenderPhase> = manager.take_renderers(tile).iter().map(|layer| layer.phase()).collect();
+    assert_eq!(order, [
+        RenderPhase::Background,
+        RenderPhase::Cells,
+        RenderPhase::Overlay,
+        RenderPhase::Overlay,
+        RenderPhase::Border,
+    ]);
+}
+
+/// Render layer recording which updates it received, as `(resized to, view updates, data updates)`.
+struct RecordingLayer(Arc<Mutex<(Vec2, usize, usize)>>);
+
+impl TileRenderer for RecordingLayer {
+    fn init(&self, _queue: &wgpu::Queue) {}
+    fn resize(&mut self, size: Vec2, _queue: &wgpu::Queue) {
+        self.0.lock().unwrap().0 = size;
+    }
+    fn update_view(&mut self, _queue: &wgpu::Queue) {
+        self.0.lock().unwrap().1 += 1;
+    }
+    fn update_render_data(&mut self, _state: &SimulationState, _context: &GpuContext) {
+        self.0.lock().unwrap().2 += 1;
+    }
+    fn render_pipeline<'a>(&'a self, _render_pass: &mut wgpu::RenderPass<'a>) {}
+}
+
+/// Tests that updating views resizes layers and moves their cameras without touching simulation data,
+/// so frames drawn while the simulation is busy still follow the window and camera.
+///
+/// Needs a GPU adapter, so it only runs with `cargo test -- --include-ignored`.
+#[test]
+#[ignore = "requires GPU adapter"]
+fn test_update_views_without_state() {
+    let (_device, queue) = headless_device().expect("No GPU adapter available");
+
+    let mut manager = TileViewManager::new();
+    let tile = manager.add_leaf(manager.root(), Style {
+        size: Size { width: Dimension::percent(1.0), height: Dimension::percent(1.0) },
+        ..Default::default()
+    });
+    let record = Arc::new(Mutex::new((Vec2::ZERO, 0, 0)));
+    manager.set_renderers(tile, vec![Box::new(RecordingLayer(record.clone()))]);
+    manager.resize(Vec2::new(320.0, 200.0));
+
+    manager.update_views(&queue);
+    manager.update_views(&queue);
+    assert_eq!(*record.lock().unwrap(), (Vec2::new(320.0, 200.0), 2, 0));
+}
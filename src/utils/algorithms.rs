@@ -50,6 +50,33 @@ impl CSR {
         Self { indices, indptr }
     }
 
+    /// Builds per-node lists of the indices of the connections touching each node
+    pub fn incident_from_connections(connections: &[IdxPair], node_count: usize) -> Self {
+        let mut degrees = vec![0usize; node_count];
+        for conn in connections {
+            degrees[conn.a] += 1;
+            degrees[conn.b] += 1;
+        }
+
+        let mut indptr = Vec::with_capacity(node_count);
+        let mut offset = 0;
+        for &deg in &degrees {
+            indptr.push(IdxPair::new(offset, offset + deg));
+            offset += deg;
+        }
+
+        let mut indices = vec![0usize; offset];
+        let mut write_pos: Vec<usize> = indptr.iter().map(|p| p.a).collect();
+        for (i, conn) in connections.iter().enumerate() {
+            indices[write_pos[conn.a]] = i;
+            write_pos[conn.a] += 1;
+            indices[write_pos[conn.b]] = i;
+            write_pos[conn.b] += 1;
+        }
+
+        Self { indices, indptr }
+    }
+
     /// Computes connected groups using BFS over adjacency lists
     pub fn groups_from_connections(connections: &[IdxPair], max_index: usize) -> Self {
         let adj = CSR::adjacent_from_connections(connections, max_index);
@@ -86,6 +113,98 @@ impl CSR {
         CSR { indices, indptr }
     }
 
+    /// Computes connected groups with a union-find over the connections, skipping adjacency lists.
+    /// Groups come in the same order as `groups_from_connections`, each listing its nodes ascending.
+    pub fn groups_union_find(connections: &[IdxPair], max_index: usize) -> Self {
+        let node_count = max_index + 1;
+        let mut parent: Vec<usize> = (0..node_count).collect();
+        let mut size = vec![1usize; node_count];
+
+        // Follows parents to the root, halving the path along the way
+        fn find(parent: &mut [usize], mut node: usize) -> usize {
+            while parent[node] != node {
+                parent[node] = parent[parent[node]];
+                node = parent[node];
+            }
+            node
+        }
+
+        for conn in connections {
+            let (mut a, mut b) = (find(&mut parent, conn.a), find(&mut parent, conn.b));
+            if a == b {
+                continue;
+            }
+            // Hang the smaller tree under the larger to keep trees shallow
+            if size[a] < size[b] {
+                std::mem::swap(&mut a, &mut b);
+            }
+            parent[b] = a;
+            size[a] += size[b];
+        }
+
+        // Number groups by their smallest node, then bucket nodes by group
+        let mut group_of_root = vec![usize::MAX; node_count];
+        let mut group_sizes = Vec::new();
+        let groups: Vec<usize> = (0..node_count)
+            .map(|node| {
+                let root = find(&mut parent, node);
+                if group_of_root[root] == usize::MAX {
+                    group_of_root[root] = group_sizes.len();
+                    group_sizes.push(0);
+                }
+                let group = group_of_root[root];
+                group_sizes[group] += 1;
+                group
+            })
+            .collect();
+
+        let mut indptr = Vec::with_capacity(group_sizes.len());
+        let mut offset = 0;
+        for &group_size in &group_sizes {
+            indptr.push(IdxPair::new(offset, offset + group_size));
+            offset += group_size;
+        }
+
+        let mut indices = vec![0usize; node_count];
+        let mut write_pos: Vec<usize> = indptr.iter().map(|p| p.a).collect();
+        for (node, &group) in groups.iter().enumerate() {
+            indices[write_pos[group]] = node;
+            write_pos[group] += 1;
+        }
+
+        CSR { indices, indptr }
+    }
+
+    /// Exports an adjacency CSR as an undirected Graphviz DOT graph.
+    ///
+    /// `label` names each node; nodes for which it returns `None` are omitted
+    /// along with their edges. Self entries and the symmetric duplicate of
+    /// each edge are skipped.
+    pub fn to_dot_labeled(&self, label: impl Fn(usize) -> Option<String>) -> String {
+        let labels: Vec<Option<String>> = (0..self.indptr.len()).map(label).collect();
+        let mut dot = String::from("graph {\n");
+
+        for (node, name) in labels.iter().enumerate() {
+            if let Some(name) = name {
+                dot.push_str(&format!("    {node} [label=\"{name}\"];\n"));
+            }
+        }
+
+        for (node, neighbors) in self.rows().enumerate() {
+            if labels[node].is_none() {
+                continue;
+            }
+            for &neighbor in neighbors {
+                if neighbor > node && labels[neighbor].is_some() {
+                    dot.push_str(&format!("    {node} -- {neighbor};\n"));
+                }
+            }
+        }
+
+        dot.push('}');
+        dot
+    }
+
     /// Prints adjacency info for debugging
     pub fn print_debug(&self) {
         for (node, range) in self.indptr.iter().enumerate() {
@@ -1,3 +1,6 @@
+use serde::de::Error as _;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
 #[derive(Debug)]
 pub struct IdxPair {
     pub a: usize,
@@ -24,6 +27,22 @@ enum HeapSlot<T> {
 
 pub struct Heap<T> {
     slots: Vec<HeapSlot<T>>,
+    live: usize, // number of initialized slots
+}
+
+impl<T: Clone> Clone for Heap<T> {
+    fn clone(&self) -> Self {
+        Heap {
+            slots: self.slots.clone(),
+            live: self.live,
+        }
+    }
+
+    // Reuse the existing slot allocation when copying from another heap
+    fn clone_from(&mut self, source: &Self) {
+        self.slots.clone_from(&source.slots);
+        self.live = source.live;
+    }
 }
 
 impl<T: Clone> Heap<T> {
@@ -31,6 +50,7 @@ impl<T: Clone> Heap<T> {
     pub fn with_capacity(capacity: usize) -> Self {
         Heap {
             slots: vec![HeapSlot::None; capacity],
+            live: 0,
         }
     }
 }
@@ -62,7 +82,9 @@ impl<T> Heap<T> {
 
     // Free one slot at index
     pub fn free(&mut self, slot: usize) {
-        self.slots[slot] = HeapSlot::None;
+        if let HeapSlot::Some(_) = std::mem::replace(&mut self.slots[slot], HeapSlot::None) {
+            self.live -= 1;
+        }
     }
 
     // Insert values into already allocated slots at start
@@ -79,12 +101,64 @@ impl<T> Heap<T> {
         for (slot, value) in self.slots[start..end].iter_mut().zip(values) {
             *slot = HeapSlot::Some(value);
         }
+        self.live += end - start;
     }
 
-    // Allocate slots and insert values immediately
-    pub fn insert_alloc_vec(&mut self, values: Vec<T>) {
+    // Allocate slots and insert values immediately; return start index
+    pub fn insert_alloc_vec(&mut self, values: Vec<T>) -> usize {
         let start = self.allocate_slots(values.len());
         self.insert_vec(start, values);
+        start
+    }
+
+    // Move all initialized values to the front, preserving order.
+    // Allocated-but-uninitialized slots are released. Returns the new index
+    // of each old index, or None for slots that held no value.
+    pub fn compact(&mut self) -> Vec<Option<usize>> {
+        let mut remap = vec![None; self.slots.len()];
+        let mut next = 0;
+
+        for (index, new_index) in remap.iter_mut().enumerate() {
+            match self.slots[index] {
+                HeapSlot::Some(_) => {
+                    self.slots.swap(next, index);
+                    *new_index = Some(next);
+                    next += 1;
+                }
+                HeapSlot::Allocated => self.slots[index] = HeapSlot::None,
+                HeapSlot::None => {}
+            }
+        }
+
+        remap
+    }
+
+    // Number of initialized values
+    pub fn len(&self) -> usize {
+        self.live
+    }
+
+    // True if no slot holds an initialized value
+    pub fn is_empty(&self) -> bool {
+        self.live == 0
+    }
+
+    // Total number of slots, whether free, allocated, or initialized
+    pub fn capacity(&self) -> usize {
+        self.slots.len()
+    }
+
+    // Number of free slots available for allocation
+    pub fn free_count(&self) -> usize {
+        self.slots
+            .iter()
+            .filter(|slot| matches!(slot, HeapSlot::None))
+            .count()
+    }
+
+    // Check whether the slot at index holds an initialized value
+    pub fn is_initialized(&self, index: usize) -> bool {
+        matches!(self.slots.get(index), Some(HeapSlot::Some(_)))
     }
 
     // Get immutable reference to value at index
@@ -111,6 +185,22 @@ impl<T> Heap<T> {
         }
     }
 
+    // Get immutable reference to value at index, or None if the slot holds no value
+    pub fn get_checked(&self, index: usize) -> Option<&T> {
+        match self.slots.get(index) {
+            Some(HeapSlot::Some(value)) => Some(value),
+            _ => None,
+        }
+    }
+
+    // Get mutable reference to value at index, or None if the slot holds no value
+    pub fn get_mut_checked(&mut self, index: usize) -> Option<&mut T> {
+        match self.slots.get_mut(index) {
+            Some(HeapSlot::Some(value)) => Some(value),
+            _ => None,
+        }
+    }
+
     // Get mutable references to two distinct values safely
     pub fn get_mut_pair(&mut self, a: usize, b: usize) -> (&mut T, &mut T) {
         assert_ne!(a, b, "Indices must be different");
@@ -162,6 +252,28 @@ impl<T> Heap<T> {
         })
     }
 
+    // Iterator over (original_index, &value)
+    pub fn iter_indexed(&self) -> impl Iterator<Item = (usize, &T)> + '_ {
+        self.slots
+            .iter()
+            .enumerate()
+            .filter_map(|(index, slot)| match slot {
+                HeapSlot::Some(value) => Some((index, value)),
+                _ => None,
+            })
+    }
+
+    // Mutable iterator over (original_index, &mut value)
+    pub fn iter_indexed_mut(&mut self) -> impl Iterator<Item = (usize, &mut T)> + '_ {
+        self.slots
+            .iter_mut()
+            .enumerate()
+            .filter_map(|(index, slot)| match slot {
+                HeapSlot::Some(value) => Some((index, value)),
+                _ => None,
+            })
+    }
+
     // Iterator over (original_index, flattened_index, &value)
     pub fn flatten_enumerate(&self) -> impl Iterator<Item = (usize, usize, &T)> + '_ {
         self.slots
@@ -177,3 +289,47 @@ impl<T> Heap<T> {
             })
     }
 }
+
+// Serialized form of a heap: the slot count plus each value with its index,
+// so indices held elsewhere stay valid after loading.
+#[derive(Serialize, Deserialize)]
+#[serde(rename = "Heap")]
+struct HeapData<T> {
+    capacity: usize,
+    values: Vec<(usize, T)>,
+}
+
+// Allocated-but-uninitialized slots hold nothing to save, so they load as free.
+impl<T: Serialize> Serialize for Heap<T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        HeapData {
+            capacity: self.capacity(),
+            values: self.iter_indexed().collect(),
+        }
+        .serialize(serializer)
+    }
+}
+
+// Rebuilds the exact slot layout, rejecting out-of-range or repeated indices
+impl<'de, T: Deserialize<'de>> Deserialize<'de> for Heap<T> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let data = HeapData::<T>::deserialize(deserializer)?;
+        let mut slots: Vec<HeapSlot<T>> = (0..data.capacity).map(|_| HeapSlot::None).collect();
+        let live = data.values.len();
+
+        for (index, value) in data.values {
+            match slots.get_mut(index) {
+                Some(slot @ HeapSlot::None) => *slot = HeapSlot::Some(value),
+                Some(_) => return Err(D::Error::custom(format!("duplicate heap index {index}"))),
+                None => {
+                    return Err(D::Error::custom(format!(
+                        "heap index {index} out of bounds for capacity {}",
+                        data.capacity
+                    )))
+                }
+            }
+        }
+
+        Ok(Heap { slots, live })
+    }
+}
@@ -0,0 +1,58 @@
+use super::vector::Vec2d;
+use std::collections::HashMap;
+
+/// Integer coordinates of a grid cell.
+type CellKey = (i64, i64);
+
+/// Buckets IDs by position on a uniform grid for fast proximity queries.
+///
+/// Points are keyed on the grid cell containing them, so a radius query only
+/// visits the cells overlapping the query's bounding square.
+#[derive(Debug, Clone)]
+pub struct SpatialHash<T> {
+    cell_size: f64,
+    buckets: HashMap<CellKey, Vec<(T, Vec2d)>>,
+}
+
+impl<T: Copy> SpatialHash<T> {
+    /// Creates an empty grid with square cells of side `cell_size`.
+    pub fn new(cell_size: f64) -> Self {
+        assert!(cell_size > 0.0, "SpatialHash cell size must be positive");
+        Self {
+            cell_size,
+            buckets: HashMap::new(),
+        }
+    }
+
+    /// Returns the grid cell containing `pos`. Points on an edge belong to the cell above/right of it.
+    fn key(&self, pos: Vec2d) -> CellKey {
+        (
+            (pos.x / self.cell_size).floor() as i64,
+            (pos.y / self.cell_size).floor() as i64,
+        )
+    }
+
+    /// Adds an ID at the given position.
+    pub fn insert(&mut self, id: T, pos: Vec2d) {
+        let key = self.key(pos);
+        self.buckets.entry(key).or_default().push((id, pos));
+    }
+
+    /// Removes all entries.
+    pub fn clear(&mut self) {
+        self.buckets.clear();
+    }
+
+    /// Returns the IDs of all entries within distance `r` of `center`, inclusive.
+    pub fn query_radius(&self, center: Vec2d, r: f64) -> impl Iterator<Item = T> + '_ {
+        let (min_x, min_y) = self.key(center - Vec2d::new(r, r));
+        let (max_x, max_y) = self.key(center + Vec2d::new(r, r));
+
+        (min_x..=max_x)
+            .flat_map(move |x| (min_y..=max_y).map(move |y| (x, y)))
+            .filter_map(|key| self.buckets.get(&key))
+            .flatten()
+            .filter(move |(_, pos)| pos.distance(center) <= r)
+            .map(|&(id, _)| id)
+    }
+}
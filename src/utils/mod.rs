@@ -1,3 +1,4 @@
 pub mod algorithms;
 pub mod data;
+pub mod grid;
 pub mod vector;
@@ -1,3 +1,4 @@
+use std::f64::consts::{PI, TAU};
 use std::ops::{Add, AddAssign, Div, Mul, Neg, Sub};
 
 #[derive(Debug, Clone, Copy, PartialEq)]
@@ -18,6 +19,10 @@ impl Vec2d {
         Self::new(a.cos(), a.sin())
     }
 
+    pub fn angle(self) -> f64 {
+        self.y.atan2(self.x)
+    }
+
     pub fn dot(self, other: Self) -> f64 {
         self.x * other.x + self.y * other.y
     }
@@ -42,6 +47,35 @@ impl Vec2d {
     pub fn distance(self, other: Self) -> f64 {
         (self - other).length()
     }
+
+    // True if neither component is NaN or infinite
+    pub fn is_finite(self) -> bool {
+        self.x.is_finite() && self.y.is_finite()
+    }
+
+    // Rotate counterclockwise by angle in radians
+    pub fn rotate(self, angle: f64) -> Self {
+        let (sin, cos) = angle.sin_cos();
+        Self::new(self.x * cos - self.y * sin, self.x * sin + self.y * cos)
+    }
+
+    // Narrow to glam's f32 Vec2 for rendering
+    pub fn to_glam(self) -> Vec2 {
+        Vec2::new(self.x as f32, self.y as f32)
+    }
+
+    pub fn from_array([x, y]: [f64; 2]) -> Self {
+        Self::new(x, y)
+    }
+
+    pub fn to_array(self) -> [f64; 2] {
+        [self.x, self.y]
+    }
+}
+
+// Wrap an angle in radians into [-PI, PI)
+pub fn wrap_angle(angle: f64) -> f64 {
+    (angle + PI).rem_euclid(TAU) - PI
 }
 
 // Operators for Vec2d: add, sub, mul (scalar), div (scalar), neg, add_assign
@@ -95,7 +129,7 @@ impl AddAssign for Vec2d {
     }
 }
 
-// Conversion from glam's Vec2 to your Vec2d
+// Conversions between glam's Vec2 and Vec2d
 
 use glam::Vec2;
 
@@ -107,3 +141,21 @@ impl From<Vec2> for Vec2d {
         }
     }
 }
+
+impl From<Vec2d> for Vec2 {
+    fn from(v: Vec2d) -> Self {
+        v.to_glam()
+    }
+}
+
+impl From<[f64; 2]> for Vec2d {
+    fn from(a: [f64; 2]) -> Self {
+        Self::from_array(a)
+    }
+}
+
+impl From<Vec2d> for [f64; 2] {
+    fn from(v: Vec2d) -> Self {
+        v.to_array()
+    }
+}